@@ -1,11 +1,13 @@
-use std::path::Path;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use async_std::io::WriteExt;
 use async_std::stream::StreamExt;
 use async_trait::async_trait;
 use core_types::events::{DownloadEvent, SyncEvent};
 use core_types::FileType;
-use flume::Sender;
+use flume::{Receiver, Sender};
 pub use s3::bucket::Bucket;
 use s3::creds::Credentials;
 use s3::error::S3Error;
@@ -16,7 +18,10 @@ use s3::serde_types::Part;
 pub use s3::bucket::Bucket as S3Bucket;
 
 mod ops;
-pub use ops::CloudStorageOps;
+pub use ops::{CloudStorageOps, RemoteObject};
+
+mod upload_state;
+pub use upload_state::MultipartUploadState;
 
 pub mod mock;
 
@@ -33,6 +38,70 @@ pub enum CloudStorageError {
 
     #[error("Invalid credentials: {0}")]
     InvalidCredentials(String),
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Bucket not found: {0}")]
+    BucketNotFound(String),
+
+    #[error("Endpoint unreachable: {0}")]
+    EndpointUnreachable(String),
+
+    #[error("Operation cancelled")]
+    Cancelled,
+}
+
+/// Minimum part size S3 allows for all but the last part of a multipart upload.
+pub const MIN_MULTIPART_CHUNK_SIZE: usize = 5 * 1024 * 1024;
+
+/// Configuration for multipart uploads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartConfig {
+    /// Size of each uploaded part in bytes. Must be at least [`MIN_MULTIPART_CHUNK_SIZE`].
+    pub chunk_size: usize,
+    /// How many times a failed part upload is retried before the upload is aborted.
+    pub max_retries: u32,
+    /// Delay before the first retry. The delay is doubled for each following retry.
+    pub retry_base_delay: Duration,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            chunk_size: MIN_MULTIPART_CHUNK_SIZE,
+            max_retries: 3,
+            retry_base_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl MultipartConfig {
+    /// Creates a validated configuration with the given chunk size.
+    pub fn with_chunk_size(chunk_size: usize) -> Result<Self, CloudStorageError> {
+        let config = Self {
+            chunk_size,
+            ..Default::default()
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), CloudStorageError> {
+        if self.chunk_size < MIN_MULTIPART_CHUNK_SIZE {
+            return Err(CloudStorageError::InvalidConfig(format!(
+                "Multipart chunk size {} is smaller than the minimum {} bytes",
+                self.chunk_size, MIN_MULTIPART_CHUNK_SIZE
+            )));
+        }
+        Ok(())
+    }
+
+    /// Delay before the given retry attempt, starting from 1.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        self.retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
 }
 
 /// Compute the S3 cloud key for a file given its type and archive file name.
@@ -45,6 +114,16 @@ pub fn cloud_key(file_type: FileType, archive_file_name: &str) -> String {
     format!("{}/{}", file_type.dir_name(), archive_file_name)
 }
 
+/// How the bucket is addressed in request URLs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BucketAddressing {
+    /// Bucket name in the path (`https://endpoint/bucket`), required by MinIO.
+    #[default]
+    PathStyle,
+    /// Bucket name in the host name (`https://bucket.endpoint`), required by e.g. AWS and Wasabi.
+    VirtualHosted,
+}
+
 /// Note, this doesn't actually establish a persistent connection,
 /// but prepares the Bucket object for further operations.
 pub fn prepare_bucket(
@@ -53,6 +132,7 @@ pub fn prepare_bucket(
     bucket: &str,
     key_id: &str,
     secret_key: &str,
+    addressing: BucketAddressing,
 ) -> Result<Box<Bucket>, CloudStorageError> {
     println!(
         "Preparing S3 bucket connection: endpoint={}, region={}, bucket={}",
@@ -66,9 +146,25 @@ pub fn prepare_bucket(
     let credentials = Credentials::new(Some(key_id), Some(secret_key), None, None, None)
         .map_err(|_| CloudStorageError::Other("Credentials error".to_string()))?;
 
-    let bucket = Bucket::new(bucket, region, credentials)?.with_path_style();
+    let bucket = Bucket::new(bucket, region, credentials)?;
+
+    match addressing {
+        BucketAddressing::PathStyle => Ok(bucket.with_path_style()),
+        BucketAddressing::VirtualHosted => Ok(bucket),
+    }
+}
 
-    Ok(bucket)
+/// Map an error from the connection check to an error telling whether the credentials, the
+/// bucket or the endpoint is wrong.
+fn connection_error(bucket_name: &str, error: S3Error) -> CloudStorageError {
+    match error {
+        S3Error::HttpFailWithBody(401 | 403, body) => CloudStorageError::InvalidCredentials(body),
+        S3Error::HttpFailWithBody(404, _) => {
+            CloudStorageError::BucketNotFound(bucket_name.to_string())
+        }
+        S3Error::HttpFailWithBody(..) => CloudStorageError::S3(error),
+        e => CloudStorageError::EndpointUnreachable(e.to_string()),
+    }
 }
 
 async fn upload_file(
@@ -82,6 +178,9 @@ async fn upload_file(
 }
 
 /// Download a file from the bucket to the specified local path.
+/// The file is downloaded to a `.part` file next to the local path (see
+/// [`partial_download_path`]) and renamed to the local path when complete. If the `.part` file of
+/// an interrupted download exists, only the remaining bytes are downloaded and appended to it.
 /// If a progress_tx channel is provided, send progress events during the download.
 /// Doesn't send progress events from failed download or write operation but instead returns an
 /// error immediately. The caller can handle the error and send any necessary events.
@@ -99,58 +198,462 @@ async fn download_file(
     key: &str,
     progress_tx: Option<&Sender<DownloadEvent>>,
 ) -> Result<(), CloudStorageError> {
-    let mut response_stream = bucket.get_object_stream(key).await?;
-    let mut file = async_std::fs::File::create(local_path).await?;
+    let part_path = partial_download_path(local_path);
+    let partial_size = async_std::fs::metadata(&part_path)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    if partial_size > 0 {
+        resume_download(bucket, &part_path, key, partial_size, progress_tx).await?;
+    } else {
+        let mut response_stream = bucket.get_object_stream(key).await?;
+        let mut file = async_std::fs::File::create(&part_path).await?;
+
+        while let Some(chunk_res) = response_stream.bytes.next().await {
+            let chunk = chunk_res?;
+            file.write_all(&chunk).await?;
+            if let Some(tx) = progress_tx {
+                tx.send(DownloadEvent::FileDownloadProgress {
+                    key: key.to_string(),
+                    bytes_downloaded: chunk.len() as u64,
+                })
+                .ok();
+            }
+        }
+        file.flush().await?;
+    }
+
+    async_std::fs::rename(&part_path, local_path).await?;
+    Ok(())
+}
+
+/// Path of the partial file an unfinished download is written to, e.g. `abc.zst.part`.
+pub fn partial_download_path(local_path: &Path) -> PathBuf {
+    let mut part_path = local_path.as_os_str().to_owned();
+    part_path.push(".part");
+    PathBuf::from(part_path)
+}
+
+/// Size of the ranges requested when resuming a partial download.
+const RESUME_RANGE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Ranged object download used for resuming partial downloads.
+#[async_trait]
+trait RangedGet: Sync {
+    /// Returns the response status code and body for the inclusive byte range.
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<(u16, Vec<u8>), S3Error>;
+}
+
+#[async_trait]
+impl RangedGet for Bucket {
+    async fn get_range(&self, key: &str, start: u64, end: u64) -> Result<(u16, Vec<u8>), S3Error> {
+        let response = self.get_object_range(key, start, Some(end)).await?;
+        Ok((response.status_code(), response.bytes().to_vec()))
+    }
+}
+
+/// Appends the rest of the object starting from `offset` to the partial local file with ranged
+/// requests. If the server ignores the range and returns the whole object, the local file is
+/// replaced with it. A range starting past the end of the object means that the partial file is
+/// either complete or not of this object, which can't be told apart, so the whole object is
+/// downloaded again.
+async fn resume_download<B: RangedGet + ?Sized>(
+    bucket: &B,
+    local_path: &Path,
+    key: &str,
+    offset: u64,
+    progress_tx: Option<&Sender<DownloadEvent>>,
+) -> Result<(), CloudStorageError> {
+    if let Some(tx) = progress_tx {
+        tx.send(DownloadEvent::FileDownloadResumed {
+            key: key.to_string(),
+            offset,
+        })
+        .ok();
+    }
+    let mut file = async_std::fs::OpenOptions::new()
+        .append(true)
+        .open(local_path)
+        .await?;
+    let mut offset = offset;
 
-    while let Some(chunk_res) = response_stream.bytes.next().await {
-        let chunk = chunk_res?;
-        file.write_all(&chunk).await?;
+    loop {
+        let (status_code, bytes) = match bucket
+            .get_range(key, offset, offset + RESUME_RANGE_SIZE - 1)
+            .await
+        {
+            Ok(response) => response,
+            Err(S3Error::HttpFailWithBody(416, _)) if offset > 0 => {
+                println!(
+                    "Partial file of {} doesn't continue the object, downloading the whole file",
+                    key
+                );
+                file = async_std::fs::File::create(local_path).await?;
+                offset = 0;
+                continue;
+            }
+            // Object is empty
+            Err(S3Error::HttpFailWithBody(416, _)) => break,
+            Err(e) => return Err(e.into()),
+        };
+        if status_code != 206 {
+            println!(
+                "Server ignored range request for {}, downloading the whole file",
+                key
+            );
+            file = async_std::fs::File::create(local_path).await?;
+        }
+        file.write_all(&bytes).await?;
         if let Some(tx) = progress_tx {
             tx.send(DownloadEvent::FileDownloadProgress {
                 key: key.to_string(),
-                bytes_downloaded: chunk.len() as u64,
+                bytes_downloaded: bytes.len() as u64,
             })
             .ok();
         }
+        if status_code != 206 || (bytes.len() as u64) < RESUME_RANGE_SIZE {
+            break;
+        }
+        offset += bytes.len() as u64;
     }
 
+    file.flush().await?;
     Ok(())
 }
 
+/// Upload a file in parts. Upload progress is persisted to a sidecar file (see
+/// [`MultipartUploadState`]) after each part, so that an interrupted upload can be continued with
+/// [`resume_multipart_upload`]. The sidecar is removed when the upload completes or is aborted.
+/// After a failure that may be temporary, e.g. a lost connection, the upload is kept in progress
+/// so that it can be resumed. Other failures abort it.
+///
+/// Upload stops with `CloudStorageError::Cancelled` if a cancel message is received before a
+/// part. A cancelled upload is kept in progress, so that it can be resumed.
 pub async fn multipart_upload(
     bucket: &Bucket,
     file_path: &Path,
     key: &str,
+    config: &MultipartConfig,
     progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
 ) -> Result<(), CloudStorageError> {
-    use async_std::io::ReadExt;
+    start_multipart_upload(bucket, file_path, key, config, progress_tx, cancel_rx).await
+}
 
-    let mut file = async_std::fs::File::open(file_path).await?;
-    // 5 MB chunk size
-    let mut buffer = vec![0u8; 5 * 1024 * 1024];
-    let mut part_number = 1;
-    let mut parts: Vec<Part> = Vec::new();
+async fn start_multipart_upload<B: MultipartBucket + ?Sized>(
+    bucket: &B,
+    file_path: &Path,
+    key: &str,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+) -> Result<(), CloudStorageError> {
+    config.validate()?;
+    let upload_id = bucket.initiate(key, content_type(key)).await?;
+    let state = MultipartUploadState::new(upload_id, config.chunk_size);
+    // Saved before the first part, so that the upload can be resumed even if no part completes
+    state.save(file_path).await?;
+    continue_multipart_upload(
+        bucket,
+        file_path,
+        key,
+        state,
+        config,
+        progress_tx,
+        cancel_rx,
+    )
+    .await
+}
 
-    let content_type = "application/zstd";
+/// Continue an interrupted multipart upload from the part following the completed parts of
+/// `state`. Part size of `config` must match the part size the upload was started with.
+///
+/// S3 API used doesn't support listing the parts of an upload, so the completed parts of the
+/// state (usually loaded from the sidecar with [`MultipartUploadState::load`]) are trusted. The
+/// upload is checked to be still in progress, since S3 may have expired it.
+pub async fn resume_multipart_upload(
+    bucket: &Bucket,
+    file_path: &Path,
+    key: &str,
+    state: MultipartUploadState,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+) -> Result<(), CloudStorageError> {
+    resume_saved_multipart_upload(
+        bucket,
+        file_path,
+        key,
+        state,
+        config,
+        progress_tx,
+        cancel_rx,
+    )
+    .await
+}
 
-    let response = bucket.initiate_multipart_upload(key, content_type).await?;
+async fn resume_saved_multipart_upload<B: MultipartBucket + ?Sized>(
+    bucket: &B,
+    file_path: &Path,
+    key: &str,
+    state: MultipartUploadState,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+) -> Result<(), CloudStorageError> {
+    config.validate()?;
+    if state.chunk_size != config.chunk_size {
+        return Err(CloudStorageError::InvalidConfig(format!(
+            "Upload was started with part size {}, but part size {} was given",
+            state.chunk_size, config.chunk_size
+        )));
+    }
+    if !bucket.is_in_progress(key, &state.upload_id).await? {
+        return Err(CloudStorageError::Other(format!(
+            "Multipart upload {} for {} is no longer in progress",
+            state.upload_id, key
+        )));
+    }
+    continue_multipart_upload(
+        bucket,
+        file_path,
+        key,
+        state,
+        config,
+        progress_tx,
+        cancel_rx,
+    )
+    .await
+}
+
+/// Content type of an uploaded object, derived from the extension of the key. The body of a
+/// `.zst` object is zstd compressed whatever the file type, so it and anything not recognized
+/// are uploaded as `application/zstd`.
+fn content_type(key: &str) -> &'static str {
+    let extension = Path::new(key)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("pdf") => "application/pdf",
+        _ => "application/zstd",
+    }
+}
+
+/// Multipart upload operations of a bucket.
+#[async_trait]
+trait MultipartBucket: Sync {
+    /// Initiates a multipart upload and returns the upload id.
+    async fn initiate(&self, key: &str, content_type: &str) -> Result<String, S3Error>;
+
+    async fn put_part(
+        &self,
+        chunk: Vec<u8>,
+        key: &str,
+        part_number: u32,
+        upload_id: &str,
+        content_type: &str,
+    ) -> Result<Part, S3Error>;
+
+    async fn abort(&self, key: &str, upload_id: &str) -> Result<(), S3Error>;
+
+    async fn complete(&self, key: &str, upload_id: &str, parts: Vec<Part>) -> Result<(), S3Error>;
+
+    /// Checks that the upload hasn't been completed, aborted or expired.
+    async fn is_in_progress(&self, key: &str, upload_id: &str) -> Result<bool, S3Error>;
+}
+
+#[async_trait]
+impl MultipartBucket for Bucket {
+    async fn initiate(&self, key: &str, content_type: &str) -> Result<String, S3Error> {
+        let response = self.initiate_multipart_upload(key, content_type).await?;
+        Ok(response.upload_id)
+    }
+
+    async fn put_part(
+        &self,
+        chunk: Vec<u8>,
+        key: &str,
+        part_number: u32,
+        upload_id: &str,
+        content_type: &str,
+    ) -> Result<Part, S3Error> {
+        self.put_multipart_chunk(chunk, key, part_number, upload_id, content_type)
+            .await
+    }
+
+    async fn abort(&self, key: &str, upload_id: &str) -> Result<(), S3Error> {
+        self.abort_upload(key, upload_id).await
+    }
+
+    async fn complete(&self, key: &str, upload_id: &str, parts: Vec<Part>) -> Result<(), S3Error> {
+        self.complete_multipart_upload(key, upload_id, parts)
+            .await?;
+        Ok(())
+    }
+
+    async fn is_in_progress(&self, key: &str, upload_id: &str) -> Result<bool, S3Error> {
+        Ok(self
+            .list_multiparts_uploads(Some(key), None)
+            .await?
+            .iter()
+            .flat_map(|result| &result.uploads)
+            .any(|upload| upload.id == upload_id && upload.key == key))
+    }
+}
+
+/// Uploads the file resuming the upload saved in its sidecar. A saved upload that can't be
+/// resumed is aborted before a new upload is started, so that its parts don't linger in the
+/// bucket. Transient failures are returned and the saved upload is kept for the next attempt.
+async fn upload_file_resuming<B: MultipartBucket + ?Sized>(
+    bucket: &B,
+    file_path: &Path,
+    key: &str,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+) -> Result<(), CloudStorageError> {
+    if let Some(state) = saved_upload_state(file_path).await {
+        let upload_id = state.upload_id.clone();
+        if state.chunk_size == config.chunk_size {
+            match resume_saved_multipart_upload(
+                bucket,
+                file_path,
+                key,
+                state,
+                config,
+                progress_tx,
+                cancel_rx,
+            )
+            .await
+            {
+                Ok(()) => return Ok(()),
+                Err(e) if matches!(e, CloudStorageError::Cancelled) || is_transient(&e) => {
+                    return Err(e);
+                }
+                Err(e) => eprintln!("Resuming upload failed, starting a new upload: {}", e),
+            }
+        } else {
+            eprintln!(
+                "Upload was started with part size {}, starting a new upload with part size {}",
+                state.chunk_size, config.chunk_size
+            );
+        }
+        bucket.abort(key, &upload_id).await.ok();
+        MultipartUploadState::remove(file_path).await?;
+    }
+    start_multipart_upload(bucket, file_path, key, config, progress_tx, cancel_rx).await
+}
+
+async fn continue_multipart_upload<B: MultipartBucket + ?Sized>(
+    bucket: &B,
+    file_path: &Path,
+    key: &str,
+    mut state: MultipartUploadState,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+) -> Result<(), CloudStorageError> {
+    let upload_id = state.upload_id.clone();
+    let content_type = content_type(key);
+    let result = upload_remaining_parts(
+        file_path,
+        key,
+        &mut state,
+        config,
+        progress_tx,
+        cancel_rx,
+        |chunk, part_number| bucket.put_part(chunk, key, part_number, &upload_id, content_type),
+    )
+    .await;
+
+    if let Err(e) = result {
+        // Upload and its state are kept for resuming when it was cancelled or the failure may be
+        // temporary
+        if !matches!(e, CloudStorageError::Cancelled) && !is_transient(&e) {
+            bucket.abort(key, &upload_id).await.ok();
+            MultipartUploadState::remove(file_path).await.ok();
+        }
+        return Err(e);
+    }
+    bucket
+        .complete(key, &upload_id, state.completed_parts)
+        .await?;
+    MultipartUploadState::remove(file_path).await?;
+    Ok(())
+}
+
+/// Connection failures and server side errors may succeed when the upload is resumed later.
+/// Rejected requests, e.g. an expired upload or missing permissions, and local file errors
+/// won't.
+fn is_transient(error: &CloudStorageError) -> bool {
+    match error {
+        CloudStorageError::S3(S3Error::HttpFailWithBody(status, _)) => {
+            matches!(status, 408 | 429 | 500..)
+        }
+        CloudStorageError::S3(_) | CloudStorageError::EndpointUnreachable(_) => true,
+        _ => false,
+    }
+}
+
+/// Loads the saved state of an interrupted upload of the file. A sidecar that can't be read is
+/// removed, so that the upload starts over instead of failing.
+async fn saved_upload_state(file_path: &Path) -> Option<MultipartUploadState> {
+    match MultipartUploadState::load(file_path).await {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!(
+                "Discarding unreadable upload state of {}: {}",
+                file_path.display(),
+                e
+            );
+            MultipartUploadState::remove(file_path).await.ok();
+            None
+        }
+    }
+}
+
+/// Uploads the parts of the file following the completed parts in `state` with `put_part`,
+/// saving the state to the sidecar file after each part. Returns `CloudStorageError::Cancelled`
+/// if a cancel message is received before a part.
+async fn upload_remaining_parts<F, Fut>(
+    file_path: &Path,
+    key: &str,
+    state: &mut MultipartUploadState,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+    mut put_part: F,
+) -> Result<(), CloudStorageError>
+where
+    F: FnMut(Vec<u8>, u32) -> Fut,
+    Fut: Future<Output = Result<Part, S3Error>>,
+{
+    use async_std::io::SeekExt;
+
+    let mut file = async_std::fs::File::open(file_path).await?;
+    file.seek(std::io::SeekFrom::Start(state.next_offset()))
+        .await?;
+    let mut buffer = vec![0u8; state.chunk_size];
 
     loop {
-        let bytes_read = file.read(&mut buffer).await?;
+        if cancel_rx.is_some_and(|rx| rx.try_recv().is_ok()) {
+            println!("Upload of {} cancelled", key);
+            return Err(CloudStorageError::Cancelled);
+        }
+        let bytes_read = read_chunk(&mut file, &mut buffer).await?;
         if bytes_read == 0 {
             break;
         }
 
+        let part_number = state.next_part_number();
         println!("Uploading part {} ({} bytes)", part_number, bytes_read);
-        let result = bucket
-            .put_multipart_chunk(
-                buffer[..bytes_read].to_vec(),
-                key,
-                part_number,
-                &response.upload_id,
-                content_type,
-            )
-            .await;
+        let result = upload_part_with_retry(key, part_number, config, progress_tx, || {
+            put_part(buffer[..bytes_read].to_vec(), part_number)
+        })
+        .await;
         println!("Finished part {} upload", part_number);
 
         match result {
@@ -163,8 +666,8 @@ pub async fn multipart_upload(
                     })
                     .ok();
                 }
-                parts.push(part);
-                part_number += 1;
+                state.completed_parts.push(part);
+                state.save(file_path).await?;
             }
             Err(e) => {
                 eprintln!("Error uploading part {}: {}", part_number, e);
@@ -175,28 +678,69 @@ pub async fn multipart_upload(
                     })
                     .ok();
                 }
-                bucket.abort_upload(key, &response.upload_id).await.ok();
                 return Err(CloudStorageError::S3(e));
             }
         };
     }
-    bucket
-        .complete_multipart_upload(key, &response.upload_id, parts)
-        .await?;
     Ok(())
 }
 
-#[cfg(test)]
-mod key_tests {
-    use super::*;
-    use core_types::FileType;
+/// Runs `put_part` until it succeeds or `config.max_retries` retries are used. Waits with
+/// exponential backoff between attempts and sends a `SyncEvent::PartRetrying` event before each
+/// retry. Returns the error of the last attempt if all attempts fail.
+async fn upload_part_with_retry<F, Fut>(
+    key: &str,
+    part_number: u32,
+    config: &MultipartConfig,
+    progress_tx: Option<&Sender<SyncEvent>>,
+    mut put_part: F,
+) -> Result<Part, S3Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Part, S3Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match put_part().await {
+            Ok(part) => return Ok(part),
+            Err(e) if attempt < config.max_retries => {
+                attempt += 1;
+                eprintln!(
+                    "Error uploading part {}, retrying (attempt {}): {}",
+                    part_number, attempt, e
+                );
+                if let Some(tx) = progress_tx {
+                    tx.send(SyncEvent::PartRetrying {
+                        key: key.to_string(),
+                        part: part_number,
+                        attempt,
+                    })
+                    .ok();
+                }
+                async_std::task::sleep(config.retry_delay(attempt)).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
 
-    #[test]
-    fn test_cloud_key_formats() {
-        assert_eq!(cloud_key(FileType::Rom, "abc.zst"), "rom/abc.zst");
-        // DiskImage must use underscore separator — NOT a space ("disk image/...")
-        assert_eq!(cloud_key(FileType::DiskImage, "def.zst"), "disk_image/def.zst");
+/// Reads until the buffer is full or the end of the file is reached, since S3 requires all
+/// parts except the last one to be at least the minimum part size.
+async fn read_chunk(
+    file: &mut async_std::fs::File,
+    buffer: &mut [u8],
+) -> Result<usize, CloudStorageError> {
+    use async_std::io::ReadExt;
+
+    let mut total_read = 0;
+    while total_read < buffer.len() {
+        let bytes_read = file.read(&mut buffer[total_read..]).await?;
+        if bytes_read == 0 {
+            break;
+        }
+        total_read += bytes_read;
     }
+    Ok(total_read)
 }
 
 pub async fn delete_file(bucket: &Bucket, key: &str) -> Result<(), CloudStorageError> {
@@ -216,8 +760,16 @@ impl S3CloudStorage {
         bucket_name: &str,
         key_id: &str,
         secret_key: &str,
+        addressing: BucketAddressing,
     ) -> Result<Self, CloudStorageError> {
-        let bucket = prepare_bucket(endpoint, region, bucket_name, key_id, secret_key)?;
+        let bucket = prepare_bucket(
+            endpoint,
+            region,
+            bucket_name,
+            key_id,
+            secret_key,
+            addressing,
+        )?;
         Ok(Self { bucket })
     }
 
@@ -233,19 +785,44 @@ impl CloudStorageOps for S3CloudStorage {
         &self,
         file_path: &Path,
         cloud_key: &str,
+        config: &MultipartConfig,
         progress_tx: Option<&Sender<SyncEvent>>,
+        cancel_rx: Option<&Receiver<()>>,
     ) -> Result<(), CloudStorageError> {
-        multipart_upload(&self.bucket, file_path, cloud_key, progress_tx).await
+        upload_file_resuming(
+            self.bucket.as_ref(),
+            file_path,
+            cloud_key,
+            config,
+            progress_tx,
+            cancel_rx,
+        )
+        .await
     }
 
     async fn delete_file(&self, cloud_key: &str) -> Result<(), CloudStorageError> {
         delete_file(&self.bucket, cloud_key).await
     }
 
-    async fn file_exists(&self, cloud_key: &str) -> Result<bool, CloudStorageError> {
+    async fn check_connection(&self) -> Result<(), CloudStorageError> {
+        self.bucket
+            .list_page(String::new(), None, None, None, Some(1))
+            .await
+            .map_err(|e| connection_error(&self.bucket.name(), e))?;
+        Ok(())
+    }
+
+    async fn object_metadata(
+        &self,
+        cloud_key: &str,
+    ) -> Result<Option<RemoteObject>, CloudStorageError> {
         match self.bucket.head_object(cloud_key).await {
-            Ok(_) => Ok(true),
-            Err(S3Error::HttpFailWithBody(404, _)) => Ok(false),
+            Ok((head, _)) => Ok(Some(RemoteObject {
+                key: cloud_key.to_string(),
+                size: head.content_length.unwrap_or(0).max(0) as u64,
+                etag: head.e_tag,
+            })),
+            Err(S3Error::HttpFailWithBody(404, _)) => Ok(None),
             Err(e) => Err(CloudStorageError::S3(e)),
         }
     }
@@ -259,6 +836,20 @@ impl CloudStorageOps for S3CloudStorage {
         download_file(&self.bucket, destination_path, cloud_key, progress_tx).await
     }
 
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<RemoteObject>, CloudStorageError> {
+        // Bucket::list follows continuation tokens and returns all result pages
+        let results = self.bucket.list(prefix.to_string(), None).await?;
+        Ok(results
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .map(|object| RemoteObject {
+                key: object.key,
+                size: object.size,
+                etag: object.e_tag,
+            })
+            .collect())
+    }
+
     async fn move_file(
         &self,
         source_cloud_key: &str,
@@ -280,3 +871,553 @@ impl CloudStorageOps for S3CloudStorage {
         }
     }
 }
+
+#[cfg(test)]
+mod key_tests {
+    use super::*;
+    use core_types::FileType;
+
+    #[test]
+    fn test_cloud_key_formats() {
+        assert_eq!(cloud_key(FileType::Rom, "abc.zst"), "rom/abc.zst");
+        // DiskImage must use underscore separator — NOT a space ("disk image/...")
+        assert_eq!(cloud_key(FileType::DiskImage, "def.zst"), "disk_image/def.zst");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepare_bucket_addressing() {
+        let prepare = |addressing| {
+            prepare_bucket(
+                "https://s3.example.com",
+                "eu-north-1",
+                "collection",
+                "key-id",
+                "secret",
+                addressing,
+            )
+            .unwrap()
+        };
+
+        let path_style = prepare(BucketAddressing::PathStyle);
+        assert!(path_style.is_path_style());
+        assert_eq!(path_style.url(), "https://s3.example.com/collection");
+
+        let virtual_hosted = prepare(BucketAddressing::VirtualHosted);
+        assert!(!virtual_hosted.is_path_style());
+        assert_eq!(virtual_hosted.url(), "https://collection.s3.example.com");
+    }
+
+    #[test]
+    fn test_multipart_config_rejects_chunk_size_below_minimum() {
+        assert!(matches!(
+            MultipartConfig::with_chunk_size(MIN_MULTIPART_CHUNK_SIZE - 1),
+            Err(CloudStorageError::InvalidConfig(_))
+        ));
+        assert_eq!(
+            MultipartConfig::with_chunk_size(16 * 1024 * 1024)
+                .unwrap()
+                .chunk_size,
+            16 * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_for_each_attempt() {
+        let config = MultipartConfig::default();
+        assert_eq!(config.retry_delay(1), Duration::from_secs(1));
+        assert_eq!(config.retry_delay(2), Duration::from_secs(2));
+        assert_eq!(config.retry_delay(3), Duration::from_secs(4));
+    }
+
+    fn retry_test_config(max_retries: u32) -> MultipartConfig {
+        MultipartConfig {
+            max_retries,
+            retry_base_delay: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    /// Simulates a bucket where the first `failures` part uploads fail.
+    fn flaky_put_part(
+        failures: u32,
+        attempts: &std::sync::atomic::AtomicU32,
+    ) -> impl FnMut() -> std::future::Ready<Result<Part, S3Error>> + '_ {
+        move || {
+            let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::future::ready(if attempt < failures {
+                Err(S3Error::HttpFailWithBody(500, "Internal error".to_string()))
+            } else {
+                Ok(Part {
+                    etag: "etag".to_string(),
+                    part_number: 1,
+                })
+            })
+        }
+    }
+
+    #[async_std::test]
+    async fn test_upload_part_with_retry_succeeds_after_failures() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let (tx, rx) = flume::unbounded();
+
+        let result = upload_part_with_retry(
+            "rom/game.zst",
+            1,
+            &retry_test_config(3),
+            Some(&tx),
+            flaky_put_part(2, &attempts),
+        )
+        .await;
+
+        assert_eq!(result.unwrap().etag, "etag");
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+        let retry_attempts: Vec<u32> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                SyncEvent::PartRetrying {
+                    part: 1, attempt, ..
+                } => Some(attempt),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(retry_attempts, vec![1, 2]);
+    }
+
+    #[async_std::test]
+    async fn test_upload_part_with_retry_fails_after_retries_are_exhausted() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+
+        let result = upload_part_with_retry(
+            "rom/game.zst",
+            1,
+            &retry_test_config(1),
+            None,
+            flaky_put_part(2, &attempts),
+        )
+        .await;
+
+        assert!(matches!(result, Err(S3Error::HttpFailWithBody(500, _))));
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[async_std::test]
+    async fn test_upload_remaining_parts_skips_completed_parts() {
+        let file_path =
+            std::env::temp_dir().join(format!("resume_upload_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, b"aaaabbbbcc")
+            .await
+            .unwrap();
+        let mut state = MultipartUploadState::new("upload-1", 4);
+        state.completed_parts.push(Part {
+            etag: "etag-1".to_string(),
+            part_number: 1,
+        });
+        let uploaded = std::sync::Mutex::new(Vec::new());
+        let (tx, rx) = flume::unbounded();
+
+        upload_remaining_parts(
+            &file_path,
+            "rom/game.zst",
+            &mut state,
+            &retry_test_config(0),
+            Some(&tx),
+            None,
+            |chunk, part_number| {
+                uploaded.lock().unwrap().push((part_number, chunk));
+                std::future::ready(Ok(Part {
+                    etag: format!("etag-{}", part_number),
+                    part_number,
+                }))
+            },
+        )
+        .await
+        .unwrap();
+
+        let saved_state = MultipartUploadState::load(&file_path).await.unwrap();
+        MultipartUploadState::remove(&file_path).await.unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+
+        assert_eq!(
+            uploaded.into_inner().unwrap(),
+            vec![(2, b"bbbb".to_vec()), (3, b"cc".to_vec())]
+        );
+        let part_events: Vec<u32> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                SyncEvent::PartUploaded { part, .. } => Some(part),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(part_events, vec![2, 3]);
+        let saved_state = saved_state.unwrap();
+        assert_eq!(saved_state.upload_id, "upload-1");
+        assert_eq!(saved_state.chunk_size, 4);
+        let saved_etags: Vec<&str> = saved_state
+            .completed_parts
+            .iter()
+            .map(|part| part.etag.as_str())
+            .collect();
+        assert_eq!(saved_etags, vec!["etag-1", "etag-2", "etag-3"]);
+        assert_eq!(state.completed_parts.len(), 3);
+    }
+
+    /// Records calls made to the bucket during a multipart upload.
+    #[derive(Default)]
+    struct RecordingBucket {
+        content_types: std::sync::Mutex<Vec<String>>,
+        /// Part number and HTTP status of a part upload that fails
+        failing_part: Option<(u32, u16)>,
+        uploaded_parts: std::sync::Mutex<Vec<u32>>,
+        aborted_uploads: std::sync::Mutex<Vec<String>>,
+        completed: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl MultipartBucket for RecordingBucket {
+        async fn initiate(&self, _key: &str, content_type: &str) -> Result<String, S3Error> {
+            self.content_types
+                .lock()
+                .unwrap()
+                .push(content_type.to_string());
+            Ok("upload-1".to_string())
+        }
+
+        async fn put_part(
+            &self,
+            _chunk: Vec<u8>,
+            _key: &str,
+            part_number: u32,
+            _upload_id: &str,
+            content_type: &str,
+        ) -> Result<Part, S3Error> {
+            self.content_types
+                .lock()
+                .unwrap()
+                .push(content_type.to_string());
+            if let Some((failing_part, status)) = self.failing_part
+                && failing_part == part_number
+            {
+                return Err(S3Error::HttpFailWithBody(status, "Part failed".to_string()));
+            }
+            self.uploaded_parts.lock().unwrap().push(part_number);
+            Ok(Part {
+                etag: format!("etag-{}", part_number),
+                part_number,
+            })
+        }
+
+        async fn abort(&self, _key: &str, upload_id: &str) -> Result<(), S3Error> {
+            self.aborted_uploads
+                .lock()
+                .unwrap()
+                .push(upload_id.to_string());
+            Ok(())
+        }
+
+        async fn complete(
+            &self,
+            _key: &str,
+            _upload_id: &str,
+            _parts: Vec<Part>,
+        ) -> Result<(), S3Error> {
+            self.completed
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn is_in_progress(&self, _key: &str, _upload_id: &str) -> Result<bool, S3Error> {
+            Ok(true)
+        }
+    }
+
+    #[async_std::test]
+    async fn test_cancelled_upload_is_kept_for_resuming() {
+        let file_path =
+            std::env::temp_dir().join(format!("cancel_upload_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, b"aaaabbbbcc")
+            .await
+            .unwrap();
+        let bucket = RecordingBucket::default();
+        let (cancel_tx, cancel_rx) = flume::unbounded();
+        cancel_tx.send(()).unwrap();
+
+        let result = start_multipart_upload(
+            &bucket,
+            &file_path,
+            "rom/game.zst",
+            &MultipartConfig::default(),
+            None,
+            Some(&cancel_rx),
+        )
+        .await;
+
+        let saved_state = MultipartUploadState::load(&file_path).await.unwrap();
+        MultipartUploadState::remove(&file_path).await.unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+
+        assert!(matches!(result, Err(CloudStorageError::Cancelled)));
+        assert!(bucket.uploaded_parts.lock().unwrap().is_empty());
+        assert!(bucket.aborted_uploads.lock().unwrap().is_empty());
+        assert!(!bucket.completed.load(std::sync::atomic::Ordering::SeqCst));
+        let saved_state = saved_state.unwrap();
+        assert_eq!(saved_state.upload_id, "upload-1");
+        assert!(saved_state.completed_parts.is_empty());
+    }
+
+    /// Uploads a three part file with the second part failing with the given status. Returns the
+    /// bucket and the state left in the sidecar.
+    async fn upload_with_failing_part(
+        status: u16,
+    ) -> (RecordingBucket, Option<MultipartUploadState>) {
+        let file_path =
+            std::env::temp_dir().join(format!("failing_upload_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, b"aaaabbbbcc")
+            .await
+            .unwrap();
+        let bucket = RecordingBucket {
+            failing_part: Some((2, status)),
+            ..Default::default()
+        };
+
+        let result = continue_multipart_upload(
+            &bucket,
+            &file_path,
+            "rom/game.zst",
+            MultipartUploadState::new("upload-1", 4),
+            &retry_test_config(0),
+            None,
+            None,
+        )
+        .await;
+
+        let saved_state = MultipartUploadState::load(&file_path).await.unwrap();
+        MultipartUploadState::remove(&file_path).await.unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+        assert!(matches!(
+            result,
+            Err(CloudStorageError::S3(S3Error::HttpFailWithBody(s, _))) if s == status
+        ));
+        (bucket, saved_state)
+    }
+
+    #[async_std::test]
+    async fn test_transient_failure_keeps_upload_for_resuming() {
+        let (bucket, saved_state) = upload_with_failing_part(503).await;
+
+        assert!(bucket.aborted_uploads.lock().unwrap().is_empty());
+        let saved_state = saved_state.unwrap();
+        assert_eq!(saved_state.upload_id, "upload-1");
+        assert_eq!(saved_state.completed_parts.len(), 1);
+    }
+
+    #[async_std::test]
+    async fn test_rejected_part_aborts_upload() {
+        let (bucket, saved_state) = upload_with_failing_part(403).await;
+
+        assert_eq!(*bucket.aborted_uploads.lock().unwrap(), vec!["upload-1"]);
+        assert!(saved_state.is_none());
+    }
+
+    #[async_std::test]
+    async fn test_saved_upload_with_other_part_size_is_aborted() {
+        let file_path =
+            std::env::temp_dir().join(format!("part_size_upload_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, b"aaaabbbbcc")
+            .await
+            .unwrap();
+        MultipartUploadState::new("old-upload", 4)
+            .save(&file_path)
+            .await
+            .unwrap();
+        let bucket = RecordingBucket::default();
+
+        let result = upload_file_resuming(
+            &bucket,
+            &file_path,
+            "rom/game.zst",
+            &MultipartConfig::default(),
+            None,
+            None,
+        )
+        .await;
+
+        let saved_state = MultipartUploadState::load(&file_path).await.unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!(*bucket.aborted_uploads.lock().unwrap(), vec!["old-upload"]);
+        assert!(bucket.completed.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(saved_state.is_none());
+    }
+
+    #[async_std::test]
+    async fn test_unreadable_upload_state_is_discarded() {
+        let file_path =
+            std::env::temp_dir().join(format!("corrupt_state_{}.zst", uuid::Uuid::new_v4()));
+        let sidecar_path = MultipartUploadState::sidecar_path(&file_path);
+        async_std::fs::write(&sidecar_path, "upload_id upload-1\nchunk_si")
+            .await
+            .unwrap();
+
+        let state = saved_upload_state(&file_path).await;
+
+        let sidecar_exists = sidecar_path.exists();
+        async_std::fs::remove_file(&sidecar_path).await.ok();
+        assert!(state.is_none());
+        assert!(!sidecar_exists);
+    }
+
+    async fn uploaded_content_types(key: &str) -> Vec<String> {
+        let file_path =
+            std::env::temp_dir().join(format!("content_type_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, b"content").await.unwrap();
+        let bucket = RecordingBucket::default();
+
+        let result = start_multipart_upload(
+            &bucket,
+            &file_path,
+            key,
+            &MultipartConfig::default(),
+            None,
+            None,
+        )
+        .await;
+        async_std::fs::remove_file(&file_path).await.unwrap();
+
+        assert!(result.is_ok());
+        assert!(bucket.completed.load(std::sync::atomic::Ordering::SeqCst));
+        bucket.content_types.into_inner().unwrap()
+    }
+
+    #[async_std::test]
+    async fn test_upload_content_type_is_derived_from_key_extension() {
+        assert_eq!(
+            uploaded_content_types("thumbnails/abc.png").await,
+            vec!["image/png", "image/png"]
+        );
+        assert_eq!(
+            uploaded_content_types(&cloud_key(FileType::Screenshot, "abc.zst")).await,
+            vec!["application/zstd", "application/zstd"]
+        );
+    }
+
+    #[test]
+    fn test_content_type_mapping() {
+        assert_eq!(content_type("cover_scan/abc.JPG"), "image/jpeg");
+        assert_eq!(content_type("cover_scan/abc.jpeg"), "image/jpeg");
+        assert_eq!(content_type("manual/abc.pdf"), "application/pdf");
+        assert_eq!(content_type("manual/abc.zst"), "application/zstd");
+        assert_eq!(content_type("no_extension"), "application/zstd");
+    }
+
+    /// Serves ranges of an object and records the requested range starts.
+    struct RangeServingBucket {
+        content: Vec<u8>,
+        supports_ranges: bool,
+        requested_starts: std::sync::Mutex<Vec<u64>>,
+    }
+
+    #[async_trait]
+    impl RangedGet for RangeServingBucket {
+        async fn get_range(
+            &self,
+            _key: &str,
+            start: u64,
+            end: u64,
+        ) -> Result<(u16, Vec<u8>), S3Error> {
+            self.requested_starts.lock().unwrap().push(start);
+            if !self.supports_ranges {
+                return Ok((200, self.content.clone()));
+            }
+            if start >= self.content.len() as u64 {
+                return Err(S3Error::HttpFailWithBody(
+                    416,
+                    "Range not satisfiable".into(),
+                ));
+            }
+            let end = (end as usize + 1).min(self.content.len());
+            Ok((206, self.content[start as usize..end].to_vec()))
+        }
+    }
+
+    async fn resume_from_partial_file(bucket: &RangeServingBucket, partial: &[u8]) -> Vec<u8> {
+        let file_path =
+            std::env::temp_dir().join(format!("resume_download_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, partial).await.unwrap();
+
+        resume_download(
+            bucket,
+            &file_path,
+            "rom/game.zst",
+            partial.len() as u64,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let content = async_std::fs::read(&file_path).await.unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+        content
+    }
+
+    #[async_std::test]
+    async fn test_resume_download_requests_only_remaining_bytes() {
+        let bucket = RangeServingBucket {
+            content: b"aaaabbbbcc".to_vec(),
+            supports_ranges: true,
+            requested_starts: std::sync::Mutex::new(Vec::new()),
+        };
+
+        let content = resume_from_partial_file(&bucket, b"aaaa").await;
+
+        assert_eq!(content, b"aaaabbbbcc");
+        assert_eq!(*bucket.requested_starts.lock().unwrap(), vec![4]);
+    }
+
+    #[async_std::test]
+    async fn test_resume_download_replaces_partial_file_when_range_is_ignored() {
+        let bucket = RangeServingBucket {
+            content: b"aaaabbbbcc".to_vec(),
+            supports_ranges: false,
+            requested_starts: std::sync::Mutex::new(Vec::new()),
+        };
+
+        let content = resume_from_partial_file(&bucket, b"aaaa").await;
+
+        assert_eq!(content, b"aaaabbbbcc");
+    }
+
+    #[async_std::test]
+    async fn test_resume_download_replaces_partial_file_past_end_of_object() {
+        let bucket = RangeServingBucket {
+            content: b"aaaabbbbcc".to_vec(),
+            supports_ranges: true,
+            requested_starts: std::sync::Mutex::new(Vec::new()),
+        };
+
+        let content = resume_from_partial_file(&bucket, b"a longer file of another object").await;
+
+        assert_eq!(content, b"aaaabbbbcc");
+        assert_eq!(*bucket.requested_starts.lock().unwrap(), vec![31, 0]);
+    }
+
+    #[test]
+    fn test_partial_download_path() {
+        assert_eq!(
+            partial_download_path(Path::new("/collection/rom/abc.zst")),
+            Path::new("/collection/rom/abc.zst.part")
+        );
+    }
+
+    #[test]
+    fn test_upload_state_sidecar_path() {
+        assert_eq!(
+            MultipartUploadState::sidecar_path(Path::new("/collection/rom/abc.zst")),
+            Path::new("/collection/rom/abc.zst.upload")
+        );
+    }
+}
@@ -1,12 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use core_types::events::{DownloadEvent, SyncEvent};
-use flume::Sender;
+use flume::{Receiver, Sender};
 
-use crate::{CloudStorageError, ops::CloudStorageOps};
+use s3::error::S3Error;
+
+use crate::{
+    CloudStorageError, MultipartConfig, connection_error,
+    ops::{CloudStorageOps, RemoteObject},
+    partial_download_path,
+};
 
 /// Internal state for MockCloudStorage.
 ///
@@ -21,14 +28,30 @@ struct MockState {
     fail_upload_keys: HashSet<String>,
     /// Keys that should fail on deletion
     fail_delete_keys: HashSet<String>,
+    /// Keys that should fail on download
+    fail_download_keys: HashSet<String>,
+    /// Keys of successfully downloaded files in download order
+    downloaded_files: Vec<String>,
     /// Number of parts to simulate in multipart upload (default: 3)
     simulate_part_count: u32,
+    /// Range starts requested when resuming downloads (cloud_key, offset)
+    requested_ranges: Vec<(String, u64)>,
+    /// How long each upload takes
+    upload_delay: Option<Duration>,
+    /// Number of uploads currently in progress
+    uploads_in_flight: usize,
+    /// Highest number of uploads that have been in progress at the same time
+    max_uploads_in_flight: usize,
+    /// Derive the simulated part count from file size and configured chunk size
+    parts_by_chunk_size: bool,
+    /// HTTP status the connection check fails with
+    connection_failure_status: Option<u16>,
 }
 
 /// Mock implementation of CloudStorageOps for testing
 ///
 /// This mock allows you to:
-/// - Simulate file uploads and deletions
+/// - Simulate file uploads, downloads and deletions
 /// - Test failure scenarios
 /// - Verify what operations were performed
 /// - Simulate progress events
@@ -80,18 +103,55 @@ impl MockCloudStorage {
         state.fail_delete_keys.insert(cloud_key.into());
     }
 
+    /// Make connection check fail as if the server responded with the given HTTP status
+    pub fn fail_connection_with_status(&self, status: u16) {
+        let mut state = self.state.lock().unwrap();
+        state.connection_failure_status = Some(status);
+    }
+
+    /// Make download fail for a specific key
+    pub fn fail_download_for(&self, cloud_key: impl Into<String>) {
+        let mut state = self.state.lock().unwrap();
+        state.fail_download_keys.insert(cloud_key.into());
+    }
+
     /// Set how many parts to simulate in multipart upload
     pub fn set_part_count(&self, count: u32) {
         let mut state = self.state.lock().unwrap();
         state.simulate_part_count = count;
     }
 
+    /// Simulate as many parts as a real multipart upload would use for the uploaded file with
+    /// the configured chunk size
+    pub fn simulate_parts_by_chunk_size(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.parts_by_chunk_size = true;
+    }
+
+    /// Make each upload take the given time, allowing concurrent uploads to overlap
+    pub fn set_upload_delay(&self, delay: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.upload_delay = Some(delay);
+    }
+
+    /// Get the highest number of uploads that have been in progress at the same time
+    pub fn max_uploads_in_flight(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.max_uploads_in_flight
+    }
+
     /// Check if a file was uploaded
     pub fn was_uploaded(&self, cloud_key: &str) -> bool {
         let state = self.state.lock().unwrap();
         state.uploaded_files.contains_key(cloud_key)
     }
 
+    /// Check if a file was downloaded
+    pub fn was_downloaded(&self, cloud_key: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        state.downloaded_files.iter().any(|key| key == cloud_key)
+    }
+
     /// Check if a file was deleted
     pub fn was_deleted(&self, cloud_key: &str) -> bool {
         let state = self.state.lock().unwrap();
@@ -104,12 +164,32 @@ impl MockCloudStorage {
         state.uploaded_files.get(cloud_key).cloned()
     }
 
+    /// Get the ranges requested when resuming partial downloads as (cloud_key, offset) pairs
+    pub fn get_requested_ranges(&self) -> Vec<(String, u64)> {
+        let state = self.state.lock().unwrap();
+        state.requested_ranges.clone()
+    }
+
     /// Get all uploaded file keys
     pub fn get_uploaded_keys(&self) -> Vec<String> {
         let state = self.state.lock().unwrap();
         state.uploaded_files.keys().cloned().collect()
     }
 
+    /// Get keys of all files currently stored, sorted
+    pub fn stored_keys(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let mut keys: Vec<String> = state.uploaded_files.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Get keys of downloaded files in download order
+    pub fn get_downloaded_keys(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state.downloaded_files.clone()
+    }
+
     /// Get all deleted file keys
     pub fn get_deleted_keys(&self) -> Vec<String> {
         let state = self.state.lock().unwrap();
@@ -144,8 +224,16 @@ impl CloudStorageOps for MockCloudStorage {
         &self,
         file_path: &Path,
         cloud_key: &str,
+        config: &MultipartConfig,
         progress_tx: Option<&Sender<SyncEvent>>,
+        cancel_rx: Option<&Receiver<()>>,
     ) -> Result<(), CloudStorageError> {
+        config.validate()?;
+
+        if cancel_rx.is_some_and(|rx| rx.try_recv().is_ok()) {
+            return Err(CloudStorageError::Cancelled);
+        }
+
         // Check if we should fail this upload
         let should_fail = {
             let state = self.state.lock().unwrap();
@@ -168,10 +256,30 @@ impl CloudStorageOps for MockCloudStorage {
             )));
         }
 
+        // Read the actual file content (or use dummy data if file doesn't exist)
+        // This allows testing without creating actual files
+        let content = async_std::fs::read(file_path)
+            .await
+            .unwrap_or_else(|_| format!("mock-content-for-{}", file_path.display()).into_bytes());
+
+        let upload_delay = {
+            let mut state = self.state.lock().unwrap();
+            state.uploads_in_flight += 1;
+            state.max_uploads_in_flight = state.max_uploads_in_flight.max(state.uploads_in_flight);
+            state.upload_delay
+        };
+        if let Some(delay) = upload_delay {
+            async_std::task::sleep(delay).await;
+        }
+
         // Simulate multipart upload progress
         let part_count = {
             let state = self.state.lock().unwrap();
-            state.simulate_part_count
+            if state.parts_by_chunk_size {
+                content.len().div_ceil(config.chunk_size).max(1) as u32
+            } else {
+                state.simulate_part_count
+            }
         };
 
         if let Some(tx) = progress_tx {
@@ -184,19 +292,25 @@ impl CloudStorageOps for MockCloudStorage {
             }
         }
 
-        // Read the actual file content (or use dummy data if file doesn't exist)
-        // This allows testing without creating actual files
-        let content = async_std::fs::read(file_path)
-            .await
-            .unwrap_or_else(|_| format!("mock-content-for-{}", file_path.display()).into_bytes());
-
         // Store the uploaded file
         let mut state = self.state.lock().unwrap();
         state.uploaded_files.insert(cloud_key.to_string(), content);
+        state.uploads_in_flight -= 1;
 
         Ok(())
     }
 
+    async fn check_connection(&self) -> Result<(), CloudStorageError> {
+        let state = self.state.lock().unwrap();
+        match state.connection_failure_status {
+            Some(status) => Err(connection_error(
+                "mock-bucket",
+                S3Error::HttpFailWithBody(status, "Mock connection failure".to_string()),
+            )),
+            None => Ok(()),
+        }
+    }
+
     async fn delete_file(&self, cloud_key: &str) -> Result<(), CloudStorageError> {
         let mut state = self.state.lock().unwrap();
 
@@ -217,31 +331,111 @@ impl CloudStorageOps for MockCloudStorage {
         Ok(())
     }
 
-    async fn file_exists(&self, cloud_key: &str) -> Result<bool, CloudStorageError> {
+    async fn object_metadata(
+        &self,
+        cloud_key: &str,
+    ) -> Result<Option<RemoteObject>, CloudStorageError> {
         let state = self.state.lock().unwrap();
         // A file exists if it's in uploaded_files and not in deleted_files
-        let is_uploaded = state.uploaded_files.contains_key(cloud_key);
-        let is_deleted = state.deleted_files.contains(cloud_key);
-
-        Ok(is_uploaded && !is_deleted)
+        if state.deleted_files.contains(cloud_key) {
+            return Ok(None);
+        }
+        Ok(state
+            .uploaded_files
+            .get(cloud_key)
+            .map(|content| RemoteObject {
+                key: cloud_key.to_string(),
+                size: content.len() as u64,
+                etag: None,
+            }))
     }
 
-    // TODO: simulate download progress events
+    /// Doesn't write the downloaded file unless the `.part` file of an interrupted download exists
+    /// next to the destination path. In that case the remaining content is appended to it and it's
+    /// renamed to the destination path, simulating a resumed download.
+    /// Downloaded bytes are reported with a single `FileDownloadProgress` event.
     async fn download_file(
         &self,
         cloud_key: &str,
-        _destination_path: &Path,
-        _progress_tx: Option<&Sender<DownloadEvent>>,
+        destination_path: &Path,
+        progress_tx: Option<&Sender<DownloadEvent>>,
     ) -> Result<(), CloudStorageError> {
-        let state = self.state.lock().unwrap();
-        if state.uploaded_files.contains_key(cloud_key) {
-            Ok(())
-        } else {
-            Err(CloudStorageError::Other(format!(
+        let (content, should_fail) = {
+            let state = self.state.lock().unwrap();
+            (
+                state.uploaded_files.get(cloud_key).cloned(),
+                state.fail_download_keys.contains(cloud_key),
+            )
+        };
+        if should_fail {
+            return Err(CloudStorageError::Other(format!(
+                "Mock download failure for key: {}",
+                cloud_key
+            )));
+        }
+        let Some(content) = content else {
+            return Err(CloudStorageError::Other(format!(
                 "Mock download failed, key not found: {}",
                 cloud_key
-            )))
+            )));
+        };
+
+        let mut offset = 0;
+        let part_path = partial_download_path(destination_path);
+        if let Ok(metadata) = async_std::fs::metadata(&part_path).await {
+            offset = metadata.len().min(content.len() as u64);
+            if let Some(tx) = progress_tx {
+                tx.send(DownloadEvent::FileDownloadResumed {
+                    key: cloud_key.to_string(),
+                    offset,
+                })
+                .ok();
+            }
+            self.state
+                .lock()
+                .unwrap()
+                .requested_ranges
+                .push((cloud_key.to_string(), offset));
+
+            use async_std::io::WriteExt;
+            let mut file = async_std::fs::OpenOptions::new()
+                .append(true)
+                .open(&part_path)
+                .await?;
+            file.write_all(&content[offset as usize..]).await?;
+            file.flush().await?;
+            async_std::fs::rename(&part_path, destination_path).await?;
         }
+
+        if let Some(tx) = progress_tx {
+            tx.send(DownloadEvent::FileDownloadProgress {
+                key: cloud_key.to_string(),
+                bytes_downloaded: content.len() as u64 - offset,
+            })
+            .ok();
+        }
+        self.state
+            .lock()
+            .unwrap()
+            .downloaded_files
+            .push(cloud_key.to_string());
+        Ok(())
+    }
+
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<RemoteObject>, CloudStorageError> {
+        let state = self.state.lock().unwrap();
+        let mut objects: Vec<RemoteObject> = state
+            .uploaded_files
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, content)| RemoteObject {
+                key: key.clone(),
+                size: content.len() as u64,
+                etag: None,
+            })
+            .collect();
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
     }
 
     async fn move_file(
@@ -273,21 +467,104 @@ mod tests {
     async fn test_mock_upload() {
         let mock = MockCloudStorage::new();
 
-        mock.upload_file(Path::new("/test/file.zst"), "rom/game.zst", None)
-            .await
-            .unwrap();
+        mock.upload_file(
+            Path::new("/test/file.zst"),
+            "rom/game.zst",
+            &MultipartConfig::default(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
 
         assert!(mock.was_uploaded("rom/game.zst"));
         assert_eq!(mock.uploaded_count(), 1);
     }
 
+    #[async_std::test]
+    async fn test_mock_upload_two_files() {
+        let mock = MockCloudStorage::new();
+
+        for (file_path, cloud_key) in [
+            ("/test/game1.zst", "rom/game1.zst"),
+            ("/test/game2.zst", "rom/game2.zst"),
+        ] {
+            mock.upload_file(
+                Path::new(file_path),
+                cloud_key,
+                &MultipartConfig::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        assert!(mock.was_uploaded("rom/game1.zst"));
+        assert!(mock.was_uploaded("rom/game2.zst"));
+        assert_eq!(mock.stored_keys(), vec!["rom/game1.zst", "rom/game2.zst"]);
+    }
+
+    #[async_std::test]
+    async fn test_mock_download() {
+        let mock = MockCloudStorage::new();
+        mock.add_file("rom/game.zst", vec![0u8; 10]);
+        mock.add_file_dummy("rom/broken.zst");
+        mock.fail_download_for("rom/broken.zst");
+        let destination = std::env::temp_dir().join(format!("{}.zst", uuid::Uuid::new_v4()));
+        let (tx, rx) = flume::unbounded();
+
+        mock.download_file("rom/game.zst", &destination, Some(&tx))
+            .await
+            .unwrap();
+        let result = mock
+            .download_file("rom/broken.zst", &destination, Some(&tx))
+            .await;
+
+        assert!(result.is_err());
+        assert!(mock.was_downloaded("rom/game.zst"));
+        assert!(!mock.was_downloaded("rom/broken.zst"));
+        let events: Vec<DownloadEvent> = rx.try_iter().collect();
+        assert!(matches!(
+            events.as_slice(),
+            [DownloadEvent::FileDownloadProgress {
+                bytes_downloaded: 10,
+                ..
+            }]
+        ));
+    }
+
+    #[async_std::test]
+    async fn test_check_connection() {
+        let mock = MockCloudStorage::new();
+        assert!(mock.check_connection().await.is_ok());
+
+        mock.fail_connection_with_status(403);
+        assert!(matches!(
+            mock.check_connection().await,
+            Err(CloudStorageError::InvalidCredentials(_))
+        ));
+
+        mock.fail_connection_with_status(404);
+        assert!(matches!(
+            mock.check_connection().await,
+            Err(CloudStorageError::BucketNotFound(_))
+        ));
+    }
+
     #[async_std::test]
     async fn test_mock_upload_failure() {
         let mock = MockCloudStorage::new();
         mock.fail_upload_for("rom/game.zst");
 
         let result = mock
-            .upload_file(Path::new("/test/file.zst"), "rom/game.zst", None)
+            .upload_file(
+                Path::new("/test/file.zst"),
+                "rom/game.zst",
+                &MultipartConfig::default(),
+                None,
+                None,
+            )
             .await;
 
         assert!(result.is_err());
@@ -334,6 +611,23 @@ mod tests {
         assert!(!mock.file_exists("rom/game.zst").await.unwrap());
     }
 
+    #[async_std::test]
+    async fn test_object_metadata() {
+        let mock = MockCloudStorage::new();
+        mock.add_file("rom/game.zst", vec![0u8; 42]);
+
+        let metadata = mock.object_metadata("rom/game.zst").await.unwrap();
+        assert_eq!(
+            metadata,
+            Some(RemoteObject {
+                key: "rom/game.zst".to_string(),
+                size: 42,
+                etag: None,
+            })
+        );
+        assert_eq!(mock.object_metadata("rom/missing.zst").await.unwrap(), None);
+    }
+
     #[async_std::test]
     async fn test_upload_with_progress_events() {
         let mock = MockCloudStorage::new();
@@ -341,9 +635,15 @@ mod tests {
 
         let (tx, rx) = flume::unbounded();
 
-        mock.upload_file(Path::new("/test/file.zst"), "rom/game.zst", Some(&tx))
-            .await
-            .unwrap();
+        mock.upload_file(
+            Path::new("/test/file.zst"),
+            "rom/game.zst",
+            &MultipartConfig::default(),
+            Some(&tx),
+            None,
+        )
+        .await
+        .unwrap();
 
         // Count part uploaded events
         let mut part_count = 0;
@@ -357,6 +657,35 @@ mod tests {
         assert!(mock.was_uploaded("rom/game.zst"));
     }
 
+    #[async_std::test]
+    async fn test_upload_part_count_matches_chunk_size() {
+        let mock = MockCloudStorage::new();
+        mock.simulate_parts_by_chunk_size();
+        // 6 MiB parts, which differs from the default simulated part count of 3
+        let config = MultipartConfig::with_chunk_size(6 * 1024 * 1024).unwrap();
+        let file_path =
+            std::env::temp_dir().join(format!("mock_upload_{}.zst", uuid::Uuid::new_v4()));
+        // Four full parts and one byte in the fifth part
+        async_std::fs::write(&file_path, vec![0u8; 24 * 1024 * 1024 + 1])
+            .await
+            .unwrap();
+        let (tx, rx) = flume::unbounded();
+
+        mock.upload_file(&file_path, "rom/game.zst", &config, Some(&tx), None)
+            .await
+            .unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+
+        let parts: Vec<u32> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                SyncEvent::PartUploaded { part, .. } => Some(part),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(parts, vec![1, 2, 3, 4, 5]);
+    }
+
     #[async_std::test]
     async fn test_clear() {
         let mock = MockCloudStorage::new();
@@ -374,6 +703,83 @@ mod tests {
         assert_eq!(mock.deleted_count(), 0);
     }
 
+    #[async_std::test]
+    async fn test_list_objects_filters_by_prefix() {
+        let mock = MockCloudStorage::new();
+        mock.add_file("rom/game1.zst", vec![0; 10]);
+        mock.add_file("rom/game2.zst", vec![0; 20]);
+        mock.add_file("disk_image/disk.zst", vec![0; 30]);
+
+        let roms = mock.list_objects("rom/").await.unwrap();
+        let all = mock.list_objects("").await.unwrap();
+
+        assert_eq!(
+            roms,
+            vec![
+                RemoteObject {
+                    key: "rom/game1.zst".to_string(),
+                    size: 10,
+                    etag: None,
+                },
+                RemoteObject {
+                    key: "rom/game2.zst".to_string(),
+                    size: 20,
+                    etag: None,
+                },
+            ]
+        );
+        assert_eq!(all.len(), 3);
+    }
+
+    #[async_std::test]
+    async fn test_download_resumes_partial_file() {
+        let mock = MockCloudStorage::new();
+        mock.add_file("rom/game.zst", b"aaaabbbbcc".to_vec());
+        let file_path =
+            std::env::temp_dir().join(format!("mock_download_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(partial_download_path(&file_path), b"aaaa")
+            .await
+            .unwrap();
+        let (tx, rx) = flume::unbounded();
+
+        mock.download_file("rom/game.zst", &file_path, Some(&tx))
+            .await
+            .unwrap();
+        let content = async_std::fs::read(&file_path).await.unwrap();
+        let part_exists = partial_download_path(&file_path).exists();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+
+        assert_eq!(content, b"aaaabbbbcc");
+        assert!(!part_exists);
+        assert_eq!(
+            mock.get_requested_ranges(),
+            vec![("rom/game.zst".to_string(), 4)]
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            DownloadEvent::FileDownloadResumed {
+                key: "rom/game.zst".to_string(),
+                offset: 4,
+            }
+        );
+    }
+
+    #[async_std::test]
+    async fn test_download_does_not_resume_existing_file() {
+        let mock = MockCloudStorage::new();
+        mock.add_file("rom/game.zst", b"aaaabbbbcc".to_vec());
+        let file_path =
+            std::env::temp_dir().join(format!("mock_download_{}.zst", uuid::Uuid::new_v4()));
+        async_std::fs::write(&file_path, b"aaaa").await.unwrap();
+
+        mock.download_file("rom/game.zst", &file_path, None)
+            .await
+            .unwrap();
+        async_std::fs::remove_file(&file_path).await.unwrap();
+
+        assert!(mock.get_requested_ranges().is_empty());
+    }
+
     #[async_std::test]
     async fn test_move_file() {
         let mock = MockCloudStorage::new();
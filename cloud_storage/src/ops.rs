@@ -2,29 +2,56 @@ use std::path::Path;
 
 use async_trait::async_trait;
 use core_types::events::{DownloadEvent, SyncEvent};
-use flume::Sender;
+use flume::{Receiver, Sender};
 
-use crate::CloudStorageError;
+use crate::{CloudStorageError, MultipartConfig};
+
+/// Object stored in cloud storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteObject {
+    pub key: String,
+    pub size: u64,
+    pub etag: Option<String>,
+}
 
 /// Trait for cloud storage operations to enable testing
 #[async_trait]
 pub trait CloudStorageOps: Send + Sync {
     /// Upload a file to cloud storage
     ///
-    /// The implementation handles multipart upload logic internally.
-    /// Progress events are sent through the optional progress_tx channel.
+    /// The implementation handles multipart upload logic internally using the part size from
+    /// `config`. Progress events are sent through the optional progress_tx channel. Upload stops
+    /// with `CloudStorageError::Cancelled` when a message is received from cancel_rx, and is
+    /// resumed by the next upload of the file.
     async fn upload_file(
         &self,
         file_path: &Path,
         cloud_key: &str,
+        config: &MultipartConfig,
         progress_tx: Option<&Sender<SyncEvent>>,
+        cancel_rx: Option<&Receiver<()>>,
     ) -> Result<(), CloudStorageError>;
 
+    /// Check that the bucket can be accessed with the configured endpoint and credentials.
+    ///
+    /// Returns `CloudStorageError::InvalidCredentials` if the credentials are rejected,
+    /// `CloudStorageError::BucketNotFound` if the bucket doesn't exist and
+    /// `CloudStorageError::EndpointUnreachable` if the endpoint can't be reached.
+    async fn check_connection(&self) -> Result<(), CloudStorageError>;
+
     /// Delete a file from cloud storage
     async fn delete_file(&self, cloud_key: &str) -> Result<(), CloudStorageError>;
 
     /// Check if a file exists in cloud storage
-    async fn file_exists(&self, cloud_key: &str) -> Result<bool, CloudStorageError>;
+    async fn file_exists(&self, cloud_key: &str) -> Result<bool, CloudStorageError> {
+        Ok(self.object_metadata(cloud_key).await?.is_some())
+    }
+
+    /// Get size and ETag of a file in cloud storage. Returns `None` if the file doesn't exist.
+    async fn object_metadata(
+        &self,
+        cloud_key: &str,
+    ) -> Result<Option<RemoteObject>, CloudStorageError>;
 
     /// Download a file from cloud storage to the specified destination path
     async fn download_file(
@@ -34,6 +61,9 @@ pub trait CloudStorageOps: Send + Sync {
         progress_tx: Option<&Sender<DownloadEvent>>,
     ) -> Result<(), CloudStorageError>;
 
+    /// List all objects whose key starts with the given prefix. Empty prefix lists all objects.
+    async fn list_objects(&self, prefix: &str) -> Result<Vec<RemoteObject>, CloudStorageError>;
+
     /// Move (rename) a file within cloud storage
     async fn move_file(
         &self,
@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use s3::serde_types::Part;
+
+use crate::CloudStorageError;
+
+/// Progress of a multipart upload, persisted to a sidecar file next to the uploaded file so that
+/// an interrupted upload can be resumed.
+///
+/// Sidecar file format is line based:
+/// ```text
+/// upload_id <upload id>
+/// chunk_size <part size in bytes>
+/// part <part number> <etag>
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultipartUploadState {
+    pub upload_id: String,
+    /// Part size used for the upload. Resumed upload must use the same part size.
+    pub chunk_size: usize,
+    /// Completed parts in part number order.
+    pub completed_parts: Vec<Part>,
+}
+
+impl MultipartUploadState {
+    pub fn new(upload_id: impl Into<String>, chunk_size: usize) -> Self {
+        Self {
+            upload_id: upload_id.into(),
+            chunk_size,
+            completed_parts: Vec::new(),
+        }
+    }
+
+    /// Path of the sidecar file for the given uploaded file.
+    pub fn sidecar_path(file_path: &Path) -> PathBuf {
+        let mut sidecar = file_path.as_os_str().to_owned();
+        sidecar.push(".upload");
+        PathBuf::from(sidecar)
+    }
+
+    /// Loads the state from the sidecar of the given file. Returns `None` if there is no sidecar.
+    pub async fn load(file_path: &Path) -> Result<Option<Self>, CloudStorageError> {
+        let sidecar_path = Self::sidecar_path(file_path);
+        if !async_std::path::Path::new(&sidecar_path).exists().await {
+            return Ok(None);
+        }
+        let content = async_std::fs::read_to_string(&sidecar_path).await?;
+        Self::parse(&content).map(Some)
+    }
+
+    /// Saves the state to the sidecar of the given file. The state is written to a temporary file
+    /// first and renamed over the sidecar, so that an interruption can't leave a truncated sidecar.
+    pub async fn save(&self, file_path: &Path) -> Result<(), CloudStorageError> {
+        let sidecar_path = Self::sidecar_path(file_path);
+        let mut temp_path = sidecar_path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        async_std::fs::write(&temp_path, self.serialize()).await?;
+        async_std::fs::rename(&temp_path, &sidecar_path).await?;
+        Ok(())
+    }
+
+    pub async fn remove(file_path: &Path) -> Result<(), CloudStorageError> {
+        let sidecar_path = Self::sidecar_path(file_path);
+        if async_std::path::Path::new(&sidecar_path).exists().await {
+            async_std::fs::remove_file(sidecar_path).await?;
+        }
+        Ok(())
+    }
+
+    /// Byte offset in the uploaded file where the next part starts.
+    pub fn next_offset(&self) -> u64 {
+        (self.completed_parts.len() * self.chunk_size) as u64
+    }
+
+    /// Part number of the next part to upload.
+    pub fn next_part_number(&self) -> u32 {
+        self.completed_parts.len() as u32 + 1
+    }
+
+    fn serialize(&self) -> String {
+        let mut content = format!(
+            "upload_id {}\nchunk_size {}\n",
+            self.upload_id, self.chunk_size
+        );
+        for part in &self.completed_parts {
+            content.push_str(&format!("part {} {}\n", part.part_number, part.etag));
+        }
+        content
+    }
+
+    fn parse(content: &str) -> Result<Self, CloudStorageError> {
+        let invalid =
+            |line: &str| CloudStorageError::Other(format!("Invalid upload state line: {}", line));
+        let mut upload_id = None;
+        let mut chunk_size = None;
+        let mut completed_parts = Vec::new();
+
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let mut fields = line.splitn(3, ' ');
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some("upload_id"), Some(id), None) => upload_id = Some(id.to_string()),
+                (Some("chunk_size"), Some(size), None) => {
+                    chunk_size = Some(size.parse().map_err(|_| invalid(line))?)
+                }
+                (Some("part"), Some(part_number), Some(etag)) => {
+                    let part_number: u32 = part_number.parse().map_err(|_| invalid(line))?;
+                    if part_number as usize != completed_parts.len() + 1 {
+                        return Err(invalid(line));
+                    }
+                    completed_parts.push(Part {
+                        part_number,
+                        etag: etag.to_string(),
+                    });
+                }
+                _ => return Err(invalid(line)),
+            }
+        }
+
+        match (upload_id, chunk_size) {
+            (Some(upload_id), Some(chunk_size)) => Ok(Self {
+                upload_id,
+                chunk_size,
+                completed_parts,
+            }),
+            _ => Err(CloudStorageError::Other(
+                "Upload state is missing upload_id or chunk_size".to_string(),
+            )),
+        }
+    }
+}
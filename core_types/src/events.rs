@@ -1,9 +1,15 @@
 use std::path::PathBuf;
 
+use crate::Sha1Checksum;
+
 #[derive(Debug, Clone)]
 pub enum HttpDownloadEvent {
     Started { total_size: Option<u64> },
+    Resumed { url: String, offset: u64 },
     Progress { bytes_downloaded: u64 },
+    Verified,
+    Retrying { url: String, attempt: u32 },
+    MirrorFailed { url: String, error: String },
     Completed { file_path: PathBuf },
     Failed { error: String },
 }
@@ -28,6 +34,12 @@ pub enum SyncEvent {
         key: String,
         error: String,
     },
+    /// Part upload failed and is retried. `attempt` is the number of the retry, starting from 1.
+    PartRetrying {
+        key: String,
+        part: u32,
+        attempt: u32,
+    },
     FileUploadCompleted {
         key: String,
         file_number: i64,
@@ -68,7 +80,88 @@ pub enum DownloadEvent {
     DownloadStarted { number_of_files: usize },
     FileDownloadStarted { key: String },
     FileDownloadProgress { key: String, bytes_downloaded: u64 },
+    FileDownloadResumed { key: String, offset: u64 },
     FileDownloadCompleted { key: String },
     FileDownloadFailed { key: String, error: String },
     DownloadCompleted,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeletionEvent {
+    StepStarted {
+        step: String,
+    },
+    StepCompleted {
+        step: String,
+    },
+    StepSkipped {
+        step: String,
+    },
+    /// Sent by the file-deleting steps for each processed file.
+    FileProgress {
+        file_name: String,
+        file_number: usize,
+        total_files: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportEvent {
+    FileStarted {
+        name: String,
+    },
+    /// Total bytes of the file processed so far.
+    BytesProcessed {
+        name: String,
+        bytes: u64,
+    },
+    FileCompleted {
+        name: String,
+        sha1: Sha1Checksum,
+    },
+    /// Sent once after all files are processed. Counts only the files written to the collection,
+    /// `total_bytes` is their size before compression.
+    ImportCompleted {
+        total_files: usize,
+        total_bytes: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportEvent {
+    /// Sent after each exported file, `index` starts from 1.
+    FileExported {
+        name: String,
+        index: usize,
+        total: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_event_variants() {
+        let events = [
+            ImportEvent::FileStarted {
+                name: "game.bin".to_string(),
+            },
+            ImportEvent::BytesProcessed {
+                name: "game.bin".to_string(),
+                bytes: 1024,
+            },
+            ImportEvent::FileCompleted {
+                name: "game.bin".to_string(),
+                sha1: [0; 20],
+            },
+            ImportEvent::ImportCompleted {
+                total_files: 1,
+                total_bytes: 1024,
+            },
+        ];
+        let cloned = events.clone();
+        assert_eq!(events, cloned);
+        assert!(format!("{:?}", events[3]).contains("total_bytes: 1024"));
+    }
+}
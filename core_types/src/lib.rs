@@ -1,20 +1,53 @@
 pub mod events;
 pub mod item_type;
 
-use hex::FromHex;
 use serde::{Deserialize, Serialize};
 use std::string::ToString;
 use strum_macros::{Display, EnumIter};
 
 pub type Sha1Checksum = [u8; 20];
+pub type Md5Checksum = [u8; 16];
+pub type Crc32 = u32;
 
 pub fn sha1_bytes_to_hex_string(checksum: &Sha1Checksum) -> String {
     checksum.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 pub fn sha1_from_hex_string(hex_str: &str) -> Result<Sha1Checksum, CoreTypeError> {
-    let bytes = <[u8; 20]>::from_hex(hex_str).map_err(|_| {
-        CoreTypeError::ConversionError("Failed to convert hex string to Sha1Checksum".to_string())
+    checksum_from_hex_string(hex_str, "Sha1Checksum")
+}
+
+pub fn md5_bytes_to_hex_string(checksum: &Md5Checksum) -> String {
+    checksum.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn md5_from_hex_string(hex_str: &str) -> Result<Md5Checksum, CoreTypeError> {
+    checksum_from_hex_string(hex_str, "Md5Checksum")
+}
+
+pub fn crc32_to_hex_string(crc: Crc32) -> String {
+    format!("{:08x}", crc)
+}
+
+pub fn crc32_from_hex_string(hex_str: &str) -> Result<Crc32, CoreTypeError> {
+    checksum_from_hex_string::<4>(hex_str, "Crc32").map(u32::from_be_bytes)
+}
+
+fn checksum_from_hex_string<const N: usize>(
+    hex_str: &str,
+    type_name: &str,
+) -> Result<[u8; N], CoreTypeError> {
+    if hex_str.len() != N * 2 {
+        return Err(CoreTypeError::ConversionError(format!(
+            "Failed to convert hex string to {}: expected {} characters, got {}",
+            type_name,
+            N * 2,
+            hex_str.len()
+        )));
+    }
+    let mut bytes = [0; N];
+    hex::decode_to_slice(hex_str, &mut bytes).map_err(|_| {
+        CoreTypeError::ConversionError(format!("Failed to convert hex string to {}", type_name))
     })?;
     Ok(bytes)
 }
@@ -39,12 +72,32 @@ impl std::fmt::Display for CoreTypeError {
 #[derive(Debug, Clone, PartialEq, Copy, EnumIter)]
 pub enum DocumentType {
     Pdf = 1,
+    Txt = 2,
+    Epub = 3,
+    /// Comic book archive, a zip file of page images
+    Cbz = 4,
+}
+
+impl DocumentType {
+    /// Document type of a file extension (with or without the leading dot, case insensitive).
+    pub fn from_extension(ext: &str) -> Option<DocumentType> {
+        match ext.trim_start_matches('.').to_lowercase().as_str() {
+            "pdf" => Some(DocumentType::Pdf),
+            "txt" => Some(DocumentType::Txt),
+            "epub" => Some(DocumentType::Epub),
+            "cbz" => Some(DocumentType::Cbz),
+            _ => None,
+        }
+    }
 }
 
 impl From<DocumentType> for i64 {
     fn from(value: DocumentType) -> Self {
         match value {
             DocumentType::Pdf => 1,
+            DocumentType::Txt => 2,
+            DocumentType::Epub => 3,
+            DocumentType::Cbz => 4,
         }
     }
 }
@@ -54,6 +107,9 @@ impl TryFrom<i64> for DocumentType {
     fn try_from(value: i64) -> Result<Self, CoreTypeError> {
         match value {
             1 => Ok(DocumentType::Pdf),
+            2 => Ok(DocumentType::Txt),
+            3 => Ok(DocumentType::Epub),
+            4 => Ok(DocumentType::Cbz),
             _ => Err(CoreTypeError::ConversionError(
                 "Failed convert to DocumentType".to_string(),
             )),
@@ -65,6 +121,9 @@ impl std::fmt::Display for DocumentType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DocumentType::Pdf => write!(f, "PDF"),
+            DocumentType::Txt => write!(f, "Text"),
+            DocumentType::Epub => write!(f, "EPUB"),
+            DocumentType::Cbz => write!(f, "CBZ"),
         }
     }
 }
@@ -93,7 +152,23 @@ pub struct ReadFile {
     pub file_size: FileSize,
 }
 
-#[derive(Debug, Clone, PartialEq, Copy, EnumIter, Display, Eq, Ord, PartialOrd, Hash)]
+/// Serialized as the same integer as stored in the database, so that persisted values don't
+/// change if variants are renamed.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Copy,
+    EnumIter,
+    Display,
+    Eq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(try_from = "u8", into = "u8")]
 #[repr(u8)]
 pub enum FileType {
     Rom = 1,
@@ -162,6 +237,12 @@ impl FileType {
         }
     }
 
+    /// Inverse of [`FileType::dir_name`].
+    pub fn from_dir_name(name: &str) -> Option<FileType> {
+        use strum::IntoEnumIterator;
+        FileType::iter().find(|file_type| file_type.dir_name() == name)
+    }
+
     pub fn to_db_int(&self) -> u8 {
         *self as u8
     }
@@ -191,6 +272,74 @@ impl FileType {
         }
     }
 
+    /// Guess the file type from a file extension (with or without the leading dot, case
+    /// insensitive). Images default to `Screenshot` since a scan can't be told apart from a
+    /// screenshot by the extension alone.
+    pub fn from_extension(ext: &str) -> Option<FileType> {
+        let ext = ext.trim_start_matches('.').to_lowercase();
+        match ext.as_str() {
+            "d64" | "d71" | "d81" | "g64" | "adf" | "dsk" | "atr" | "st" | "img" => {
+                Some(FileType::DiskImage)
+            }
+            "t64" | "tap" | "tzx" | "cas" | "cdt" | "uef" => Some(FileType::TapeImage),
+            "prg" | "crt" | "rom" | "bin" | "nes" | "sfc" | "smc" | "gb" | "gbc" | "gba" | "md"
+            | "sms" | "a26" | "n64" | "z64" => Some(FileType::Rom),
+            "vsf" | "sna" | "z80" => Some(FileType::MemorySnapshot),
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" => Some(FileType::Screenshot),
+            "pdf" => Some(FileType::Manual),
+            _ => None,
+        }
+    }
+
+    /// Default MIME type for files of this type, used when the actual format isn't known.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            FileType::Rom
+            | FileType::DiskImage
+            | FileType::TapeImage
+            | FileType::MemorySnapshot => "application/octet-stream",
+            FileType::Screenshot | FileType::LoadingScreen | FileType::TitleScreen => "image/png",
+            FileType::CoverScan
+            | FileType::ManualScan
+            | FileType::MediaScan
+            | FileType::InlayScan
+            | FileType::BoxScan
+            | FileType::Scan => "image/jpeg",
+            FileType::Manual | FileType::Box | FileType::Document => "application/pdf",
+        }
+    }
+
+    pub fn is_emulator_file(&self) -> bool {
+        EMULATOR_FILE_TYPES.contains(self)
+    }
+
+    pub fn is_image(&self) -> bool {
+        IMAGE_FILE_TYPES.contains(self)
+    }
+
+    pub fn is_document(&self) -> bool {
+        DOCUMENT_FILE_TYPES.contains(self)
+    }
+
+    pub fn category(&self) -> FileCategory {
+        match self {
+            FileType::Rom
+            | FileType::DiskImage
+            | FileType::TapeImage
+            | FileType::MemorySnapshot => FileCategory::Emulator,
+            FileType::Screenshot
+            | FileType::CoverScan
+            | FileType::LoadingScreen
+            | FileType::TitleScreen
+            | FileType::ManualScan
+            | FileType::MediaScan
+            | FileType::InlayScan
+            | FileType::BoxScan
+            | FileType::Scan => FileCategory::Image,
+            FileType::Manual | FileType::Box | FileType::Document => FileCategory::Document,
+        }
+    }
+
     pub fn is_media_type(&self) -> bool {
         matches!(
             self,
@@ -199,6 +348,30 @@ impl FileType {
     }
 }
 
+impl From<FileType> for u8 {
+    fn from(value: FileType) -> Self {
+        value.to_db_int()
+    }
+}
+
+impl TryFrom<u8> for FileType {
+    type Error = CoreTypeError;
+    fn try_from(value: u8) -> Result<Self, CoreTypeError> {
+        FileType::from_db_int(value)
+    }
+}
+
+/// Broad category of a file type, every file type belongs to exactly one category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCategory {
+    /// Files run with an emulator, see `EMULATOR_FILE_TYPES`
+    Emulator,
+    /// Screenshots and scans, see `IMAGE_FILE_TYPES`
+    Image,
+    /// Manuals and other documents, see `DOCUMENT_FILE_TYPES`
+    Document,
+}
+
 pub const EMULATOR_FILE_TYPES: &[FileType] = &[
     FileType::DiskImage,
     FileType::TapeImage,
@@ -239,9 +412,91 @@ pub enum ArgumentType {
     FlagWithValue { name: String, value: String },
     #[strum(to_string = "{name}={value}")]
     FlagEqualsValue { name: String, value: String },
+    /// Bare argument without a flag (e.g. a file name)
+    #[strum(to_string = "{value}")]
+    Positional { value: String },
     // TODO: add more types as needed
 }
 
+/// Placeholder replaced with the full path of the selected file
+pub const FILE_PLACEHOLDER: &str = "{file}";
+/// Placeholder replaced with the directory of the selected file
+pub const DIR_PLACEHOLDER: &str = "{dir}";
+/// Placeholder replaced with the selected file name without extension
+pub const FILE_STEM_PLACEHOLDER: &str = "{file_stem}";
+/// Placeholder for all files of the file set: the argument is repeated for each file, e.g.
+/// `-flipname {files}` becomes `-flipname disk1.d64 -flipname disk2.d64`
+pub const FILES_PLACEHOLDER: &str = "{files}";
+
+/// Values substituted for the placeholders in argument values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgumentPlaceholders {
+    pub file: String,
+    pub dir: String,
+    pub file_stem: String,
+    /// Full paths of all files of the file set, including the selected file
+    pub files: Vec<String>,
+}
+
+impl ArgumentType {
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            ArgumentType::Flag { .. } => None,
+            ArgumentType::FlagWithValue { value, .. }
+            | ArgumentType::FlagEqualsValue { value, .. }
+            | ArgumentType::Positional { value } => Some(value),
+        }
+    }
+
+    /// Whether the value refers to the selected file with `{file}` or `{files}`, in which case
+    /// the file is not appended as the last argument.
+    pub fn has_file_placeholder(&self) -> bool {
+        self.value().is_some_and(|value| {
+            value.contains(FILE_PLACEHOLDER) || value.contains(FILES_PLACEHOLDER)
+        })
+    }
+
+    /// Replace the `{file}`, `{dir}` and `{file_stem}` placeholders in the value. An argument
+    /// with the `{files}` placeholder is repeated for each file, and left out if there are no
+    /// files.
+    pub fn substitute_placeholders(
+        &self,
+        placeholders: &ArgumentPlaceholders,
+    ) -> Vec<ArgumentType> {
+        let Some(value) = self.value() else {
+            return vec![self.clone()];
+        };
+        let value = value
+            .replace(FILE_PLACEHOLDER, &placeholders.file)
+            .replace(DIR_PLACEHOLDER, &placeholders.dir)
+            .replace(FILE_STEM_PLACEHOLDER, &placeholders.file_stem);
+        if value.contains(FILES_PLACEHOLDER) {
+            placeholders
+                .files
+                .iter()
+                .map(|file| self.with_value(value.replace(FILES_PLACEHOLDER, file)))
+                .collect()
+        } else {
+            vec![self.with_value(value)]
+        }
+    }
+
+    fn with_value(&self, value: String) -> ArgumentType {
+        match self {
+            ArgumentType::Flag { .. } => self.clone(),
+            ArgumentType::FlagWithValue { name, .. } => ArgumentType::FlagWithValue {
+                name: name.clone(),
+                value,
+            },
+            ArgumentType::FlagEqualsValue { name, .. } => ArgumentType::FlagEqualsValue {
+                name: name.clone(),
+                value,
+            },
+            ArgumentType::Positional { .. } => ArgumentType::Positional { value },
+        }
+    }
+}
+
 impl TryFrom<&str> for ArgumentType {
     type Error = CoreTypeError;
     fn try_from(argument_string: &str) -> Result<Self, Self::Error> {
@@ -281,6 +536,59 @@ fn parse_argument(argument_string: &str) -> Result<ArgumentType, CoreTypeError>
     })
 }
 
+/// Parse a command line into arguments. The line is split on whitespace like a shell would,
+/// respecting single and double quotes and backslash escapes.
+///
+/// A token starting with `-` is a flag: `--flag=value` is a `FlagEqualsValue`, and a flag
+/// followed by a token not starting with `-` is a `FlagWithValue`. Other tokens are
+/// `Positional`. An unterminated quote extends to the end of the line.
+pub fn parse_arguments(line: &str) -> Vec<ArgumentType> {
+    let mut arguments = Vec::new();
+    let mut tokens = split_command_line(line).into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if !token.starts_with('-') {
+            arguments.push(ArgumentType::Positional { value: token });
+        } else if let Some((name, value)) = token.split_once('=') {
+            arguments.push(ArgumentType::FlagEqualsValue {
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+        } else if let Some(value) = tokens.next_if(|next| !next.starts_with('-')) {
+            arguments.push(ArgumentType::FlagWithValue { name: token, value });
+        } else {
+            arguments.push(ArgumentType::Flag { name: token });
+        }
+    }
+    arguments
+}
+
+fn split_command_line(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    current.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                // Quoted empty string is still a token
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => tokens.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    tokens.extend(current);
+    tokens
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SettingName {
     CollectionRootDir,
@@ -288,8 +596,13 @@ pub enum SettingName {
     S3Region,
     S3Bucket,
     S3FileSyncEnabled,
+    S3PathStyle,
     LibretroCoresDir,
     LibretroSystemDir,
+    TrashRetentionDays,
+    MaxConcurrentUploads,
+    ExecutableTimeoutSecs,
+    UploadPartSizeMb,
 }
 
 impl SettingName {
@@ -300,8 +613,13 @@ impl SettingName {
             SettingName::S3Region => "s3_region",
             SettingName::S3Bucket => "s3_bucket",
             SettingName::S3FileSyncEnabled => "s3_file_sync_enabled",
+            SettingName::S3PathStyle => "s3_path_style",
             SettingName::LibretroCoresDir => "libretro_cores_dir",
             SettingName::LibretroSystemDir => "libretro_system_dir",
+            SettingName::TrashRetentionDays => "trash_retention_days",
+            SettingName::MaxConcurrentUploads => "max_concurrent_uploads",
+            SettingName::ExecutableTimeoutSecs => "executable_timeout_secs",
+            SettingName::UploadPartSizeMb => "upload_part_size_mb",
         }
     }
 }
@@ -406,6 +724,64 @@ pub struct FileSetFileEqualitySpecs {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[test]
+    fn test_checksum_hex_round_trip() {
+        let sha1 = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        assert_eq!(
+            sha1_bytes_to_hex_string(&sha1_from_hex_string(sha1).unwrap()),
+            sha1
+        );
+        let md5 = "d41d8cd98f00b204e9800998ecf8427e";
+        let md5_bytes = md5_from_hex_string(md5).unwrap();
+        assert_eq!(md5_bytes[0], 0xd4);
+        assert_eq!(md5_bytes_to_hex_string(&md5_bytes), md5);
+        assert_eq!(crc32_from_hex_string("0aBcDeF1").unwrap(), 0x0abcdef1);
+        assert_eq!(crc32_to_hex_string(0x0abcdef1), "0abcdef1");
+        assert_eq!(crc32_to_hex_string(0), "00000000");
+    }
+
+    #[test]
+    fn test_checksum_from_hex_string_errors() {
+        let err = md5_from_hex_string("d41d8cd98f00b204e9800998ecf8427").unwrap_err();
+        assert!(
+            err.to_string().contains("expected 32 characters, got 31"),
+            "{}",
+            err
+        );
+        assert!(sha1_from_hex_string("d41d8cd98f00b204e9800998ecf8427e").is_err());
+        assert!(crc32_from_hex_string("1234").is_err());
+        assert!(crc32_from_hex_string("+1234567").is_err());
+        assert!(crc32_from_hex_string("xyz12345").is_err());
+    }
+
+    #[test]
+    fn test_document_type_conversions() {
+        use strum::IntoEnumIterator;
+        for document_type in DocumentType::iter() {
+            let value = i64::from(document_type);
+            assert_eq!(DocumentType::try_from(value).unwrap(), document_type);
+        }
+        assert_eq!(i64::from(DocumentType::Cbz), 4);
+        assert_eq!(DocumentType::Epub.to_string(), "EPUB");
+        assert!(DocumentType::try_from(0).is_err());
+        assert!(DocumentType::try_from(5).is_err());
+    }
+
+    #[test]
+    fn test_document_type_from_extension() {
+        assert_eq!(DocumentType::from_extension("pdf"), Some(DocumentType::Pdf));
+        assert_eq!(
+            DocumentType::from_extension(".TXT"),
+            Some(DocumentType::Txt)
+        );
+        assert_eq!(
+            DocumentType::from_extension("epub"),
+            Some(DocumentType::Epub)
+        );
+        assert_eq!(DocumentType::from_extension("Cbz"), Some(DocumentType::Cbz));
+        assert_eq!(DocumentType::from_extension("png"), None);
+    }
+
     #[test]
     fn test_parse_argument() {
         assert_eq!(
@@ -453,6 +829,210 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_arguments_with_multiple_flags() {
+        assert_eq!(
+            parse_arguments("-cart1 a.crt -cart2 b.crt --fullscreen -v=2 game.d64"),
+            vec![
+                ArgumentType::FlagWithValue {
+                    name: "-cart1".to_string(),
+                    value: "a.crt".to_string()
+                },
+                ArgumentType::FlagWithValue {
+                    name: "-cart2".to_string(),
+                    value: "b.crt".to_string()
+                },
+                ArgumentType::Flag {
+                    name: "--fullscreen".to_string()
+                },
+                ArgumentType::FlagEqualsValue {
+                    name: "-v".to_string(),
+                    value: "2".to_string()
+                },
+                ArgumentType::Positional {
+                    value: "game.d64".to_string()
+                },
+            ]
+        );
+        assert!(parse_arguments("   ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_arguments_with_quoted_values() {
+        assert_eq!(
+            parse_arguments(r#"-config "my config.cfg" 'it''s' "a \"b\"" --name="x y" """#),
+            vec![
+                ArgumentType::FlagWithValue {
+                    name: "-config".to_string(),
+                    value: "my config.cfg".to_string()
+                },
+                ArgumentType::Positional {
+                    value: "its".to_string()
+                },
+                ArgumentType::Positional {
+                    value: "a \"b\"".to_string()
+                },
+                ArgumentType::FlagEqualsValue {
+                    name: "--name".to_string(),
+                    value: "x y".to_string()
+                },
+                ArgumentType::Positional {
+                    value: "".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            parse_arguments("file\\ name \"unterminated quote"),
+            vec![
+                ArgumentType::Positional {
+                    value: "file name".to_string()
+                },
+                ArgumentType::Positional {
+                    value: "unterminated quote".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_substitute_placeholders() {
+        let placeholders = ArgumentPlaceholders {
+            file: "/roms/game.zip".to_string(),
+            dir: "/roms".to_string(),
+            file_stem: "game".to_string(),
+            files: vec![],
+        };
+        let arguments = parse_arguments("{file_stem} -rompath {dir} --state={file_stem}.sav -v");
+        let substituted: Vec<ArgumentType> = arguments
+            .iter()
+            .flat_map(|argument| argument.substitute_placeholders(&placeholders))
+            .collect();
+
+        assert_eq!(
+            substituted,
+            parse_arguments("game -rompath /roms --state=game.sav -v")
+        );
+        assert!(!arguments.iter().any(ArgumentType::has_file_placeholder));
+        assert!(parse_arguments("-cart {file}")[0].has_file_placeholder());
+        assert!(parse_arguments("-flipname {files}")[0].has_file_placeholder());
+    }
+
+    #[test]
+    fn test_substitute_files_placeholder() {
+        let placeholders = ArgumentPlaceholders {
+            file: "/roms/disk1.d64".to_string(),
+            dir: "/roms".to_string(),
+            file_stem: "disk1".to_string(),
+            files: vec!["/roms/disk1.d64".to_string(), "/roms/disk2.d64".to_string()],
+        };
+        let substituted: Vec<ArgumentType> = parse_arguments("-flipname {files} --disk={files}")
+            .iter()
+            .flat_map(|argument| argument.substitute_placeholders(&placeholders))
+            .collect();
+
+        assert_eq!(
+            substituted,
+            parse_arguments(
+                "-flipname /roms/disk1.d64 -flipname /roms/disk2.d64 \
+                 --disk=/roms/disk1.d64 --disk=/roms/disk2.d64"
+            )
+        );
+    }
+
+    #[test]
+    fn test_file_type_from_dir_name() {
+        use strum::IntoEnumIterator;
+        for file_type in FileType::iter() {
+            assert_eq!(
+                FileType::from_dir_name(file_type.dir_name()),
+                Some(file_type)
+            );
+        }
+        assert_eq!(FileType::from_dir_name("package_scan"), None);
+        assert_eq!(FileType::from_dir_name("Rom"), None);
+    }
+
+    #[test]
+    fn test_file_type_from_extension() {
+        let cases = [
+            ("d64", "disk_image"),
+            (".T64", "tape_image"),
+            ("nes", "rom"),
+            ("vsf", "memory_snapshot"),
+            ("png", "screenshot"),
+            ("JPG", "screenshot"),
+            ("pdf", "manual"),
+        ];
+        for (ext, dir_name) in cases {
+            let file_type = FileType::from_extension(ext).unwrap();
+            assert_eq!(file_type.dir_name(), dir_name, "{}", ext);
+        }
+        assert_eq!(FileType::from_extension("exe"), None);
+        assert_eq!(FileType::from_extension(""), None);
+    }
+
+    #[test]
+    fn test_file_type_mime_type() {
+        assert_eq!(FileType::Rom.mime_type(), "application/octet-stream");
+        assert_eq!(FileType::Screenshot.mime_type(), "image/png");
+        assert_eq!(FileType::Manual.mime_type(), "application/pdf");
+        for ext in ["png", "pdf"] {
+            let file_type = FileType::from_extension(ext).unwrap();
+            assert!(file_type.mime_type().ends_with(ext));
+        }
+    }
+
+    #[test]
+    fn test_file_type_category() {
+        use strum::IntoEnumIterator;
+        for file_type in FileType::iter() {
+            let category = file_type.category();
+            assert_eq!(
+                EMULATOR_FILE_TYPES.contains(&file_type),
+                category == FileCategory::Emulator,
+                "{}",
+                file_type
+            );
+            assert_eq!(
+                IMAGE_FILE_TYPES.contains(&file_type),
+                category == FileCategory::Image,
+                "{}",
+                file_type
+            );
+            assert_eq!(
+                DOCUMENT_FILE_TYPES.contains(&file_type),
+                category == FileCategory::Document,
+                "{}",
+                file_type
+            );
+            assert_eq!(
+                file_type.is_emulator_file(),
+                category == FileCategory::Emulator
+            );
+            assert_eq!(file_type.is_image(), category == FileCategory::Image);
+            assert_eq!(file_type.is_document(), category == FileCategory::Document);
+        }
+    }
+
+    #[test]
+    fn test_file_type_serde_round_trip() {
+        use strum::IntoEnumIterator;
+        for file_type in FileType::iter() {
+            let json = serde_json::to_string(&file_type).unwrap();
+            assert_eq!(json, file_type.to_db_int().to_string());
+            assert_eq!(serde_json::from_str::<FileType>(&json).unwrap(), file_type);
+        }
+        assert_eq!(serde_json::to_string(&FileType::DiskImage).unwrap(), "2");
+    }
+
+    #[test]
+    fn test_file_type_deserialize_out_of_range() {
+        assert!(serde_json::from_str::<FileType>("12").is_err());
+        assert!(serde_json::from_str::<FileType>("255").is_err());
+        assert!(serde_json::from_str::<FileType>("256").is_err());
+        assert!(serde_json::from_str::<FileType>("\"Rom\"").is_err());
+    }
+
     #[test]
     fn test_is_media_type() {
         assert!(FileType::Rom.is_media_type());
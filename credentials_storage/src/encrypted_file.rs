@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use aes_gcm::{
+    Aes256Gcm, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+};
+use sha2::Sha256;
+
+use crate::{CloudCredentials, CredentialsError};
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_DERIVATION_ROUNDS: u32 = 100_000;
+
+/// Credentials of all profiles stored in a single AES-256-GCM encrypted file.
+///
+/// File layout is `salt | nonce | ciphertext`, where the key is derived from the passphrase and
+/// salt with PBKDF2-HMAC-SHA256 and the plaintext is the profiles serialized as JSON.
+#[derive(Clone)]
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase: String,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            passphrase: passphrase.into(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load credentials of all profiles. Returns an empty map if the file doesn't exist.
+    pub fn load_all(&self) -> Result<BTreeMap<String, CloudCredentials>, CredentialsError> {
+        let content = match std::fs::read(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(CredentialsError::EncryptedFile(e.to_string())),
+        };
+        if content.len() < SALT_LEN + NONCE_LEN {
+            return Err(CredentialsError::EncryptedFile(
+                "Credentials file is truncated".to_string(),
+            ));
+        }
+        let (salt, rest) = content.split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher(salt)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                CredentialsError::EncryptedFile(
+                    "Failed to decrypt credentials file, wrong passphrase?".to_string(),
+                )
+            })?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Replace the credentials of all profiles. A new salt and nonce are used on every write.
+    ///
+    /// The file is written to a temporary file readable only by the owner and renamed over the
+    /// credentials file, so that a failed write can't lose the stored credentials.
+    pub fn save_all(
+        &self,
+        profiles: &BTreeMap<String, CloudCredentials>,
+    ) -> Result<(), CredentialsError> {
+        let plaintext = serde_json::to_vec(profiles)?;
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(&salt)
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| {
+                CredentialsError::EncryptedFile("Failed to encrypt credentials".to_string())
+            })?;
+
+        let mut content = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+        content.extend_from_slice(&salt);
+        content.extend_from_slice(&nonce);
+        content.extend_from_slice(&ciphertext);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| CredentialsError::EncryptedFile(e.to_string()))?;
+        }
+        write_file_replacing(&self.path, &content)
+            .map_err(|e| CredentialsError::EncryptedFile(e.to_string()))
+    }
+
+    pub fn store(
+        &self,
+        profile: &str,
+        credentials: &CloudCredentials,
+    ) -> Result<(), CredentialsError> {
+        let mut profiles = self.load_all()?;
+        profiles.insert(profile.to_string(), credentials.clone());
+        self.save_all(&profiles)
+    }
+
+    pub fn load(&self, profile: &str) -> Result<CloudCredentials, CredentialsError> {
+        self.load_all()?
+            .remove(profile)
+            .ok_or(CredentialsError::NoCredentials)
+    }
+
+    pub fn delete(&self, profile: &str) -> Result<(), CredentialsError> {
+        let mut profiles = self.load_all()?;
+        if profiles.remove(profile).is_some() {
+            self.save_all(&profiles)?;
+        }
+        Ok(())
+    }
+
+    fn cipher(&self, salt: &[u8]) -> Aes256Gcm {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(
+            self.passphrase.as_bytes(),
+            salt,
+            KEY_DERIVATION_ROUNDS,
+            &mut key,
+        );
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+    }
+}
+
+/// Path of the temporary file the credentials file is written to before renaming.
+fn temp_file_path(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    PathBuf::from(temp_path)
+}
+
+fn write_file_replacing(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let temp_path = temp_file_path(path);
+    // A leftover temporary file would keep its permissions, so it's created anew
+    match std::fs::remove_file(&temp_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let result = options.open(&temp_path).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(e) = result.and_then(|_| std::fs::rename(&temp_path, path)) {
+        std::fs::remove_file(&temp_path).ok();
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_store(name: &str, passphrase: &str) -> EncryptedFileStore {
+        let path = std::env::temp_dir().join(format!(
+            "efm-credentials-{}-{}.enc",
+            name,
+            std::process::id()
+        ));
+        std::fs::remove_file(&path).ok();
+        EncryptedFileStore::new(path, passphrase)
+    }
+
+    fn test_credentials(access_key_id: &str) -> CloudCredentials {
+        CloudCredentials {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: format!("{}-secret", access_key_id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip_profiles() {
+        let store = test_store("round-trip", "correct horse battery staple");
+        let home = test_credentials("home");
+        let remote = test_credentials("remote");
+
+        store.store("default", &home).unwrap();
+        store.store("remote", &remote).unwrap();
+
+        assert_eq!(store.load("default").unwrap(), home);
+        assert_eq!(store.load("remote").unwrap(), remote);
+        let content = std::fs::read(store.path()).unwrap();
+        assert!(!String::from_utf8_lossy(&content).contains("home-secret"));
+
+        store.delete("remote").unwrap();
+        assert!(matches!(
+            store.load("remote"),
+            Err(CredentialsError::NoCredentials)
+        ));
+        std::fs::remove_file(store.path()).unwrap();
+    }
+
+    #[test]
+    fn test_save_replaces_file_readable_only_by_owner() {
+        let store = test_store("replace", "passphrase");
+        std::fs::write(store.path(), b"previous content").unwrap();
+
+        store
+            .save_all(&BTreeMap::from([(
+                "default".to_string(),
+                test_credentials("home"),
+            )]))
+            .unwrap();
+
+        assert!(store.load("default").is_ok());
+        assert!(!temp_file_path(store.path()).exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(store.path())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(store.path()).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_has_no_credentials() {
+        let store = test_store("missing", "passphrase");
+
+        assert!(store.load_all().unwrap().is_empty());
+        assert!(matches!(
+            store.load("default"),
+            Err(CredentialsError::NoCredentials)
+        ));
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails() {
+        let store = test_store("wrong-passphrase", "passphrase");
+        store.store("default", &test_credentials("home")).unwrap();
+
+        let wrong = EncryptedFileStore::new(store.path(), "other passphrase");
+
+        assert!(matches!(
+            wrong.load("default"),
+            Err(CredentialsError::EncryptedFile(_))
+        ));
+        std::fs::remove_file(store.path()).unwrap();
+    }
+}
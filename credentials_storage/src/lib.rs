@@ -1,9 +1,103 @@
+use std::sync::{Mutex, OnceLock};
+
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
 
+mod encrypted_file;
+pub use encrypted_file::EncryptedFileStore;
+
 #[cfg(not(test))]
 const SERVICE_NAME: &str = "efm-cloud-sync";
-const USERNAME: &str = "s3-credentials"; // Fixed username for all credentials
+const USERNAME: &str = "s3-credentials"; // Username of the default profile
+const PROFILES_USERNAME: &str = "s3-credential-profiles"; // Names of the stored profiles
+
+/// Profile used before named profiles were supported.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Environment variable holding the passphrase of the encrypted credentials file.
+pub const PASSPHRASE_ENV_VAR: &str = "EFM_CREDENTIALS_PASSPHRASE";
+
+/// Where credentials are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialsBackend {
+    /// System keyring
+    Keyring,
+    /// AES encrypted file in the application data directory, used when the keyring is not
+    /// available (e.g. on headless servers) and a passphrase is given in `PASSPHRASE_ENV_VAR`.
+    EncryptedFile,
+}
+
+/// Encrypted file store set with [`set_encrypted_file_store`], used instead of the selected
+/// backend.
+static ENCRYPTED_FILE_STORE_OVERRIDE: Mutex<Option<EncryptedFileStore>> = Mutex::new(None);
+
+/// Use the given encrypted file store for the credential functions of this crate regardless of
+/// the keyring availability, e.g. in tests. `None` restores the backend selection of
+/// [`active_backend`].
+pub fn set_encrypted_file_store(store: Option<EncryptedFileStore>) {
+    *ENCRYPTED_FILE_STORE_OVERRIDE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = store;
+}
+
+fn encrypted_file_store_override() -> Option<EncryptedFileStore> {
+    ENCRYPTED_FILE_STORE_OVERRIDE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Select the backend used by the credential functions of this crate.
+///
+/// System keyring is preferred. Encrypted file is used if a store has been set with
+/// [`set_encrypted_file_store`], or if the keyring is not available and the passphrase
+/// environment variable is set.
+pub fn active_backend() -> CredentialsBackend {
+    if encrypted_file_store_override().is_some()
+        || (std::env::var_os(PASSPHRASE_ENV_VAR).is_some() && !is_keyring_available())
+    {
+        CredentialsBackend::EncryptedFile
+    } else {
+        CredentialsBackend::Keyring
+    }
+}
+
+/// Check if the keyring service can be accessed. The keyring is probed only once per process,
+/// since the probe may be slow and its result doesn't change while the application runs.
+fn is_keyring_available() -> bool {
+    static KEYRING_AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *KEYRING_AVAILABLE.get_or_init(probe_keyring)
+}
+
+fn probe_keyring() -> bool {
+    let entry = match Entry::new(get_service_name(), PROFILES_USERNAME) {
+        Ok(e) => e,
+        Err(_) => return false,
+    };
+
+    // Try a simple operation - if it fails with platform/service error, keyring is unavailable
+    !matches!(
+        entry.get_password(),
+        Err(keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_))
+    )
+}
+
+/// Get the encrypted file store if it's the active backend.
+fn encrypted_file_store() -> Option<EncryptedFileStore> {
+    if let Some(store) = encrypted_file_store_override() {
+        return Some(store);
+    }
+    match active_backend() {
+        CredentialsBackend::Keyring => None,
+        CredentialsBackend::EncryptedFile => {
+            let passphrase = std::env::var(PASSPHRASE_ENV_VAR).ok()?;
+            Some(EncryptedFileStore::new(
+                file_system::get_credentials_file_path(),
+                passphrase,
+            ))
+        }
+    }
+}
 
 // Test-specific service name to avoid polluting production keyring
 #[cfg(test)]
@@ -21,11 +115,60 @@ fn get_service_name() -> &'static str {
     }
 }
 
+/// Get the keyring username of a credential profile.
+///
+/// Default profile keeps the username used before named profiles, so previously stored
+/// credentials are found from it.
+fn get_username(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        USERNAME.to_string()
+    } else {
+        format!("{}:{}", USERNAME, profile)
+    }
+}
+
+fn get_entry(profile: &str) -> Result<Entry, CredentialsError> {
+    Ok(Entry::new(get_service_name(), &get_username(profile))?)
+}
+
+/// Load names of the profiles that have been stored. Doesn't include the default profile
+/// stored before named profiles were supported.
+fn load_profile_index() -> Result<Vec<String>, CredentialsError> {
+    let entry = Entry::new(get_service_name(), PROFILES_USERNAME)?;
+    match entry.get_password() {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(keyring::Error::NoEntry) => Ok(Vec::new()),
+        Err(e) => Err(CredentialsError::Keyring(e)),
+    }
+}
+
+fn save_profile_index(profiles: &[String]) -> Result<(), CredentialsError> {
+    let entry = Entry::new(get_service_name(), PROFILES_USERNAME)?;
+    if profiles.is_empty() {
+        match entry.delete_credential() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(CredentialsError::Keyring(e)),
+        }
+    } else {
+        entry.set_password(&serde_json::to_string(profiles)?)?;
+        Ok(())
+    }
+}
+
 /// Cloud storage credentials (S3-compatible)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+///
+/// Connection details are optional so that credentials stored before they were added can still
+/// be deserialized.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CloudCredentials {
     pub access_key_id: String,
     pub secret_access_key: String,
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub bucket: Option<String>,
 }
 
 /// Errors that can occur when working with credentials
@@ -39,14 +182,19 @@ pub enum CredentialsError {
 
     #[error("Failed to serialize/deserialize credentials: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    #[error("Encrypted credentials file error: {0}")]
+    EncryptedFile(String),
 }
 
 /// Store cloud sync credentials securely in the system keyring.
 ///
-/// The credentials are stored as JSON in the system's native credential store
+/// The credentials are stored as JSON in the system's native credential store, or in the
+/// encrypted credentials file if the keyring is not available (see [`active_backend`]).
 ///
 /// # Arguments
 ///
+/// * `profile` - Name of the credential profile, e.g. `DEFAULT_PROFILE`
 /// * `credentials` - The credentials to store
 ///
 /// # Errors
@@ -59,17 +207,30 @@ pub enum CredentialsError {
 /// let creds = CloudCredentials {
 ///     access_key_id: "my-key-id".to_string(),
 ///     secret_access_key: "my-secret".to_string(),
+///     ..Default::default()
 /// };
-/// store_credentials(&creds)?;
+/// store_credentials(DEFAULT_PROFILE, &creds)?;
 /// ```
-pub fn store_credentials(credentials: &CloudCredentials) -> Result<(), CredentialsError> {
-    let entry = Entry::new(get_service_name(), USERNAME)?;
+pub fn store_credentials(
+    profile: &str,
+    credentials: &CloudCredentials,
+) -> Result<(), CredentialsError> {
+    if let Some(store) = encrypted_file_store() {
+        return store.store(profile, credentials);
+    }
+    let entry = get_entry(profile)?;
     let json = serde_json::to_string(credentials)?;
     entry.set_password(&json)?;
+
+    let mut profiles = load_profile_index()?;
+    if !profiles.iter().any(|p| p == profile) {
+        profiles.push(profile.to_string());
+        save_profile_index(&profiles)?;
+    }
     Ok(())
 }
 
-/// Load cloud sync credentials from the system keyring.
+/// Load cloud sync credentials of a profile from the system keyring.
 ///
 /// # Errors
 ///
@@ -79,14 +240,17 @@ pub fn store_credentials(credentials: &CloudCredentials) -> Result<(), Credentia
 /// # Example
 ///
 /// ```ignore
-/// match load_credentials() {
+/// match load_credentials(DEFAULT_PROFILE) {
 ///     Ok(creds) => println!("Loaded credentials for: {}", creds.access_key_id),
 ///     Err(CredentialsError::NoCredentials) => println!("No credentials stored"),
 ///     Err(e) => eprintln!("Error: {}", e),
 /// }
 /// ```
-pub fn load_credentials() -> Result<CloudCredentials, CredentialsError> {
-    let entry = Entry::new(get_service_name(), USERNAME)?;
+pub fn load_credentials(profile: &str) -> Result<CloudCredentials, CredentialsError> {
+    if let Some(store) = encrypted_file_store() {
+        return store.load(profile);
+    }
+    let entry = get_entry(profile)?;
     match entry.get_password() {
         Ok(json) => {
             let credentials = serde_json::from_str(&json)?;
@@ -103,6 +267,8 @@ pub fn load_credentials() -> Result<CloudCredentials, CredentialsError> {
 /// are found there, it falls back to the AWS standard environment variables:
 /// - `AWS_ACCESS_KEY_ID`
 /// - `AWS_SECRET_ACCESS_KEY`
+/// - `AWS_DEFAULT_REGION` (optional)
+/// - `AWS_ENDPOINT_URL` (optional)
 ///
 /// This provides backward compatibility and allows for different deployment scenarios
 /// (e.g., containerized environments where environment variables are preferred).
@@ -116,11 +282,11 @@ pub fn load_credentials() -> Result<CloudCredentials, CredentialsError> {
 ///
 /// ```ignore
 /// // Will use keyring if available, otherwise environment variables
-/// let creds = load_credentials_with_fallback()?;
+/// let creds = load_credentials_with_fallback(DEFAULT_PROFILE)?;
 /// ```
-pub fn load_credentials_with_fallback() -> Result<CloudCredentials, CredentialsError> {
+pub fn load_credentials_with_fallback(profile: &str) -> Result<CloudCredentials, CredentialsError> {
     // Try keyring first
-    match load_credentials() {
+    match load_credentials(profile) {
         Ok(creds) => Ok(creds),
         Err(CredentialsError::NoCredentials) => {
             // Fall back to environment variables
@@ -131,6 +297,9 @@ pub fn load_credentials_with_fallback() -> Result<CloudCredentials, CredentialsE
                 (Some(access_key_id), Some(secret_access_key)) => Ok(CloudCredentials {
                     access_key_id,
                     secret_access_key,
+                    endpoint: std::env::var("AWS_ENDPOINT_URL").ok(),
+                    region: std::env::var("AWS_DEFAULT_REGION").ok(),
+                    bucket: None,
                 }),
                 _ => Err(CredentialsError::NoCredentials),
             }
@@ -139,7 +308,7 @@ pub fn load_credentials_with_fallback() -> Result<CloudCredentials, CredentialsE
     }
 }
 
-/// Delete cloud sync credentials from the system keyring.
+/// Delete cloud sync credentials of a profile from the system keyring.
 ///
 /// This operation is idempotent - deleting already-deleted credentials succeeds.
 ///
@@ -150,19 +319,29 @@ pub fn load_credentials_with_fallback() -> Result<CloudCredentials, CredentialsE
 /// # Example
 ///
 /// ```ignore
-/// delete_credentials()?;
+/// delete_credentials(DEFAULT_PROFILE)?;
 /// println!("Credentials removed");
 /// ```
-pub fn delete_credentials() -> Result<(), CredentialsError> {
-    let entry = Entry::new(get_service_name(), USERNAME)?;
+pub fn delete_credentials(profile: &str) -> Result<(), CredentialsError> {
+    if let Some(store) = encrypted_file_store() {
+        return store.delete(profile);
+    }
+    let entry = get_entry(profile)?;
     match entry.delete_credential() {
-        Ok(_) => Ok(()),
-        Err(keyring::Error::NoEntry) => Ok(()), // Already deleted
-        Err(e) => Err(CredentialsError::Keyring(e)),
+        Ok(_) => {}
+        Err(keyring::Error::NoEntry) => {} // Already deleted
+        Err(e) => return Err(CredentialsError::Keyring(e)),
+    }
+
+    let mut profiles = load_profile_index()?;
+    if profiles.iter().any(|p| p == profile) {
+        profiles.retain(|p| p != profile);
+        save_profile_index(&profiles)?;
     }
+    Ok(())
 }
 
-/// Check if credentials are currently stored in the keyring.
+/// Check if credentials of a profile are currently stored in the keyring.
 ///
 /// Note: This does not check environment variables. Use `load_credentials_with_fallback()`
 /// if you want to check all sources.
@@ -174,20 +353,38 @@ pub fn delete_credentials() -> Result<(), CredentialsError> {
 /// # Example
 ///
 /// ```ignore
-/// if has_credentials()? {
+/// if has_credentials(DEFAULT_PROFILE)? {
 ///     println!("Credentials are stored");
 /// } else {
 ///     println!("No credentials found");
 /// }
 /// ```
-pub fn has_credentials() -> Result<bool, CredentialsError> {
-    match load_credentials() {
+pub fn has_credentials(profile: &str) -> Result<bool, CredentialsError> {
+    match load_credentials(profile) {
         Ok(_) => Ok(true),
         Err(CredentialsError::NoCredentials) => Ok(false),
         Err(e) => Err(e),
     }
 }
 
+/// List names of the credential profiles stored in the keyring, sorted by name.
+///
+/// # Errors
+///
+/// Returns an error if the keyring is not accessible.
+pub fn list_profiles() -> Result<Vec<String>, CredentialsError> {
+    if let Some(store) = encrypted_file_store() {
+        return Ok(store.load_all()?.into_keys().collect());
+    }
+    let mut profiles = load_profile_index()?;
+    // Default profile may have been stored before the profile names were recorded
+    if !profiles.iter().any(|p| p == DEFAULT_PROFILE) && has_credentials(DEFAULT_PROFILE)? {
+        profiles.push(DEFAULT_PROFILE.to_string());
+    }
+    profiles.sort();
+    Ok(profiles)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,23 +400,9 @@ mod tests {
 
     // Helper to clean up test credentials after each test
     fn cleanup_test_credentials() {
-        delete_credentials().ok(); // Ignore errors - might not exist
-    }
-
-    // Helper to check if keyring service is available
-    fn is_keyring_available() -> bool {
-        let entry = match Entry::new(get_service_name(), USERNAME) {
-            Ok(e) => e,
-            Err(_) => return false,
-        };
-        
-        // Try a simple operation - if it fails with platform/service error, keyring is unavailable
-        match entry.get_password() {
-            Ok(_) => true, // Password exists, service is available
-            Err(keyring::Error::NoEntry) => true, // No password stored but service is available
-            Err(keyring::Error::PlatformFailure(_)) => false, // Service unavailable
-            Err(_) => true, // Other errors mean service is available but operation failed
-        }
+        // Ignore errors - might not exist
+        delete_credentials(DEFAULT_PROFILE).ok();
+        delete_credentials("remote").ok();
     }
 
     #[test]
@@ -227,6 +410,9 @@ mod tests {
         let creds = CloudCredentials {
             access_key_id: "test-key-id".to_string(),
             secret_access_key: "test-secret".to_string(),
+            endpoint: Some("https://s3.example.com".to_string()),
+            region: Some("us-east-1".to_string()),
+            bucket: Some("my-bucket".to_string()),
         };
 
         let json = serde_json::to_string(&creds).unwrap();
@@ -235,6 +421,19 @@ mod tests {
         assert_eq!(creds, deserialized);
     }
 
+    #[test]
+    fn test_deserialize_credentials_without_connection_details() {
+        let json = r#"{"access_key_id":"old-key-id","secret_access_key":"old-secret"}"#;
+
+        let creds: CloudCredentials = serde_json::from_str(json).unwrap();
+
+        assert_eq!(creds.access_key_id, "old-key-id");
+        assert_eq!(creds.secret_access_key, "old-secret");
+        assert_eq!(creds.endpoint, None);
+        assert_eq!(creds.region, None);
+        assert_eq!(creds.bucket, None);
+    }
+
     #[test]
     #[serial]
     fn test_store_and_load() {
@@ -248,13 +447,14 @@ mod tests {
         let creds = CloudCredentials {
             access_key_id: "test-store-key".to_string(),
             secret_access_key: "test-store-secret".to_string(),
+            ..Default::default()
         };
 
         // Store
-        store_credentials(&creds).expect("Failed to store credentials");
+        store_credentials(DEFAULT_PROFILE, &creds).expect("Failed to store credentials");
 
         // Load
-        let loaded = load_credentials().expect("Failed to load credentials");
+        let loaded = load_credentials(DEFAULT_PROFILE).expect("Failed to load credentials");
         assert_eq!(creds, loaded);
 
         cleanup_test_credentials();
@@ -273,14 +473,88 @@ mod tests {
         let creds = CloudCredentials {
             access_key_id: "test-delete-key".to_string(),
             secret_access_key: "test-delete-secret".to_string(),
+            ..Default::default()
         };
 
-        store_credentials(&creds).unwrap();
-        assert!(has_credentials().unwrap());
+        store_credentials(DEFAULT_PROFILE, &creds).unwrap();
+        assert!(has_credentials(DEFAULT_PROFILE).unwrap());
 
-        delete_credentials().unwrap();
-        assert!(!has_credentials().unwrap());
+        delete_credentials(DEFAULT_PROFILE).unwrap();
+        assert!(!has_credentials(DEFAULT_PROFILE).unwrap());
 
         cleanup_test_credentials();
     }
+
+    #[test]
+    #[serial]
+    fn test_store_and_load_multiple_profiles() {
+        if !is_keyring_available() {
+            eprintln!(
+                "Skipping test_store_and_load_multiple_profiles: keyring service not available"
+            );
+            return;
+        }
+
+        cleanup_test_credentials();
+
+        let home_creds = CloudCredentials {
+            access_key_id: "test-home-key".to_string(),
+            secret_access_key: "test-home-secret".to_string(),
+            ..Default::default()
+        };
+        let remote_creds = CloudCredentials {
+            access_key_id: "test-remote-key".to_string(),
+            secret_access_key: "test-remote-secret".to_string(),
+            ..Default::default()
+        };
+
+        store_credentials(DEFAULT_PROFILE, &home_creds).unwrap();
+        store_credentials("remote", &remote_creds).unwrap();
+
+        assert_eq!(load_credentials(DEFAULT_PROFILE).unwrap(), home_creds);
+        assert_eq!(load_credentials("remote").unwrap(), remote_creds);
+        assert_eq!(list_profiles().unwrap(), vec!["default", "remote"]);
+
+        delete_credentials("remote").unwrap();
+        assert!(!has_credentials("remote").unwrap());
+        assert_eq!(load_credentials(DEFAULT_PROFILE).unwrap(), home_creds);
+        assert_eq!(list_profiles().unwrap(), vec!["default"]);
+
+        cleanup_test_credentials();
+    }
+
+    #[test]
+    #[serial]
+    fn test_encrypted_file_store_override() {
+        let path = std::env::temp_dir().join(format!(
+            "efm-credentials-override-{}.enc",
+            std::process::id()
+        ));
+        std::fs::remove_file(&path).ok();
+        set_encrypted_file_store(Some(EncryptedFileStore::new(&path, "passphrase")));
+        let creds = CloudCredentials {
+            access_key_id: "test-file-key".to_string(),
+            secret_access_key: "test-file-secret".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(active_backend(), CredentialsBackend::EncryptedFile);
+        store_credentials("remote", &creds).unwrap();
+        let loaded = load_credentials("remote");
+        let profiles = list_profiles();
+        delete_credentials("remote").unwrap();
+        let has_deleted = has_credentials("remote");
+
+        set_encrypted_file_store(None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), creds);
+        assert_eq!(profiles.unwrap(), vec!["remote"]);
+        assert!(!has_deleted.unwrap());
+    }
+
+    #[test]
+    fn test_profile_username() {
+        assert_eq!(get_username(DEFAULT_PROFILE), "s3-credentials");
+        assert_eq!(get_username("remote"), "s3-credentials:remote");
+    }
 }
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::{DatFile, DatGame};
+
+/// Location of a rom in the indexed games as `(game_index, rom_index)`.
+pub type RomLocation = (usize, usize);
+
+/// Checksum lookup index over the games of a DAT file.
+///
+/// Checksums are normalized to lowercase, zero-padded hex so that lookups don't depend on how
+/// the DAT file or the caller formats them. The same rom may be included in several games, so
+/// each checksum maps to all locations it's found from.
+#[derive(Debug, Clone, Default)]
+pub struct DatIndex {
+    games: Vec<DatGame>,
+    by_crc: HashMap<String, Vec<RomLocation>>,
+    by_md5: HashMap<String, Vec<RomLocation>>,
+    by_sha1: HashMap<String, Vec<RomLocation>>,
+}
+
+impl DatFile {
+    /// Build a checksum lookup index over the games of the DAT file.
+    pub fn build_index(&self) -> DatIndex {
+        let mut index = DatIndex::default();
+        for game in &self.games {
+            index.add_game(game.clone());
+        }
+        index
+    }
+}
+
+impl DatIndex {
+    /// Add a game and its roms to the index. Allows building the index incrementally.
+    pub fn add_game(&mut self, game: DatGame) {
+        let game_index = self.games.len();
+        for (rom_index, rom) in game.roms.iter().enumerate() {
+            let location = (game_index, rom_index);
+            if let Some(crc) = normalize_checksum(&rom.crc, 8) {
+                self.by_crc.entry(crc).or_default().push(location);
+            }
+            if let Some(md5) = normalize_checksum(&rom.md5, 32) {
+                self.by_md5.entry(md5).or_default().push(location);
+            }
+            if let Some(sha1) = normalize_checksum(&rom.sha1, 40) {
+                self.by_sha1.entry(sha1).or_default().push(location);
+            }
+        }
+        self.games.push(game);
+    }
+
+    pub fn games(&self) -> &[DatGame] {
+        &self.games
+    }
+
+    pub fn game(&self, game_index: usize) -> Option<&DatGame> {
+        self.games.get(game_index)
+    }
+
+    pub fn find_by_crc(&self, crc: &str) -> &[RomLocation] {
+        find(&self.by_crc, crc, 8)
+    }
+
+    pub fn find_by_md5(&self, md5: &str) -> &[RomLocation] {
+        find(&self.by_md5, md5, 32)
+    }
+
+    pub fn find_by_sha1(&self, sha1: &str) -> &[RomLocation] {
+        find(&self.by_sha1, sha1, 40)
+    }
+}
+
+fn find<'a>(
+    checksums: &'a HashMap<String, Vec<RomLocation>>,
+    checksum: &str,
+    width: usize,
+) -> &'a [RomLocation] {
+    normalize_checksum(checksum, width)
+        .and_then(|checksum| checksums.get(&checksum))
+        .map(|locations| locations.as_slice())
+        .unwrap_or_default()
+}
+
+/// Lowercase and left pad the hex checksum to the given width. Returns `None` for empty or
+/// non-hex checksums and checksums longer than the width.
+fn normalize_checksum(checksum: &str, width: usize) -> Option<String> {
+    let checksum = checksum.trim();
+    if checksum.is_empty()
+        || checksum.len() > width
+        || !checksum.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    Some(format!(
+        "{:0>width$}",
+        checksum.to_ascii_lowercase(),
+        width = width
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::parse_dat_file;
+
+    #[test]
+    fn test_find_bios_by_crc() {
+        let dat = parse_dat_file(Path::new("example-data/coleco.dat")).unwrap();
+        let index = dat.build_index();
+
+        let locations = index.find_by_crc("3AA93EF3");
+
+        assert_eq!(locations.len(), 1);
+        let (game_index, rom_index) = locations[0];
+        let game = index.game(game_index).unwrap();
+        assert_eq!(game.name, "[BIOS] ColecoVision (USA, Europe)");
+        assert_eq!(game.roms[rom_index].crc, "3aa93ef3");
+        assert_eq!(index.find_by_sha1(&game.roms[rom_index].sha1), locations);
+        assert_eq!(index.find_by_md5(&game.roms[rom_index].md5), locations);
+    }
+
+    #[test]
+    fn test_normalize_checksum() {
+        assert_eq!(normalize_checksum("ABC", 8), Some("00000abc".to_string()));
+        assert_eq!(normalize_checksum("", 8), None);
+        assert_eq!(normalize_checksum("xyz", 8), None);
+        assert_eq!(normalize_checksum("123456789", 8), None);
+    }
+}
@@ -1,10 +1,20 @@
 use async_trait::async_trait;
-use serde::Deserialize;
+use quick_xml::events::Event;
+use serde::{Deserialize, Deserializer};
 use std::fmt::Display;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 
+mod index;
+pub use index::{DatIndex, RomLocation};
+
+mod matcher;
+pub use matcher::{GameMatch, match_files};
+
+mod streaming;
+pub use streaming::parse_dat_file_streaming;
+
 #[async_trait]
 pub trait DatFileParserOps: Send + Sync {
     fn parse_dat_file(&self, path: &Path) -> Result<DatFile, DatFileParserError>;
@@ -63,6 +73,28 @@ pub struct DatFile {
     pub games: Vec<DatGame>,
 }
 
+/// Summary of a parsed DAT file.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DatStats {
+    pub game_count: usize,
+    pub rom_count: usize,
+    /// Sum of the sizes of all roms as declared in the DAT file
+    pub total_declared_size: u64,
+    pub subset: Option<String>,
+}
+
+impl DatFile {
+    pub fn stats(&self) -> DatStats {
+        let roms = self.games.iter().flat_map(|game| &game.roms);
+        DatStats {
+            game_count: self.games.len(),
+            rom_count: roms.clone().count(),
+            total_declared_size: roms.map(|rom| rom.size).sum(),
+            subset: self.header.subset.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, PartialEq, Clone, Default)]
 pub struct DatHeader {
     pub id: i32,
@@ -90,11 +122,19 @@ pub struct DatGame {
     pub cloneof: Option<String>,
     #[serde(rename = "@cloneofid", default)]
     pub cloneofid: Option<String>,
+    /// Parent set the roms are shared from (MAME and Logiqx DATs)
+    #[serde(rename = "@romof", default)]
+    pub romof: Option<String>,
+    #[serde(rename = "@isbios", default, deserialize_with = "deserialize_yes_no")]
+    pub is_bios: bool,
     #[serde(rename = "category", default)]
     pub categories: Vec<String>,
     pub description: String,
-    #[serde(rename = "rom")]
+    #[serde(rename = "rom", default)]
     pub roms: Vec<DatRom>,
+    /// CHD disk images of the game (arcade and CD based systems)
+    #[serde(rename = "disk", default)]
+    pub disks: Vec<DatDisk>,
     #[serde(rename = "release", default)]
     pub releases: Vec<DatRelease>,
 }
@@ -105,11 +145,12 @@ pub struct DatRom {
     pub name: String,
     #[serde(rename = "@size")]
     pub size: u64,
-    #[serde(rename = "@crc")]
+    // MAME roms don't have md5 and undumped roms don't have any checksums
+    #[serde(rename = "@crc", default)]
     pub crc: String,
-    #[serde(rename = "@md5")]
+    #[serde(rename = "@md5", default)]
     pub md5: String,
-    #[serde(rename = "@sha1")]
+    #[serde(rename = "@sha1", default)]
     pub sha1: String,
     #[serde(rename = "@sha256", default)]
     pub sha256: Option<String>,
@@ -121,6 +162,17 @@ pub struct DatRom {
     pub header: Option<String>,
 }
 
+#[derive(Debug, Deserialize, PartialEq, Clone, Default)]
+pub struct DatDisk {
+    #[serde(rename = "@name")]
+    pub name: String,
+    // Undumped disks don't have a checksum
+    #[serde(rename = "@sha1", default)]
+    pub sha1: String,
+    #[serde(rename = "@status", default)]
+    pub status: Option<String>,
+}
+
 #[derive(Debug, Deserialize, PartialEq, Clone, Default)]
 pub struct DatRelease {
     #[serde(rename = "@name")]
@@ -129,17 +181,153 @@ pub struct DatRelease {
     pub region: String,
 }
 
-pub fn parse_dat_file(path: &Path) -> Result<DatFile, DatFileParserError> {
-    let file = File::open(path).map_err(|e| {
+/// MAME `-listxml` output, where games are `machine` elements and there's no header.
+#[derive(Debug, Deserialize)]
+#[serde(rename = "mame")]
+struct MameListXml {
+    #[serde(rename = "@build", default)]
+    build: Option<String>,
+    #[serde(rename = "machine", default)]
+    machines: Vec<DatGame>,
+}
+
+impl From<MameListXml> for DatFile {
+    fn from(mame: MameListXml) -> Self {
+        DatFile {
+            header: mame_header(mame.build),
+            games: mame.machines,
+        }
+    }
+}
+
+/// Header for a MAME `-listxml` file, which only has the MAME build as metadata.
+fn mame_header(build: Option<String>) -> DatHeader {
+    let build = build.unwrap_or_default();
+    DatHeader {
+        name: "MAME".to_string(),
+        description: format!("MAME {}", build).trim_end().to_string(),
+        version: build,
+        ..Default::default()
+    }
+}
+
+fn deserialize_yes_no<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    Ok(String::deserialize(deserializer)? == "yes")
+}
+
+/// Magic bytes at the start of a gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Open the file for reading, decompressing it if it's gzip compressed (e.g. `.dat.gz`).
+fn open_reader(path: &Path) -> Result<Box<dyn BufRead>, DatFileParserError> {
+    let io_error = |e: std::io::Error| {
         DatFileParserError::IoError(format!("Failed opening path {:?}: {}", path, e))
-    })?;
-    let reader = BufReader::new(file);
-    let dat_file: DatFile = quick_xml::de::from_reader(reader).map_err(|e| {
-        DatFileParserError::ParseError(format!("Failed parsing file {:?}: {}", path, e))
-    })?;
+    };
+    let file = File::open(path).map_err(io_error)?;
+    let mut reader = BufReader::new(file);
+    if reader
+        .fill_buf()
+        .map_err(io_error)?
+        .starts_with(&GZIP_MAGIC)
+    {
+        Ok(Box::new(BufReader::new(flate2::read::GzDecoder::new(
+            reader,
+        ))))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Read the name of the root element to detect the DAT format.
+fn read_root_element(path: &Path) -> Result<String, DatFileParserError> {
+    let mut reader = quick_xml::Reader::from_reader(open_reader(path)?);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(element)) | Ok(Event::Empty(element)) => {
+                return Ok(String::from_utf8_lossy(element.local_name().as_ref()).to_string());
+            }
+            Ok(Event::Eof) => {
+                return Err(DatFileParserError::ParseError(format!(
+                    "No root element in file {:?}",
+                    path
+                )));
+            }
+            Ok(_) => buf.clear(),
+            Err(e) => return Err(parse_error(path, e, reader.error_position())),
+        }
+    }
+}
+
+/// Parse a No-Intro/Logiqx `datafile` or a MAME `-listxml` file, detected by the root element.
+pub fn parse_dat_file(path: &Path) -> Result<DatFile, DatFileParserError> {
+    let root_element = read_root_element(path)?;
+    let mut deserializer = quick_xml::de::Deserializer::from_reader(open_reader(path)?);
+    let dat_file = match root_element.as_str() {
+        "mame" => MameListXml::deserialize(&mut deserializer).map(DatFile::from),
+        _ => DatFile::deserialize(&mut deserializer),
+    }
+    .map_err(|e| parse_error(path, e, error_position(deserializer.get_ref().get_ref())))?;
     Ok(dat_file)
 }
 
+/// Maximum length of the snippet of the offending input included in parse errors.
+const ERROR_SNIPPET_LEN: usize = 80;
+
+/// Position to report for an error of the reader: the start of the markup where a syntax error
+/// was found, or the current position for errors found by the deserializer.
+fn error_position<R>(reader: &quick_xml::Reader<R>) -> u64 {
+    match reader.error_position() {
+        0 => reader.buffer_position(),
+        position => position,
+    }
+}
+
+/// Create a parse error with the line, column and a snippet of the input at the byte position.
+fn parse_error(path: &Path, error: impl Display, position: u64) -> DatFileParserError {
+    let location = match locate(path, position) {
+        Some((line, column, snippet)) => format!(
+            "line {}, column {} (byte {}) near `{}`",
+            line, column, position, snippet
+        ),
+        None => format!("byte {}", position),
+    };
+    DatFileParserError::ParseError(format!(
+        "Failed parsing file {:?} at {}: {}",
+        path, location, error
+    ))
+}
+
+/// Find the 1-based line and column of the byte position and the input from the position to the
+/// end of the line. The file is read again, so this should only be used for reporting errors.
+fn locate(path: &Path, position: u64) -> Option<(usize, usize, String)> {
+    let mut content = Vec::new();
+    open_reader(path)
+        .ok()?
+        .take(position + ERROR_SNIPPET_LEN as u64)
+        .read_to_end(&mut content)
+        .ok()?;
+    let position = usize::try_from(position).ok()?.min(content.len());
+    let (before, after) = content.split_at(position);
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .chars()
+        .count()
+        + 1;
+    let snippet_end = after
+        .iter()
+        .position(|&b| b == b'\n')
+        .unwrap_or(after.len());
+    let snippet = String::from_utf8_lossy(&after[..snippet_end])
+        .trim()
+        .to_string();
+    Some((line, column, snippet))
+}
+
 impl From<DatGame> for domain::naming_conventions::no_intro::DatGame {
     fn from(dat_game: DatGame) -> Self {
         domain::naming_conventions::no_intro::DatGame {
@@ -150,6 +338,7 @@ impl From<DatGame> for domain::naming_conventions::no_intro::DatGame {
             categories: dat_game.categories,
             description: dat_game.description,
             roms: dat_game.roms.into_iter().map(|rom| rom.into()).collect(),
+            disks: dat_game.disks.into_iter().map(|disk| disk.into()).collect(),
             releases: dat_game
                 .releases
                 .into_iter()
@@ -169,11 +358,13 @@ impl From<domain::naming_conventions::no_intro::DatGame> for DatGame {
             categories: dat_game.categories,
             description: dat_game.description,
             roms: dat_game.roms.into_iter().map(|rom| rom.into()).collect(),
+            disks: dat_game.disks.into_iter().map(|disk| disk.into()).collect(),
             releases: dat_game
                 .releases
                 .into_iter()
                 .map(|release| release.into())
                 .collect(),
+            ..Default::default()
         }
     }
 }
@@ -210,6 +401,26 @@ impl From<domain::naming_conventions::no_intro::DatRom> for DatRom {
     }
 }
 
+impl From<DatDisk> for domain::naming_conventions::no_intro::DatDisk {
+    fn from(dat_disk: DatDisk) -> Self {
+        domain::naming_conventions::no_intro::DatDisk {
+            name: dat_disk.name,
+            sha1: dat_disk.sha1,
+            status: dat_disk.status,
+        }
+    }
+}
+
+impl From<domain::naming_conventions::no_intro::DatDisk> for DatDisk {
+    fn from(dat_disk: domain::naming_conventions::no_intro::DatDisk) -> Self {
+        DatDisk {
+            name: dat_disk.name,
+            sha1: dat_disk.sha1,
+            status: dat_disk.status,
+        }
+    }
+}
+
 impl From<DatRelease> for domain::naming_conventions::no_intro::DatRelease {
     fn from(dat_release: DatRelease) -> Self {
         domain::naming_conventions::no_intro::DatRelease {
@@ -308,4 +519,91 @@ mod tests {
         let dat_converted_back: DatFile = domain_dat.into();
         assert_eq!(dat, dat_converted_back);
     }
+
+    #[test]
+    fn test_parse_gzip_compressed_dat() {
+        let dat = parse_dat_file(Path::new("example-data/coleco.dat")).unwrap();
+
+        let gzip_dat = parse_dat_file(Path::new("example-data/coleco.dat.gz")).unwrap();
+
+        assert_eq!(gzip_dat, dat);
+    }
+
+    #[test]
+    fn test_parse_mame_listxml() {
+        let dat = parse_dat_file(Path::new("example-data/mame_listxml.xml")).unwrap();
+
+        assert_eq!(dat.header.name, "MAME");
+        assert_eq!(dat.header.version, "0.276 (mame0276)");
+        assert_eq!(dat.games.len(), 4);
+
+        let bios = &dat.games[0];
+        assert_eq!(bios.name, "neogeo");
+        assert!(bios.is_bios);
+
+        let clone = dat.games.iter().find(|game| game.name == "pacman").unwrap();
+        assert!(!clone.is_bios);
+        assert_eq!(clone.cloneof, Some("puckman".to_string()));
+        assert_eq!(clone.romof, Some("puckman".to_string()));
+        assert_eq!(clone.description, "Pac-Man (Midway)");
+        assert_eq!(clone.roms.len(), 2);
+        assert_eq!(clone.roms[0].crc, "c1e6ab10");
+        assert_eq!(clone.roms[0].md5, "");
+
+        let neogeo_game = dat.games.iter().find(|game| game.name == "mslug").unwrap();
+        assert_eq!(neogeo_game.cloneof, None);
+        assert_eq!(neogeo_game.romof, Some("neogeo".to_string()));
+    }
+
+    #[test]
+    fn test_parse_disks() {
+        let dat = parse_dat_file(Path::new("example-data/arcade_chd.dat")).unwrap();
+
+        let game = &dat.games[0];
+        assert_eq!(game.roms.len(), 1);
+        assert_eq!(
+            game.disks,
+            vec![DatDisk {
+                name: "kinst".to_string(),
+                sha1: "81d833236e994528d1482979261401b198d1ca53".to_string(),
+                status: None,
+            }]
+        );
+        let undumped = &dat.games[1].disks[0];
+        assert_eq!(undumped.sha1, "");
+        assert_eq!(undumped.status, Some("nodump".to_string()));
+
+        let domain_dat: domain::naming_conventions::no_intro::DatFile = dat.clone().into();
+        assert_eq!(domain_dat.games[0].disks[0].name, "kinst");
+        let dat_converted_back: DatFile = domain_dat.into();
+        assert_eq!(dat, dat_converted_back);
+    }
+
+    #[test]
+    fn test_stats() {
+        let dat = parse_dat_file(Path::new("example-data/coleco.dat")).unwrap();
+
+        let stats = dat.stats();
+
+        assert!(stats.game_count > 0);
+        assert_eq!(stats.game_count, dat.games.len());
+        let roms: Vec<&DatRom> = dat.games.iter().flat_map(|game| &game.roms).collect();
+        assert_eq!(stats.rom_count, roms.len());
+        assert_eq!(
+            stats.total_declared_size,
+            roms.iter().map(|rom| rom.size).sum::<u64>()
+        );
+        assert_eq!(stats.subset, None);
+    }
+
+    #[test]
+    fn test_parse_error_reports_position() {
+        let result = parse_dat_file(Path::new("example-data/truncated.dat"));
+
+        let Err(DatFileParserError::ParseError(message)) = result else {
+            panic!("Expected a parse error, got {:?}", result);
+        };
+        assert!(message.contains("line 15, column 3"), "{}", message);
+        assert!(message.contains("<rom name=\"Broken"), "{}", message);
+    }
 }
@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use core_types::{ReadFile, sha1_bytes_to_hex_string};
+
+use crate::DatIndex;
+
+/// Result of matching a set of files against a game in a DAT file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameMatch {
+    pub game_name: String,
+    /// Names of the game's roms found from the files
+    pub matched_roms: Vec<String>,
+    /// Names of the game's roms not found from the files
+    pub missing_roms: Vec<String>,
+    /// Names of the files not matching any rom of the game
+    pub extra_files: Vec<String>,
+}
+
+impl GameMatch {
+    /// All roms of the game were found and there were no other files.
+    pub fn is_complete(&self) -> bool {
+        self.missing_roms.is_empty() && self.extra_files.is_empty()
+    }
+}
+
+/// Match files against the games of the index by SHA1 checksum.
+///
+/// Returns a match for each game having at least one of the files, best match first: games with
+/// the most matched roms, then the fewest missing roms and then the fewest extra files.
+/// `ReadFile` doesn't have a CRC checksum, so files are matched by SHA1 only.
+pub fn match_files(read_files: &[ReadFile], index: &DatIndex) -> Vec<GameMatch> {
+    // game index -> (indices of the matched roms, indices of the matching files)
+    let mut matches: BTreeMap<usize, (BTreeSet<usize>, BTreeSet<usize>)> = BTreeMap::new();
+    for (file_index, read_file) in read_files.iter().enumerate() {
+        let sha1 = sha1_bytes_to_hex_string(&read_file.sha1_checksum);
+        for &(game_index, rom_index) in index.find_by_sha1(&sha1) {
+            let (matched_roms, matched_files) = matches.entry(game_index).or_default();
+            matched_roms.insert(rom_index);
+            matched_files.insert(file_index);
+        }
+    }
+
+    let mut game_matches: Vec<GameMatch> = matches
+        .into_iter()
+        .filter_map(|(game_index, (rom_indices, file_indices))| {
+            let game = index.game(game_index)?;
+            let (matched_roms, missing_roms) = game
+                .roms
+                .iter()
+                .enumerate()
+                .partition::<Vec<_>, _>(|(rom_index, _)| rom_indices.contains(rom_index));
+            Some(GameMatch {
+                game_name: game.name.clone(),
+                matched_roms: matched_roms
+                    .into_iter()
+                    .map(|(_, rom)| rom.name.clone())
+                    .collect(),
+                missing_roms: missing_roms
+                    .into_iter()
+                    .map(|(_, rom)| rom.name.clone())
+                    .collect(),
+                extra_files: read_files
+                    .iter()
+                    .enumerate()
+                    .filter(|(file_index, _)| !file_indices.contains(file_index))
+                    .map(|(_, read_file)| read_file.file_name.clone())
+                    .collect(),
+            })
+        })
+        .collect();
+
+    game_matches.sort_by(|a, b| {
+        b.matched_roms
+            .len()
+            .cmp(&a.matched_roms.len())
+            .then(a.missing_roms.len().cmp(&b.missing_roms.len()))
+            .then(a.extra_files.len().cmp(&b.extra_files.len()))
+    });
+    game_matches
+}
+
+#[cfg(test)]
+mod tests {
+    use core_types::sha1_from_hex_string;
+
+    use super::*;
+    use crate::{DatGame, DatRom};
+
+    const SHA1_A: &str = "1111111111111111111111111111111111111111";
+    const SHA1_B: &str = "2222222222222222222222222222222222222222";
+    const SHA1_C: &str = "3333333333333333333333333333333333333333";
+    const SHA1_UNKNOWN: &str = "4444444444444444444444444444444444444444";
+
+    fn rom(name: &str, sha1: &str) -> DatRom {
+        DatRom {
+            name: name.to_string(),
+            sha1: sha1.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn read_file(file_name: &str, sha1: &str) -> ReadFile {
+        ReadFile {
+            file_name: file_name.to_string(),
+            sha1_checksum: sha1_from_hex_string(sha1).unwrap(),
+            file_size: 0,
+        }
+    }
+
+    fn test_index() -> DatIndex {
+        let mut index = DatIndex::default();
+        index.add_game(DatGame {
+            name: "Two Disk Game".to_string(),
+            roms: vec![rom("disk1.dsk", SHA1_A), rom("disk2.dsk", SHA1_B)],
+            ..Default::default()
+        });
+        index.add_game(DatGame {
+            name: "Other Game".to_string(),
+            roms: vec![rom("other.dsk", SHA1_C)],
+            ..Default::default()
+        });
+        index
+    }
+
+    #[test]
+    fn test_full_match() {
+        let files = vec![read_file("a.dsk", SHA1_A), read_file("b.dsk", SHA1_B)];
+
+        let matches = match_files(&files, &test_index());
+
+        assert_eq!(
+            matches,
+            vec![GameMatch {
+                game_name: "Two Disk Game".to_string(),
+                matched_roms: vec!["disk1.dsk".to_string(), "disk2.dsk".to_string()],
+                missing_roms: vec![],
+                extra_files: vec![],
+            }]
+        );
+        assert!(matches[0].is_complete());
+    }
+
+    #[test]
+    fn test_partial_match() {
+        let files = vec![
+            read_file("a.dsk", SHA1_A),
+            read_file("unknown.dsk", SHA1_UNKNOWN),
+        ];
+
+        let matches = match_files(&files, &test_index());
+
+        assert_eq!(
+            matches,
+            vec![GameMatch {
+                game_name: "Two Disk Game".to_string(),
+                matched_roms: vec!["disk1.dsk".to_string()],
+                missing_roms: vec!["disk2.dsk".to_string()],
+                extra_files: vec!["unknown.dsk".to_string()],
+            }]
+        );
+        assert!(!matches[0].is_complete());
+    }
+}
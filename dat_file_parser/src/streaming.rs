@@ -0,0 +1,111 @@
+use std::fmt;
+use std::path::Path;
+
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+
+use crate::{
+    DatFileParserError, DatGame, DatHeader, error_position, mame_header, open_reader, parse_error,
+    read_root_element,
+};
+
+/// Parse a DAT file one game at a time, calling `on_game` for each game in file order.
+///
+/// Unlike [`crate::parse_dat_file`], the games are not collected to memory, which allows
+/// processing DAT files too large to be loaded at once. Returns the header of the file, which is
+/// parsed eagerly when encountered.
+pub fn parse_dat_file_streaming(
+    path: &Path,
+    on_game: impl FnMut(DatGame),
+) -> Result<DatHeader, DatFileParserError> {
+    let is_mame = read_root_element(path)? == "mame";
+    let mut deserializer = quick_xml::de::Deserializer::from_reader(open_reader(path)?);
+    let root = GameStream { on_game }
+        .deserialize(&mut deserializer)
+        .map_err(|e| parse_error(path, e, error_position(deserializer.get_ref().get_ref())))?;
+
+    match (root.header, is_mame) {
+        (Some(header), _) => Ok(header),
+        (None, true) => Ok(mame_header(root.build)),
+        (None, false) => Err(DatFileParserError::ParseError(format!(
+            "No header in file {:?}",
+            path
+        ))),
+    }
+}
+
+/// Root element content other than the games.
+struct StreamedRoot {
+    header: Option<DatHeader>,
+    build: Option<String>,
+}
+
+/// Visits the root element, passing each game to the callback instead of collecting them.
+struct GameStream<F> {
+    on_game: F,
+}
+
+impl<'de, F: FnMut(DatGame)> DeserializeSeed<'de> for GameStream<F> {
+    type Value = StreamedRoot;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(DatGame)> Visitor<'de> for GameStream<F> {
+    type Value = StreamedRoot;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a datafile or mame root element")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut root = StreamedRoot {
+            header: None,
+            build: None,
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "header" => root.header = Some(map.next_value()?),
+                "game" | "machine" => (self.on_game)(map.next_value()?),
+                "@build" => root.build = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_dat_file;
+
+    #[test]
+    fn test_callback_is_called_once_per_game() {
+        let path = Path::new("example-data/coleco.dat");
+        let mut games = Vec::new();
+
+        let header = parse_dat_file_streaming(path, |game| games.push(game)).unwrap();
+
+        let dat = parse_dat_file(path).unwrap();
+        assert_eq!(header, dat.header);
+        assert_eq!(games.len(), 201);
+        assert_eq!(games, dat.games);
+    }
+
+    #[test]
+    fn test_streaming_mame_listxml() {
+        let mut game_names = Vec::new();
+
+        let header = parse_dat_file_streaming(Path::new("example-data/mame_listxml.xml"), |game| {
+            game_names.push(game.name)
+        })
+        .unwrap();
+
+        assert_eq!(header.version, "0.276 (mame0276)");
+        assert_eq!(game_names, vec!["neogeo", "mslug", "puckman", "pacman"]);
+    }
+}
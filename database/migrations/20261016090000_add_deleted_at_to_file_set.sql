@@ -0,0 +1,4 @@
+-- Soft-delete support for file sets. A non-NULL deleted_at marks the file set as moved to trash;
+-- it's hidden from listings until it's either restored (deleted_at set back to NULL) or purged
+-- after the trash retention period has passed.
+ALTER TABLE file_set ADD COLUMN deleted_at TIMESTAMP;
@@ -266,6 +266,27 @@ pub struct DatRom {
     pub header: Option<String>,
 }
 
+/// A page of a list query fetched with `limit` and `offset`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of items matching the query on all pages
+    pub total_count: i64,
+    /// Offset of the next page, `None` on the last page
+    pub next_cursor: Option<i64>,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total_count: i64, offset: i64) -> Self {
+        let next_offset = offset + items.len() as i64;
+        Self {
+            next_cursor: (!items.is_empty() && next_offset < total_count).then_some(next_offset),
+            items,
+            total_count,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,6 +303,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_page_next_cursor() {
+        let page = Page::new(vec![1, 2], 5, 0);
+        assert_eq!(page.next_cursor, Some(2));
+
+        let page = Page::new(vec![5], 5, 4);
+        assert_eq!(page.next_cursor, None);
+
+        let page: Page<i64> = Page::new(vec![], 5, 10);
+        assert_eq!(page.next_cursor, None);
+    }
+
     #[test]
     fn test_file_info_is_available_returns_true_when_archive_file_name_set() {
         assert!(make_file_info(Some("game.zst")).is_available());
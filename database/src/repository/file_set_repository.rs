@@ -1,12 +1,13 @@
 use std::{collections::HashSet, sync::Arc};
 
+use chrono::NaiveDateTime;
 use core_types::{CloudSyncStatus, FileSetEqualitySpecs, FileType, ImportedFile, Sha1Checksum, item_type::ItemType};
 use sqlx::{FromRow, Pool, Row, Sqlite, sqlite::SqliteRow};
 
 use crate::{
     database_error::{DatabaseError, Error},
     helper::AddFileSetParams,
-    models::{FileSet, FileSetFileInfo},
+    models::{FileSet, FileSetFileInfo, Page},
 };
 
 pub struct FindFileSetResult {
@@ -206,13 +207,42 @@ impl FileSetRepository {
     pub async fn get_all_file_sets(&self) -> Result<Vec<FileSet>, DatabaseError> {
         let file_sets = sqlx::query_as(
             "SELECT id, file_name, file_type, name, source 
-             FROM file_set",
+             FROM file_set
+             WHERE deleted_at IS NULL",
         )
         .fetch_all(&*self.pool)
         .await?;
         Ok(file_sets)
     }
 
+    /// Get a page of file sets ordered by name. Use `get_all_file_sets` for small internal uses
+    /// where all file sets are needed.
+    pub async fn get_file_sets_page(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<FileSet>, DatabaseError> {
+        // Count and page are read in the same transaction to get a consistent total.
+        let mut transaction = self.pool.begin().await?;
+        let total_count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM file_set WHERE deleted_at IS NULL")
+                .fetch_one(&mut *transaction)
+                .await?;
+        let file_sets = sqlx::query_as(
+            "SELECT id, file_name, file_type, name, source
+             FROM file_set
+             WHERE deleted_at IS NULL
+             ORDER BY name, id
+             LIMIT ? OFFSET ?",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(Page::new(file_sets, total_count, offset))
+    }
+
     pub async fn get_file_sets_by_file_type_and_systems(
         &self,
         file_type: FileType,
@@ -230,7 +260,7 @@ impl FileSetRepository {
              FROM file_set fs
              INNER JOIN file_set_file_info fsfi ON fs.id = fsfi.file_set_id
              INNER JOIN file_info_system fis ON fsfi.file_info_id = fis.file_info_id
-             WHERE fs.file_type = ? AND fis.system_id IN ({})",
+             WHERE fs.file_type = ? AND fs.deleted_at IS NULL AND fis.system_id IN ({})",
             placeholders
         );
         let mut query_builder =
@@ -252,7 +282,7 @@ impl FileSetRepository {
         let matching_file_sets = sqlx::query_scalar!(
             "SELECT id 
                  FROM file_set 
-                 WHERE name = ? AND file_name = ? AND file_type = ?",
+                 WHERE name = ? AND file_name = ? AND file_type = ? AND deleted_at IS NULL",
             equality_specs.file_set_name,
             equality_specs.file_set_file_name,
             file_type
@@ -636,6 +666,66 @@ impl FileSetRepository {
         Ok(id)
     }
 
+    /// Marks the file set as deleted without removing it from the database. Soft-deleted file
+    /// sets are hidden from listings until they are restored or purged.
+    pub async fn soft_delete_file_set(&self, id: i64) -> Result<i64, DatabaseError> {
+        if self.is_in_use(id).await? {
+            return Err(DatabaseError::InUse);
+        }
+
+        sqlx::query!(
+            "UPDATE file_set 
+             SET deleted_at = CURRENT_TIMESTAMP 
+             WHERE id = ?",
+            id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// Clears the soft-delete mark from the file set, making it visible again.
+    pub async fn restore_file_set(&self, id: i64) -> Result<i64, DatabaseError> {
+        sqlx::query!(
+            "UPDATE file_set 
+             SET deleted_at = NULL 
+             WHERE id = ?",
+            id
+        )
+        .execute(&*self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// Returns all soft-deleted file sets.
+    pub async fn get_deleted_file_sets(&self) -> Result<Vec<FileSet>, DatabaseError> {
+        let file_sets = sqlx::query_as(
+            "SELECT id, file_name, file_type, name, source 
+             FROM file_set
+             WHERE deleted_at IS NOT NULL
+             ORDER BY deleted_at ASC",
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(file_sets)
+    }
+
+    /// Returns ids of file sets that were soft-deleted at or before the given time.
+    pub async fn get_file_set_ids_deleted_before(
+        &self,
+        cutoff: NaiveDateTime,
+    ) -> Result<Vec<i64>, DatabaseError> {
+        let ids = sqlx::query_scalar!(
+            "SELECT id 
+             FROM file_set 
+             WHERE deleted_at IS NOT NULL AND deleted_at <= ?",
+            cutoff
+        )
+        .fetch_all(&*self.pool)
+        .await?;
+        Ok(ids)
+    }
+
     pub async fn get_file_set_file_info(
         &self,
         file_set_id: i64,
@@ -1183,6 +1273,90 @@ mod tests {
         assert_eq!(result.unwrap_err(), DatabaseError::InUse);
     }
 
+    #[async_std::test]
+    async fn test_soft_delete_and_restore_file_set() {
+        let pool = Arc::new(setup_test_db().await);
+        let system_id = SystemRepository::new(pool.clone())
+            .add_system("Test System")
+            .await
+            .unwrap();
+        let files = vec![ImportedFile {
+            sha1_checksum: [0; 20],
+            file_size: 123,
+            original_file_name: "test".to_string(),
+            archive_file_name: Some("123e4567-e89b-12d3-a456-426614174001".to_string()),
+        }];
+        let repository = FileSetRepository { pool: pool.clone() };
+        let file_set_id = repository
+            .add_file_set(
+                "Test File Set",
+                "test file",
+                &FileType::Rom,
+                "",
+                &files,
+                &[system_id],
+            )
+            .await
+            .unwrap();
+
+        repository.soft_delete_file_set(file_set_id).await.unwrap();
+
+        assert!(repository.get_all_file_sets().await.unwrap().is_empty());
+        assert!(
+            repository
+                .get_file_sets_by_file_type_and_systems(FileType::Rom, &[system_id])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let deleted = repository.get_deleted_file_sets().await.unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].id, file_set_id);
+        // file links are kept so that the file set can be restored
+        assert_eq!(
+            repository
+                .get_file_set_file_info(file_set_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        repository.restore_file_set(file_set_id).await.unwrap();
+
+        assert_eq!(repository.get_all_file_sets().await.unwrap().len(), 1);
+        assert!(repository.get_deleted_file_sets().await.unwrap().is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_get_file_set_ids_deleted_before() {
+        let pool = Arc::new(setup_test_db().await);
+        let repository = FileSetRepository { pool: pool.clone() };
+        let file_set_id = repository
+            .add_file_set("Test File Set", "test file", &FileType::Rom, "", &[], &[])
+            .await
+            .unwrap();
+        repository.soft_delete_file_set(file_set_id).await.unwrap();
+
+        let past = chrono::Utc::now().naive_utc() - chrono::Duration::days(1);
+        let future = chrono::Utc::now().naive_utc() + chrono::Duration::days(1);
+
+        assert!(
+            repository
+                .get_file_set_ids_deleted_before(past)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            repository
+                .get_file_set_ids_deleted_before(future)
+                .await
+                .unwrap(),
+            vec![file_set_id]
+        );
+    }
+
     #[async_std::test]
     async fn test_cascade_delete_when_release_deleted() {
         let pool = setup_test_db().await;
@@ -2103,4 +2277,58 @@ mod tests {
         // Assert
         assert!(found_id.is_none());
     }
+
+    #[async_std::test]
+    async fn test_get_file_sets_page() {
+        let pool = Arc::new(setup_test_db().await);
+        let file_set_repository = FileSetRepository::new(pool.clone());
+        let mut file_set_ids = Vec::new();
+        for i in 1..=5 {
+            let file_set_id = file_set_repository
+                .add_file_set(
+                    &format!("File Set {}", i),
+                    &format!("file_set_{}", i),
+                    &FileType::Rom,
+                    "Unit Test",
+                    &[],
+                    &[],
+                )
+                .await
+                .unwrap();
+            file_set_ids.push(file_set_id);
+        }
+        // Soft deleted file sets are not listed
+        file_set_repository
+            .soft_delete_file_set(file_set_ids[4])
+            .await
+            .unwrap();
+
+        let first_page = file_set_repository.get_file_sets_page(2, 0).await.unwrap();
+        assert_eq!(first_page.total_count, 4);
+        assert_eq!(first_page.next_cursor, Some(2));
+        assert_eq!(
+            first_page
+                .items
+                .iter()
+                .map(|file_set| file_set.id)
+                .collect::<Vec<_>>(),
+            file_set_ids[0..2]
+        );
+
+        let last_page = file_set_repository.get_file_sets_page(2, 2).await.unwrap();
+        assert_eq!(last_page.total_count, 4);
+        assert_eq!(last_page.next_cursor, None);
+        assert_eq!(
+            last_page
+                .items
+                .iter()
+                .map(|file_set| file_set.id)
+                .collect::<Vec<_>>(),
+            file_set_ids[2..4]
+        );
+
+        let past_end = file_set_repository.get_file_sets_page(2, 4).await.unwrap();
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.next_cursor, None);
+    }
 }
@@ -1,11 +1,11 @@
 use std::sync::Arc;
 
 use core_types::FileType;
-use sqlx::{Pool, Sqlite};
+use sqlx::{Pool, Sqlite, query::QueryAs, sqlite::SqliteArguments};
 
 use crate::{
     database_error::{DatabaseError, Error},
-    models::{Release, ReleaseExtended},
+    models::{Page, Release, ReleaseExtended},
 };
 
 #[derive(Debug)]
@@ -55,96 +55,63 @@ impl ReleaseRepository {
         software_title_ids: Vec<i64>,
         file_set_id: Option<i64>,
     ) -> Result<Vec<ReleaseExtended>, Error> {
-        let mut query = r#"
-            SELECT
-                r.id as id,
-                r.name as name,
-                r.thumbnail_filename as thumbnail_filename,
-                GROUP_CONCAT(DISTINCT s.name) as system_names,
-                GROUP_CONCAT(DISTINCT st.name) as software_title_names,
-                GROUP_CONCAT(DISTINCT fs.file_type) as file_types
-             FROM
-                release r
-             INNER JOIN
-                release_software_title rst ON r.id = rst.release_id
-             INNER JOIN
-                software_title st ON rst.software_title_id = st.id
-             INNER JOIN
-                release_system rs ON r.id = rs.release_id
-             INNER JOIN
-                system s ON rs.system_id = s.id
-             LEFT JOIN
-                release_file_set rfs ON r.id = rfs.release_id
-             LEFT JOIN
-                file_set fs ON rfs.file_set_id = fs.id
-            WHERE
-                (? IS NULL OR s.id = ?)
-                AND (? IS NULL OR fs.id = ?)
-        "#
-        .to_string();
+        let query = format!("{};", releases_query(&software_title_ids));
+        let q = bind_release_filters(
+            sqlx::query_as::<_, ReleaseExtendedRaw>(&query),
+            system_id,
+            &software_title_ids,
+            file_set_id,
+        );
 
-        if !software_title_ids.is_empty() {
-            let placeholders = software_title_ids
-                .iter()
-                .map(|_| "?".to_string())
-                .collect::<Vec<String>>()
-                .join(", ");
-            query.push_str(&format!(" AND st.id IN ({})", placeholders));
-        }
-
-        query.push_str(" GROUP BY r.id, r.name;");
+        let raw_releases = q.fetch_all(&*self.pool).await?;
 
-        let mut q = sqlx::query_as::<_, ReleaseExtendedRaw>(&query)
-            .bind(system_id)
-            .bind(system_id)
-            .bind(file_set_id)
-            .bind(file_set_id);
+        Ok(raw_releases
+            .into_iter()
+            .map(ReleaseExtended::from)
+            .collect())
+    }
 
-        if !software_title_ids.is_empty() {
-            for software_title_id in &software_title_ids {
-                q = q.bind(*software_title_id);
-            }
-        }
+    /// Get a page of releases ordered by name with the same filters as `get_releases`.
+    pub async fn get_releases_page(
+        &self,
+        system_id: Option<i64>,
+        software_title_ids: Vec<i64>,
+        file_set_id: Option<i64>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Page<ReleaseExtended>, Error> {
+        let query = releases_query(&software_title_ids);
 
-        let raw_releases = q.fetch_all(&*self.pool).await?;
+        // Count and page are read in the same transaction to get a consistent total.
+        let mut transaction = self.pool.begin().await?;
+        let count_query = format!("SELECT COUNT(*) FROM ({});", query);
+        let (total_count,) = bind_release_filters(
+            sqlx::query_as::<_, (i64,)>(&count_query),
+            system_id,
+            &software_title_ids,
+            file_set_id,
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
 
-        let mut releases: Vec<ReleaseExtended> = Vec::new();
-
-        for raw in raw_releases {
-            dbg!("Raw release: {}", &raw);
-            let system_names = raw
-                .system_names
-                .unwrap_or_default()
-                .split(',')
-                .map(String::from)
-                .collect();
-            let software_title_names = raw
-                .software_title_names
-                .unwrap_or_default()
-                .split(',')
-                .map(String::from)
-                .collect();
-            let file_types: Vec<FileType> = raw
-                .file_types
-                .unwrap_or_default()
-                .split(',')
-                .filter(|s| !s.is_empty())
-                .map(|ft| {
-                    let int_ft: u8 = ft.parse().expect("Failed to parse file type as u8");
-                    FileType::from_db_int(int_ft).expect("Invalid file type")
-                })
-                .collect();
-            releases.push(ReleaseExtended {
-                id: raw.id,
-                name: raw.name,
-                thumbnail_filename: raw.thumbnail_filename,
-                system_names,
-                software_title_names,
-                file_types,
-            });
-        }
+        let page_query = format!("{} ORDER BY r.name, r.id LIMIT ? OFFSET ?;", query);
+        let raw_releases = bind_release_filters(
+            sqlx::query_as::<_, ReleaseExtendedRaw>(&page_query),
+            system_id,
+            &software_title_ids,
+            file_set_id,
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
 
-        Ok(releases)
+        let releases = raw_releases
+            .into_iter()
+            .map(ReleaseExtended::from)
+            .collect();
+        Ok(Page::new(releases, total_count, offset))
     }
 
     pub async fn get_releases_by_software_title_with_tx(
@@ -515,6 +482,102 @@ impl ReleaseRepository {
     }
 }
 
+/// Query of releases with the system, software title and file set filters, see
+/// `bind_release_filters` for binding them.
+fn releases_query(software_title_ids: &[i64]) -> String {
+    let mut query = r#"
+            SELECT
+                r.id as id,
+                r.name as name,
+                r.thumbnail_filename as thumbnail_filename,
+                GROUP_CONCAT(DISTINCT s.name) as system_names,
+                GROUP_CONCAT(DISTINCT st.name) as software_title_names,
+                GROUP_CONCAT(DISTINCT fs.file_type) as file_types
+             FROM
+                release r
+             INNER JOIN
+                release_software_title rst ON r.id = rst.release_id
+             INNER JOIN
+                software_title st ON rst.software_title_id = st.id
+             INNER JOIN
+                release_system rs ON r.id = rs.release_id
+             INNER JOIN
+                system s ON rs.system_id = s.id
+             LEFT JOIN
+                release_file_set rfs ON r.id = rfs.release_id
+             LEFT JOIN
+                file_set fs ON rfs.file_set_id = fs.id
+            WHERE
+                (? IS NULL OR s.id = ?)
+                AND (? IS NULL OR fs.id = ?)
+        "#
+    .to_string();
+
+    if !software_title_ids.is_empty() {
+        let placeholders = software_title_ids
+            .iter()
+            .map(|_| "?".to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        query.push_str(&format!(" AND st.id IN ({})", placeholders));
+    }
+
+    query.push_str(" GROUP BY r.id, r.name");
+    query
+}
+
+fn bind_release_filters<'q, O>(
+    query: QueryAs<'q, Sqlite, O, SqliteArguments<'q>>,
+    system_id: Option<i64>,
+    software_title_ids: &[i64],
+    file_set_id: Option<i64>,
+) -> QueryAs<'q, Sqlite, O, SqliteArguments<'q>> {
+    let mut query = query
+        .bind(system_id)
+        .bind(system_id)
+        .bind(file_set_id)
+        .bind(file_set_id);
+    for software_title_id in software_title_ids {
+        query = query.bind(*software_title_id);
+    }
+    query
+}
+
+impl From<ReleaseExtendedRaw> for ReleaseExtended {
+    fn from(raw: ReleaseExtendedRaw) -> Self {
+        let system_names = raw
+            .system_names
+            .unwrap_or_default()
+            .split(',')
+            .map(String::from)
+            .collect();
+        let software_title_names = raw
+            .software_title_names
+            .unwrap_or_default()
+            .split(',')
+            .map(String::from)
+            .collect();
+        let file_types: Vec<FileType> = raw
+            .file_types
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(|ft| {
+                let int_ft: u8 = ft.parse().expect("Failed to parse file type as u8");
+                FileType::from_db_int(int_ft).expect("Invalid file type")
+            })
+            .collect();
+        ReleaseExtended {
+            id: raw.id,
+            name: raw.name,
+            thumbnail_filename: raw.thumbnail_filename,
+            system_names,
+            software_title_names,
+            file_types,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core_types::{ImportedFile, Sha1Checksum};
@@ -981,6 +1044,75 @@ mod tests {
         assert_eq!(release_exists, 1);
     }
 
+    #[async_std::test]
+    async fn test_get_releases_page() {
+        let pool = Arc::new(setup_test_db().await);
+        let release_repository = ReleaseRepository::new(pool.clone());
+        let software_title_repository = SoftwareTitleRepository::new(pool.clone());
+        let system_repository = SystemRepository::new(pool.clone());
+        let system_id = system_repository.add_system("Test System").await.unwrap();
+        let system_id_2 = system_repository.add_system("Test System 2").await.unwrap();
+        let software_title_id = software_title_repository
+            .add_software_title("Test Software Title", None)
+            .await
+            .unwrap();
+        for i in 1..=5 {
+            release_repository
+                .add_release_full(
+                    &format!("Release {}", i),
+                    &[software_title_id],
+                    &[],
+                    &[system_id],
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+        release_repository
+            .add_release_full(
+                "Release on other system",
+                &[software_title_id],
+                &[],
+                &[system_id_2],
+                None,
+            )
+            .await
+            .unwrap();
+
+        let first_page = release_repository
+            .get_releases_page(Some(system_id), vec![], None, 2, 0)
+            .await
+            .unwrap();
+        assert_eq!(first_page.total_count, 5);
+        assert_eq!(first_page.next_cursor, Some(2));
+        assert_eq!(
+            first_page
+                .items
+                .iter()
+                .map(|release| release.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Release 1", "Release 2"]
+        );
+
+        let last_page = release_repository
+            .get_releases_page(Some(system_id), vec![], None, 2, 4)
+            .await
+            .unwrap();
+        assert_eq!(last_page.total_count, 5);
+        assert_eq!(last_page.next_cursor, None);
+        assert_eq!(last_page.items.len(), 1);
+        assert_eq!(last_page.items[0].name, "Release 5");
+        assert_eq!(last_page.items[0].system_names, vec!["Test System"]);
+
+        let all_systems = release_repository
+            .get_releases_page(None, vec![software_title_id], None, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(all_systems.total_count, 6);
+        assert_eq!(all_systems.items.len(), 6);
+        assert_eq!(all_systems.next_cursor, None);
+    }
+
     #[async_std::test]
     async fn test_get_releases() {
         let file_1_sha: Sha1Checksum = [0; 20];
@@ -0,0 +1,175 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::{DEFAULT_ARTICLES, extract_tags, move_trailing_article};
+
+/// Name following the GoodTools naming convention, e.g. `Super Game (U) (M3) [T+Eng1.0] [b1]`.
+///
+/// Parenthetical tags hold the country and multi-language codes, square bracket codes hold the
+/// dump info.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GoodToolsName {
+    pub name: String,
+    pub title: String,
+    /// Parenthetical tags, e.g. `U`, `M3` or `PD`
+    pub tags: Vec<String>,
+    /// Square bracket codes, e.g. `!`, `b1` or `T+Eng1.0`
+    pub codes: Vec<String>,
+    /// Verified good dump, `[!]`
+    pub good_dump: bool,
+    /// Bad dump, `[b]` or numbered `[b1]`
+    pub bad_dump: bool,
+    /// Overdump, `[o]` or numbered `[o1]`
+    pub overdump: bool,
+    /// Hack, `[h]` and its variants like `[h1]` or `[hI]`
+    pub hack: bool,
+    /// Translation, e.g. `Eng1.0` from `[T+Eng1.0]`
+    pub translation: Option<String>,
+    /// Number of languages, e.g. 3 from `(M3)`
+    pub language_count: Option<u32>,
+}
+
+impl GoodToolsName {
+    /// Parse a GoodTools name. File extension is expected to be removed.
+    pub fn parse(name: &str) -> Self {
+        static TAG_RE: OnceLock<Regex> = OnceLock::new();
+        let tag_re = TAG_RE.get_or_init(|| Regex::new(r"\(([^)]+)\)").unwrap());
+        static CODE_RE: OnceLock<Regex> = OnceLock::new();
+        let code_re = CODE_RE.get_or_init(|| Regex::new(r"\[([^\]]+)\]").unwrap());
+        static LANGUAGE_COUNT_RE: OnceLock<Regex> = OnceLock::new();
+        let language_count_re = LANGUAGE_COUNT_RE.get_or_init(|| Regex::new(r"^M(\d+)$").unwrap());
+        static TRANSLATION_RE: OnceLock<Regex> = OnceLock::new();
+        let translation_re = TRANSLATION_RE.get_or_init(|| Regex::new(r"^T[+-](.+)$").unwrap());
+        static NUMBERED_RE: OnceLock<Regex> = OnceLock::new();
+        let numbered_re = NUMBERED_RE.get_or_init(|| Regex::new(r"^([bo])\d*$").unwrap());
+
+        let title = name
+            .split(['(', '['])
+            .next()
+            .unwrap_or(name)
+            .trim()
+            .to_string();
+
+        let tags = extract_tags(name, tag_re);
+        let language_count = tags
+            .iter()
+            .find_map(|tag| language_count_re.captures(tag)?[1].parse().ok());
+
+        let codes = extract_tags(name, code_re);
+        let has_numbered = |letter: &str| {
+            codes.iter().any(|code| {
+                numbered_re
+                    .captures(code)
+                    .is_some_and(|cap| &cap[1] == letter)
+            })
+        };
+
+        Self {
+            name: name.to_string(),
+            title,
+            tags,
+            good_dump: codes.iter().any(|code| code == "!"),
+            bad_dump: has_numbered("b"),
+            overdump: has_numbered("o"),
+            hack: codes.iter().any(|code| code.starts_with('h')),
+            translation: codes
+                .iter()
+                .find_map(|code| translation_re.captures(code).map(|cap| cap[1].to_string())),
+            language_count,
+            codes,
+        }
+    }
+
+    /// Formalize a software title name from the title. A trailing article of `DEFAULT_ARTICLES`
+    /// is moved to the start, e.g. "Title, The" is turned to "The Title".
+    pub fn get_software_title_name(&self) -> String {
+        move_trailing_article(&self.title, DEFAULT_ARTICLES)
+    }
+
+    /// Get the release name from the parenthetical tags and the square bracket codes.
+    pub fn get_release_name(&self) -> String {
+        self.tags
+            .iter()
+            .chain(&self.codes)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" - ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_good_dump() {
+        let name = GoodToolsName::parse("Super Mario Bros. (W) [!]");
+
+        assert_eq!(name.title, "Super Mario Bros.");
+        assert_eq!(name.tags, vec!["W"]);
+        assert!(name.good_dump);
+        assert!(!name.bad_dump);
+        assert!(!name.overdump);
+        assert!(!name.hack);
+        assert_eq!(name.translation, None);
+        assert_eq!(name.language_count, None);
+    }
+
+    #[test]
+    fn test_parse_bad_dump_and_overdump() {
+        let name = GoodToolsName::parse("Legend of Zelda, The (U) [b1][o2]");
+
+        assert!(name.bad_dump);
+        assert!(name.overdump);
+        assert!(!name.good_dump);
+        assert_eq!(name.codes, vec!["b1", "o2"]);
+    }
+
+    #[test]
+    fn test_parse_multi_language() {
+        let name = GoodToolsName::parse("Asterix (E) (M3) [!]");
+
+        assert_eq!(name.language_count, Some(3));
+        assert_eq!(name.tags, vec!["E", "M3"]);
+        assert!(name.good_dump);
+    }
+
+    #[test]
+    fn test_parse_translation_and_hack() {
+        let name = GoodToolsName::parse("Final Fantasy II (J) [T+Eng1.0][hI]");
+
+        assert_eq!(name.translation.as_deref(), Some("Eng1.0"));
+        assert!(name.hack);
+        assert!(!name.bad_dump);
+
+        let name = GoodToolsName::parse("Mother (J) [T-Eng]");
+        assert_eq!(name.translation.as_deref(), Some("Eng"));
+    }
+
+    #[test]
+    fn test_get_software_title_name() {
+        let test_cases = &[
+            ("Legend of Zelda, The (U) [b1]", "The Legend of Zelda"),
+            ("Asterix (E) (M3) [!]", "Asterix"),
+            ("Tetris [!]", "Tetris"),
+        ];
+        for (input, expected) in test_cases {
+            let name = GoodToolsName::parse(input);
+            assert_eq!(name.get_software_title_name(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_get_release_name() {
+        let test_cases = &[
+            ("Super Mario Bros. (W) [!]", "W - !"),
+            ("Asterix (E) (M3) [b1]", "E - M3 - b1"),
+            ("Final Fantasy II (J) [T+Eng1.0]", "J - T+Eng1.0"),
+        ];
+        for (input, expected) in test_cases {
+            let name = GoodToolsName::parse(input);
+            assert_eq!(name.get_release_name(), *expected);
+        }
+    }
+}
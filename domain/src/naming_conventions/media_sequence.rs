@@ -0,0 +1,95 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    Disk,
+    Tape,
+    Side,
+}
+
+/// Position of a medium in a multi-disk or multi-tape release, e.g. `Disk 1 of 3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MediaSequence {
+    /// 1-based index of the medium. For a `Side` it's the position of the side letter, `A` = 1.
+    pub index: u32,
+    /// Number of media in the release, `None` when the name doesn't tell it, e.g. for `Side A`
+    pub total: Option<u32>,
+    pub kind: MediaKind,
+    /// Side letter of the medium, e.g. `A` in `Disk 1 of 2 Side A` or `Side A`
+    pub side: Option<char>,
+}
+
+impl MediaSequence {
+    /// Parse a tag like `Disk 1 of 3`, `Tape 2 of 2`, `Disk 1 of 2 Side A` or `Side A`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        static SEQUENCE_RE: OnceLock<Regex> = OnceLock::new();
+        let sequence_re = SEQUENCE_RE.get_or_init(|| {
+            Regex::new(r"^(Disk|Disc|Tape) (\d+) of (\d+)(?: Side ([A-Z]))?$").unwrap()
+        });
+        static SIDE_RE: OnceLock<Regex> = OnceLock::new();
+        let side_re = SIDE_RE.get_or_init(|| Regex::new(r"^Side ([A-Z])$").unwrap());
+
+        let tag = tag.trim();
+        if let Some(cap) = sequence_re.captures(tag) {
+            let kind = match &cap[1] {
+                "Tape" => MediaKind::Tape,
+                _ => MediaKind::Disk,
+            };
+            return Some(Self {
+                index: cap[2].parse().ok()?,
+                total: Some(cap[3].parse().ok()?),
+                kind,
+                side: cap.get(4).and_then(|side| side.as_str().chars().next()),
+            });
+        }
+        let side = side_re.captures(tag)?[1].chars().next()?;
+        Some(Self {
+            index: side as u32 - 'A' as u32 + 1,
+            total: None,
+            kind: MediaKind::Side,
+            side: Some(side),
+        })
+    }
+
+    /// Find the first tag with media sequence info.
+    pub fn from_tags<S: AsRef<str>>(tags: &[S]) -> Option<Self> {
+        tags.iter().find_map(|tag| Self::from_tag(tag.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_tag() {
+        let test_cases = &[
+            ("Disk 1 of 3", Some((1, Some(3), MediaKind::Disk, None))),
+            ("Disc 2 of 2", Some((2, Some(2), MediaKind::Disk, None))),
+            ("Tape 2 of 2", Some((2, Some(2), MediaKind::Tape, None))),
+            (
+                "Disk 1 of 2 Side A",
+                Some((1, Some(2), MediaKind::Disk, Some('A'))),
+            ),
+            (
+                "Disk 2 of 2 Side B",
+                Some((2, Some(2), MediaKind::Disk, Some('B'))),
+            ),
+            ("Side A", Some((1, None, MediaKind::Side, Some('A')))),
+            ("Side C", Some((3, None, MediaKind::Side, Some('C')))),
+            ("USA, Europe", None),
+            ("Disk 1", None),
+        ];
+        for (tag, expected) in test_cases {
+            let expected = expected.map(|(index, total, kind, side)| MediaSequence {
+                index,
+                total,
+                kind,
+                side,
+            });
+            assert_eq!(MediaSequence::from_tag(tag), expected, "{}", tag);
+        }
+    }
+}
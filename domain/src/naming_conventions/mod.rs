@@ -1 +1,71 @@
+pub mod goodtools;
+pub mod media_sequence;
 pub mod no_intro;
+pub mod tosec;
+
+use regex::Regex;
+
+/// Articles moved from the end of a title to the start by default, e.g. "Brief, Der" ->
+/// "Der Brief". English, German, French, Spanish, Italian and Dutch articles are included.
+pub const DEFAULT_ARTICLES: &[&str] = &[
+    "The", "A", "An", // English
+    "Der", "Die", "Das", "Ein", "Eine", // German
+    "Le", "La", "Les", "L'", "Un", "Une", // French
+    "El", "Los", "Las", "Una", // Spanish
+    "Il", "Lo", "Gli", // Italian
+    "De", "Het", "Een", // Dutch
+];
+
+/// Move an article from the end of the title to the start: "Title, The" -> "The Title". The
+/// article is matched case-insensitively and written as in `articles`. Titles without a trailing
+/// article are returned as is.
+pub fn move_trailing_article(title: &str, articles: &[&str]) -> String {
+    let Some((base, article)) = title.rsplit_once(", ") else {
+        return title.to_string();
+    };
+    match articles
+        .iter()
+        .find(|candidate| candidate.eq_ignore_ascii_case(article.trim()))
+    {
+        // Elided articles are joined without a space, e.g. "L'Aventure"
+        Some(article) if article.ends_with('\'') => format!("{}{}", article, base),
+        Some(article) => format!("{} {}", article, base),
+        None => title.to_string(),
+    }
+}
+
+/// Get the trimmed contents of the first capture group of every match, e.g. the tags inside
+/// parentheses of a name.
+pub(crate) fn extract_tags(input: &str, re: &Regex) -> Vec<String> {
+    re.captures_iter(input)
+        .map(|cap| cap[1].trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_trailing_article() {
+        let test_cases = &[
+            ("Game, The", "The Game"),
+            ("Brief, Der", "Der Brief"),
+            ("Brief, der", "Der Brief"),
+            ("Aventure, L'", "L'Aventure"),
+            ("Prince, Le", "Le Prince"),
+            ("Mansiones, Los", "Los Mansiones"),
+            ("Tom, Dick, Harry", "Tom, Dick, Harry"),
+            ("Donkey Kong", "Donkey Kong"),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(move_trailing_article(input, DEFAULT_ARTICLES), *expected);
+        }
+    }
+
+    #[test]
+    fn test_move_trailing_article_custom_articles() {
+        assert_eq!(move_trailing_article("Brief, Der", &["The"]), "Brief, Der");
+        assert_eq!(move_trailing_article("Spel, Det", &["Det"]), "Det Spel");
+    }
+}
@@ -0,0 +1,28 @@
+/// Release status of a dump as tagged in No-Intro names, e.g. `(Proto)` or `(Unl)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DumpStatus {
+    /// Commercially released, no status tag
+    #[default]
+    Released,
+    Proto,
+    Beta,
+    Demo,
+    Sample,
+    Unlicensed,
+}
+
+impl DumpStatus {
+    /// Parse a status tag. Numbered tags like `Proto 2` or `Beta 1` are recognized.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.trim();
+        let status = tag.split_whitespace().next().unwrap_or(tag);
+        match status {
+            "Proto" => Some(DumpStatus::Proto),
+            "Beta" => Some(DumpStatus::Beta),
+            "Demo" => Some(DumpStatus::Demo),
+            "Sample" => Some(DumpStatus::Sample),
+            "Unl" => Some(DumpStatus::Unlicensed),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+/// Language of a release as written in No-Intro names, e.g. `Fr` in `Game (Europe) (En,Fr,De)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Language {
+    Chinese,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Italian,
+    Japanese,
+    Korean,
+    Norwegian,
+    Polish,
+    Portuguese,
+    Russian,
+    Spanish,
+    Swedish,
+    /// Language code not known by this enum
+    Other(String),
+}
+
+impl Language {
+    pub fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "Zh" => Language::Chinese,
+            "Da" => Language::Danish,
+            "Nl" => Language::Dutch,
+            "En" => Language::English,
+            "Fi" => Language::Finnish,
+            "Fr" => Language::French,
+            "De" => Language::German,
+            "It" => Language::Italian,
+            "Ja" => Language::Japanese,
+            "Ko" => Language::Korean,
+            "No" => Language::Norwegian,
+            "Pl" => Language::Polish,
+            "Pt" => Language::Portuguese,
+            "Ru" => Language::Russian,
+            "Es" => Language::Spanish,
+            "Sv" => Language::Swedish,
+            other => Language::Other(other.to_string()),
+        }
+    }
+}
@@ -1,7 +1,19 @@
+use std::cmp::Ordering;
 use std::sync::OnceLock;
 
 use regex::Regex;
 
+use super::media_sequence::MediaSequence;
+use super::{DEFAULT_ARTICLES, extract_tags, move_trailing_article};
+
+mod dump_status;
+mod language;
+mod region;
+
+pub use dump_status::DumpStatus;
+pub use language::Language;
+pub use region::Region;
+
 #[derive(Debug, Clone)]
 pub struct DatFile {
     pub header: DatHeader,
@@ -37,6 +49,7 @@ pub struct DatGame {
     pub categories: Vec<String>,
     pub description: String,
     pub roms: Vec<DatRom>,
+    pub disks: Vec<DatDisk>,
     pub releases: Vec<DatRelease>,
 }
 
@@ -53,6 +66,14 @@ pub struct DatRom {
     pub header: Option<String>,
 }
 
+/// CHD disk image of a game, e.g. a hard disk or CD-ROM of an arcade machine.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatDisk {
+    pub name: String,
+    pub sha1: String,
+    pub status: Option<String>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct DatRelease {
     pub name: String,
@@ -72,24 +93,18 @@ impl DatGame {
     }
 
     /// Formalize a software title name from the game name. This may involve removing
-    /// parentheticals, normalizing punctuation, and applying title case.
+    /// parentheticals, normalizing punctuation, and applying title case. A trailing article of
+    /// `DEFAULT_ARTICLES` is moved to the start, e.g. "Brief, Der" -> "Der Brief".
     pub fn get_software_title_name(&self) -> String {
+        self.get_software_title_name_with_articles(DEFAULT_ARTICLES)
+    }
+
+    /// Same as `get_software_title_name` but "Title, Article" is turned to "Article Title" only
+    /// for the given articles.
+    pub fn get_software_title_name_with_articles(&self, articles: &[&str]) -> String {
         // Remove all parenthetical content
         let title = self.name.split('(').next().unwrap_or(&self.name).trim();
-
-        // Handle "Title, The" -> "The Title" pattern
-        // Also handle "Title, A" and "Title, An"
-        if let Some(comma_pos) = title.rfind(", ") {
-            let base = &title[..comma_pos];
-            let article = &title[comma_pos + 2..];
-            if article == "The" || article == "A" || article == "An" {
-                format!("{} {}", article, base)
-            } else {
-                title.to_string()
-            }
-        } else {
-            title.to_string()
-        }
+        move_trailing_article(title, articles)
     }
 
     /// Get a list of release name for this game. Release names may include region or other
@@ -99,19 +114,117 @@ impl DatGame {
         tags.join(" - ")
     }
 
+    /// Get the regions of the game from the first parenthetical, e.g. `(USA, Europe)`.
+    pub fn regions(&self) -> Vec<Region> {
+        Self::extract_release_tags(&self.name)
+            .first()
+            .map(|tag| Region::parse_list(tag))
+            .unwrap_or_default()
+    }
+
+    /// Get the languages of the game from the parenthetical consisting only of two letter
+    /// language codes, e.g. `(En,Fr,De)`. No-Intro names have the language tag only when the
+    /// languages aren't implied by the region.
+    pub fn languages(&self) -> Vec<Language> {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^[A-Z][a-z](,[A-Z][a-z])*$").unwrap());
+
+        Self::extract_release_tags(&self.name)
+            .iter()
+            .find(|tag| re.is_match(tag))
+            .map(|tag| tag.split(',').map(Language::from_code).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the position of the game in a multi-disk or multi-tape release from a tag like
+    /// `(Disk 1 of 3)`, `(Tape 2 of 2)` or `(Side A)`.
+    pub fn media_sequence(&self) -> Option<MediaSequence> {
+        MediaSequence::from_tags(&Self::extract_release_tags(&self.name))
+    }
+
+    /// Get the version from a tag like `(v1.1)`.
+    pub fn version(&self) -> Option<String> {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^v\d+(\.\d+)*[a-z]?$").unwrap());
+
+        Self::extract_release_tags(&self.name)
+            .into_iter()
+            .find(|tag| re.is_match(tag))
+    }
+
+    /// Get the revision number from a tag like `(Rev 2)`, `(Rev A)` or `(PRG1)`. Lettered
+    /// revisions are numbered from `A` = 1.
+    pub fn revision(&self) -> Option<u32> {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^(?:Rev |PRG)(\d+|[A-Z])$").unwrap());
+
+        Self::extract_release_tags(&self.name)
+            .iter()
+            .find_map(|tag| {
+                let revision = &re.captures(tag)?[1];
+                match revision.parse() {
+                    Ok(number) => Some(number),
+                    Err(_) => Some(revision.chars().next()? as u32 - 'A' as u32 + 1),
+                }
+            })
+    }
+
+    /// Order two releases of the same title by revision and version. A release without a
+    /// revision or version is older than one with them.
+    pub fn compare_revision(&self, other: &DatGame) -> Ordering {
+        let version_numbers = |game: &DatGame| -> Vec<u32> {
+            game.version()
+                .map(|version| {
+                    version
+                        .trim_start_matches('v')
+                        .split('.')
+                        .map(|part| {
+                            part.trim_end_matches(|c: char| c.is_ascii_alphabetic())
+                                .parse()
+                                .unwrap_or(0)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        self.revision()
+            .cmp(&other.revision())
+            .then_with(|| version_numbers(self).cmp(&version_numbers(other)))
+            .then_with(|| self.version().cmp(&other.version()))
+    }
+
+    /// BIOS dumps are prefixed with `[BIOS]`, e.g. `[BIOS] ColecoVision (USA, Europe)`.
+    pub fn is_bios(&self) -> bool {
+        self.name.starts_with("[BIOS]")
+    }
+
+    /// Get the release status from tags like `(Proto)`, `(Beta)`, `(Demo)`, `(Sample)` or
+    /// `(Unl)`. Pre-release statuses take precedence over `(Unl)` when both are present.
+    pub fn dump_status(&self) -> DumpStatus {
+        let statuses: Vec<DumpStatus> = Self::extract_release_tags(&self.name)
+            .iter()
+            .filter_map(|tag| DumpStatus::from_tag(tag))
+            .collect();
+        statuses
+            .iter()
+            .find(|status| **status != DumpStatus::Unlicensed)
+            .or(statuses.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
     fn extract_release_tags(input: &str) -> Vec<String> {
         static RE: OnceLock<Regex> = OnceLock::new();
         let re = RE.get_or_init(|| Regex::new(r"\(([^)]+)\)").unwrap());
 
-        re.captures_iter(input)
-            .map(|cap| cap[1].trim().to_string())
-            .collect()
+        extract_tags(input, re)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::naming_conventions::media_sequence::MediaKind;
 
     fn create_dat_game_with_name(name: &str) -> DatGame {
         DatGame {
@@ -122,6 +235,7 @@ mod tests {
             categories: vec![],
             description: "".to_string(),
             roms: vec![],
+            disks: vec![],
             releases: vec![],
         }
     }
@@ -149,6 +263,11 @@ mod tests {
             ("Game, The (Europe)", "The Game"),
             ("Simple Game, A (Europe)", "A Simple Game"),
             ("Another Game, An (Europe)", "An Another Game"),
+            ("Brief, Der (Germany)", "Der Brief"),
+            ("Maus, die (Germany)", "Die Maus"),
+            ("Aventure, L' (France)", "L'Aventure"),
+            ("Petit Prince, Le (France) (En,Fr)", "Le Petit Prince"),
+            ("Pitufos, Los (Spain)", "Los Pitufos"),
             (
                 "Antarctic Adventure (USA, Europe) (Beta)",
                 "Antarctic Adventure",
@@ -164,6 +283,20 @@ mod tests {
             assert_eq!(game.get_software_title_name(), *expected);
         }
     }
+
+    #[test]
+    fn test_get_software_title_name_with_articles() {
+        let game = create_dat_game_with_name("Brief, Der (Germany)");
+        assert_eq!(
+            game.get_software_title_name_with_articles(&["The"]),
+            "Brief, Der"
+        );
+        assert_eq!(
+            game.get_software_title_name_with_articles(&["Der"]),
+            "Der Brief"
+        );
+    }
+
     #[test]
     fn test_get_release_name() {
         let test_cases = &[
@@ -185,6 +318,179 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_regions() {
+        let test_cases = &[
+            ("A.E. (USA) (Proto)", vec![Region::Usa]),
+            (
+                "Antarctic Adventure (USA, Europe) (Beta)",
+                vec![Region::Usa, Region::Europe],
+            ),
+            (
+                "Pac-Man (Japan, USA, Korea)",
+                vec![Region::Japan, Region::Usa, Region::Korea],
+            ),
+            ("Tetris (World)", vec![Region::World]),
+            (
+                "Some Game (Atlantis, Europe)",
+                vec![Region::Other("Atlantis".to_string()), Region::Europe],
+            ),
+            ("Some Game", vec![]),
+        ];
+        for (input, expected) in test_cases {
+            let game = create_dat_game_with_name(input);
+            assert_eq!(game.regions(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_languages() {
+        let test_cases = &[
+            (
+                "Some Game (Europe) (En,Fr,De)",
+                vec![Language::English, Language::French, Language::German],
+            ),
+            ("Donkey Kong (USA, Europe) (v1.1)", vec![]),
+            ("Tetris (Japan) (Ja)", vec![Language::Japanese]),
+            (
+                "Some Game (Europe) (En,Xx) (Beta)",
+                vec![Language::English, Language::Other("Xx".to_string())],
+            ),
+        ];
+        for (input, expected) in test_cases {
+            let game = create_dat_game_with_name(input);
+            assert_eq!(game.languages(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_regions_and_languages() {
+        let game = create_dat_game_with_name("Some Game (Europe) (En,Fr,De)");
+
+        assert_eq!(game.regions(), vec![Region::Europe]);
+        assert_eq!(
+            game.languages(),
+            vec![Language::English, Language::French, Language::German]
+        );
+    }
+
+    #[test]
+    fn test_media_sequence() {
+        let game = create_dat_game_with_name("Some Game (Europe) (Disk 2 of 3)");
+        assert_eq!(
+            game.media_sequence(),
+            Some(MediaSequence {
+                index: 2,
+                total: Some(3),
+                kind: MediaKind::Disk,
+                side: None,
+            })
+        );
+
+        let game = create_dat_game_with_name("Some Game (Europe) (Side B)");
+        assert_eq!(
+            game.media_sequence(),
+            Some(MediaSequence {
+                index: 2,
+                total: None,
+                kind: MediaKind::Side,
+                side: Some('B'),
+            })
+        );
+
+        let game = create_dat_game_with_name("Donkey Kong (USA, Europe) (v1.1)");
+        assert_eq!(game.media_sequence(), None);
+    }
+
+    #[test]
+    fn test_version() {
+        let test_cases = &[
+            ("Donkey Kong (USA, Europe) (v1.1)", Some("v1.1")),
+            ("Some Game (Japan) (v1.02b)", Some("v1.02b")),
+            ("Some Game (USA) (Rev 2)", None),
+            ("Some Game (USA)", None),
+        ];
+        for (input, expected) in test_cases {
+            let game = create_dat_game_with_name(input);
+            assert_eq!(game.version().as_deref(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_revision() {
+        let test_cases = &[
+            ("Some Game (USA) (Rev A)", Some(1)),
+            ("Some Game (USA) (Rev B)", Some(2)),
+            ("Some Game (USA) (Rev 2)", Some(2)),
+            ("Some Game (Japan) (PRG1)", Some(1)),
+            ("Donkey Kong (USA, Europe) (v1.1)", None),
+            ("Some Game (USA)", None),
+        ];
+        for (input, expected) in test_cases {
+            let game = create_dat_game_with_name(input);
+            assert_eq!(game.revision(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_compare_revision() {
+        let mut games: Vec<DatGame> = [
+            "Some Game (USA) (Rev 2)",
+            "Some Game (USA)",
+            "Some Game (USA) (Rev 10)",
+            "Some Game (USA) (Rev 1)",
+        ]
+        .iter()
+        .map(|name| create_dat_game_with_name(name))
+        .collect();
+        games.sort_by(|a, b| a.compare_revision(b));
+        let names: Vec<&str> = games.iter().map(|game| game.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Some Game (USA)",
+                "Some Game (USA) (Rev 1)",
+                "Some Game (USA) (Rev 2)",
+                "Some Game (USA) (Rev 10)",
+            ]
+        );
+
+        let older = create_dat_game_with_name("Donkey Kong (USA, Europe) (v1.2)");
+        let newer = create_dat_game_with_name("Donkey Kong (USA, Europe) (v1.10)");
+        assert_eq!(older.compare_revision(&newer), Ordering::Less);
+        assert_eq!(newer.compare_revision(&older), Ordering::Greater);
+        assert_eq!(older.compare_revision(&older), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_is_bios() {
+        let game = create_dat_game_with_name("[BIOS] ColecoVision (USA, Europe)");
+        assert!(game.is_bios());
+        assert_eq!(game.regions(), vec![Region::Usa, Region::Europe]);
+        assert_eq!(game.dump_status(), DumpStatus::Released);
+
+        let game = create_dat_game_with_name("Donkey Kong (USA, Europe) (v1.1)");
+        assert!(!game.is_bios());
+    }
+
+    #[test]
+    fn test_dump_status() {
+        let test_cases = &[
+            ("Donkey Kong (USA, Europe) (v1.1)", DumpStatus::Released),
+            ("A.E. (USA) (Proto)", DumpStatus::Proto),
+            ("Some Game (USA) (Proto 2)", DumpStatus::Proto),
+            ("Antarctic Adventure (USA, Europe) (Beta)", DumpStatus::Beta),
+            ("Some Game (Europe) (Demo)", DumpStatus::Demo),
+            ("Some Game (Japan) (Sample)", DumpStatus::Sample),
+            ("Some Game (USA) (Unl)", DumpStatus::Unlicensed),
+            ("Some Game (USA) (Unl) (Beta)", DumpStatus::Beta),
+        ];
+        for (input, expected) in test_cases {
+            let game = create_dat_game_with_name(input);
+            assert_eq!(game.dump_status(), *expected, "{}", input);
+        }
+    }
+
     #[test]
     fn dat_header_get_source() {
         let header = DatHeader {
@@ -0,0 +1,61 @@
+/// Region of a release as written in No-Intro names, e.g. `USA` in `Game (USA, Europe)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Region {
+    World,
+    Asia,
+    Australia,
+    Brazil,
+    Canada,
+    China,
+    Europe,
+    France,
+    Germany,
+    HongKong,
+    Italy,
+    Japan,
+    Korea,
+    Netherlands,
+    Spain,
+    Sweden,
+    Taiwan,
+    UnitedKingdom,
+    Usa,
+    /// Region not known by this enum
+    Other(String),
+}
+
+impl Region {
+    pub fn from_name(name: &str) -> Self {
+        match name.trim() {
+            "World" => Region::World,
+            "Asia" => Region::Asia,
+            "Australia" => Region::Australia,
+            "Brazil" => Region::Brazil,
+            "Canada" => Region::Canada,
+            "China" => Region::China,
+            "Europe" => Region::Europe,
+            "France" => Region::France,
+            "Germany" => Region::Germany,
+            "Hong Kong" => Region::HongKong,
+            "Italy" => Region::Italy,
+            "Japan" => Region::Japan,
+            "Korea" => Region::Korea,
+            "Netherlands" => Region::Netherlands,
+            "Spain" => Region::Spain,
+            "Sweden" => Region::Sweden,
+            "Taiwan" => Region::Taiwan,
+            "UK" => Region::UnitedKingdom,
+            "USA" => Region::Usa,
+            other => Region::Other(other.to_string()),
+        }
+    }
+
+    /// Parse a comma separated list of regions, e.g. `USA, Europe`.
+    pub fn parse_list(regions: &str) -> Vec<Self> {
+        regions
+            .split(',')
+            .filter(|region| !region.trim().is_empty())
+            .map(Self::from_name)
+            .collect()
+    }
+}
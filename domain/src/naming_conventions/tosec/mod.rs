@@ -0,0 +1,261 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::media_sequence::MediaSequence;
+use super::{DEFAULT_ARTICLES, extract_tags, move_trailing_article};
+
+/// Name following the TOSEC naming convention:
+/// `Title version (demo) (Year)(Publisher)(System)(Video)(Country)(Language)...[flags]`
+///
+/// Only the year and the publisher are mandatory after the title.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TosecName {
+    pub name: String,
+    pub title: String,
+    pub version: Option<String>,
+    /// Year of release, may contain unknown digits, e.g. `198x` or `19xx`
+    pub year: String,
+    pub publisher: String,
+    /// Country codes, e.g. `DE` or `US-GB`
+    pub country: Option<String>,
+    /// Parenthetical tags other than year, publisher and country, e.g. `demo` or `Disk 1 of 2`
+    pub tags: Vec<String>,
+    /// Dump info flags in square brackets, e.g. `cr Fairlight`, `t +2` or `!`
+    pub flags: Vec<String>,
+}
+
+impl TosecName {
+    /// Parse a TOSEC name. File extension is expected to be removed. Returns `None` if the name
+    /// doesn't have the mandatory year and publisher.
+    pub fn parse(name: &str) -> Option<Self> {
+        static YEAR_RE: OnceLock<Regex> = OnceLock::new();
+        let year_re =
+            YEAR_RE.get_or_init(|| Regex::new(r"^[12][0-9x]{3}(-[0-9x]{2}){0,2}$").unwrap());
+        static COUNTRY_RE: OnceLock<Regex> = OnceLock::new();
+        let country_re = COUNTRY_RE.get_or_init(|| Regex::new(r"^[A-Z]{2}(-[A-Z]{2})*$").unwrap());
+        static TAG_RE: OnceLock<Regex> = OnceLock::new();
+        let tag_re = TAG_RE.get_or_init(|| Regex::new(r"\(([^)]+)\)").unwrap());
+        static FLAG_RE: OnceLock<Regex> = OnceLock::new();
+        let flag_re = FLAG_RE.get_or_init(|| Regex::new(r"\[([^\]]+)\]").unwrap());
+
+        let (title, version) = Self::split_version(name.split('(').next()?.trim());
+        let mut tags = extract_tags(name, tag_re);
+        let year_pos = tags.iter().position(|tag| year_re.is_match(tag))?;
+        if year_pos + 1 >= tags.len() {
+            return None;
+        }
+        let year = tags.remove(year_pos);
+        let publisher = tags.remove(year_pos);
+        let country = tags[year_pos..]
+            .iter()
+            .position(|tag| country_re.is_match(tag))
+            .map(|pos| tags.remove(year_pos + pos));
+
+        Some(Self {
+            name: name.to_string(),
+            title,
+            version,
+            year,
+            publisher,
+            country,
+            tags,
+            flags: extract_tags(name, flag_re),
+        })
+    }
+
+    /// Formalize a software title name from the title. A trailing article of `DEFAULT_ARTICLES`
+    /// is moved to the start, e.g. "Title, The" is turned to "The Title".
+    pub fn get_software_title_name(&self) -> String {
+        move_trailing_article(&self.title, DEFAULT_ARTICLES)
+    }
+
+    /// Get the release name from the version, the parenthetical tags and the dump info flags.
+    pub fn get_release_name(&self) -> String {
+        self.version
+            .iter()
+            .chain([&self.year, &self.publisher])
+            .chain(&self.country)
+            .chain(&self.tags)
+            .chain(&self.flags)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" - ")
+    }
+
+    /// Get the position in a multi-disk or multi-tape release from a tag like `(Disk 1 of 2)`.
+    pub fn media_sequence(&self) -> Option<MediaSequence> {
+        MediaSequence::from_tags(&self.tags)
+    }
+
+    /// Cracked dump, `[cr]`
+    pub fn is_cracked(&self) -> bool {
+        self.has_flag("cr")
+    }
+
+    /// Trained dump, `[t]`
+    pub fn is_trained(&self) -> bool {
+        self.has_flag("t")
+    }
+
+    /// Verified good dump, `[!]`
+    pub fn is_verified(&self) -> bool {
+        self.has_flag("!")
+    }
+
+    /// Check if a flag with the code is set. The numbering of the flag, e.g. `t2` or `a3`, and the
+    /// additional info after the code are ignored.
+    pub fn has_flag(&self, code: &str) -> bool {
+        self.flags.iter().any(|flag| {
+            let flag_code = flag.split_whitespace().next().unwrap_or_default();
+            let flag_code = match flag_code.trim_end_matches(|c: char| c.is_ascii_digit()) {
+                "" => flag_code,
+                trimmed => trimmed,
+            };
+            flag_code == code
+        })
+    }
+
+    fn split_version(title: &str) -> (String, Option<String>) {
+        static RE: OnceLock<Regex> = OnceLock::new();
+        let re = RE.get_or_init(|| Regex::new(r"^(.+?)\s+(v[0-9][^\s]*)$").unwrap());
+        match re.captures(title) {
+            Some(cap) => (cap[1].to_string(), Some(cap[2].to_string())),
+            None => (title.to_string(), None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::naming_conventions::media_sequence::MediaKind;
+
+    #[test]
+    fn test_parse() {
+        let name = TosecName::parse(
+            "Great Giana Sisters, The (1987)(Rainbow Arts)(DE)[cr Fairlight][t +3 Fairlight]",
+        )
+        .unwrap();
+
+        assert_eq!(name.title, "Great Giana Sisters, The");
+        assert_eq!(name.version, None);
+        assert_eq!(name.year, "1987");
+        assert_eq!(name.publisher, "Rainbow Arts");
+        assert_eq!(name.country.as_deref(), Some("DE"));
+        assert!(name.tags.is_empty());
+        assert_eq!(name.flags, vec!["cr Fairlight", "t +3 Fairlight"]);
+        assert!(name.is_cracked());
+        assert!(name.is_trained());
+        assert!(!name.is_verified());
+    }
+
+    #[test]
+    fn test_parse_with_version_demo_and_media_tags() {
+        let name =
+            TosecName::parse("Lemmings v1.1 (demo) (1991)(Psygnosis)(US-GB)(Disk 1 of 2)[a2]")
+                .unwrap();
+
+        assert_eq!(name.title, "Lemmings");
+        assert_eq!(name.version.as_deref(), Some("v1.1"));
+        assert_eq!(name.year, "1991");
+        assert_eq!(name.publisher, "Psygnosis");
+        assert_eq!(name.country.as_deref(), Some("US-GB"));
+        assert_eq!(name.tags, vec!["demo", "Disk 1 of 2"]);
+        assert!(name.has_flag("a"));
+        assert!(!name.is_cracked());
+        assert_eq!(
+            name.media_sequence(),
+            Some(MediaSequence {
+                index: 1,
+                total: Some(2),
+                kind: MediaKind::Disk,
+                side: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_media_sequence_with_side() {
+        let name =
+            TosecName::parse("Pirates! (1987)(MicroProse)(Disk 1 of 2 Side B)[cr Triad]").unwrap();
+
+        assert_eq!(
+            name.media_sequence(),
+            Some(MediaSequence {
+                index: 1,
+                total: Some(2),
+                kind: MediaKind::Disk,
+                side: Some('B'),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_without_country() {
+        let name =
+            TosecName::parse("Bubble Bobble (1987)(Firebird)(PAL)[cr Ikari][t2 +5]").unwrap();
+
+        assert_eq!(name.title, "Bubble Bobble");
+        assert_eq!(name.year, "1987");
+        assert_eq!(name.publisher, "Firebird");
+        assert_eq!(name.country, None);
+        assert_eq!(name.tags, vec!["PAL"]);
+        assert!(name.is_cracked());
+        assert!(name.is_trained());
+    }
+
+    #[test]
+    fn test_parse_unknown_year_and_translation_flag() {
+        let name = TosecName::parse("Boulder Dash (198x)(First Star Software)[tr de][!]").unwrap();
+
+        assert_eq!(name.year, "198x");
+        assert_eq!(name.publisher, "First Star Software");
+        assert!(name.has_flag("tr"));
+        assert!(!name.is_trained());
+        assert!(name.is_verified());
+    }
+
+    #[test]
+    fn test_parse_not_tosec() {
+        assert_eq!(TosecName::parse("Donkey Kong (USA, Europe) (v1.1)"), None);
+        assert_eq!(TosecName::parse("Bubble Bobble (1987)"), None);
+    }
+
+    #[test]
+    fn test_get_software_title_name() {
+        let test_cases = &[
+            (
+                "Great Giana Sisters, The (1987)(Rainbow Arts)(DE)[cr Fairlight]",
+                "The Great Giana Sisters",
+            ),
+            ("Lemmings v1.1 (1991)(Psygnosis)", "Lemmings"),
+            (
+                "Turrican II - The Final Fight (1991)(Rainbow Arts)(PAL)[cr Skid Row]",
+                "Turrican II - The Final Fight",
+            ),
+        ];
+        for (input, expected) in test_cases {
+            let name = TosecName::parse(input).unwrap();
+            assert_eq!(name.get_software_title_name(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_get_release_name() {
+        let test_cases = &[
+            (
+                "Great Giana Sisters, The (1987)(Rainbow Arts)(DE)[cr Fairlight][t +3 Fairlight]",
+                "1987 - Rainbow Arts - DE - cr Fairlight - t +3 Fairlight",
+            ),
+            (
+                "Lemmings v1.1 (demo) (1991)(Psygnosis)(Disk 1 of 2)",
+                "v1.1 - 1991 - Psygnosis - demo - Disk 1 of 2",
+            ),
+        ];
+        for (input, expected) in test_cases {
+            let name = TosecName::parse(input).unwrap();
+            assert_eq!(name.get_release_name(), *expected);
+        }
+    }
+}
@@ -3,25 +3,63 @@ mod normalizer;
 mod rules;
 mod search_keys;
 
-pub use normalizer::{NormalizedTitle, TitleNormalizer};
+use rules::extension::strip_extension;
 
-/// Get the canonical software title from a release name,
+pub use normalizer::{
+    CasePolicy, NormalizationRule, NormalizedTitle, TitleNormalizer, TitleNormalizerBuilder,
+};
+
+/// Get the canonical software title from a release name with the default rules, see
+/// [`TitleNormalizerBuilder`] for custom rules,
 /// e.g. "Super Mario Bros. (USA)" -> "Super Mario Bros."
 /// or "castelo (Brazil) (En) (Unl)" -> "Castelo"
 pub fn get_canonical_software_title(release_name: &str) -> String {
-    let normalizer = TitleNormalizer;
+    let normalizer = TitleNormalizer::default();
     let normalized = normalizer.normalize(release_name);
     normalized.canonical
 }
 
-pub fn file_name_to_canonical_software_title(file_name: &str) -> String {
-    let file_stem = std::path::Path::new(file_name)
-        .file_stem()
-        .unwrap()
+/// Get the canonical software title from a file name or path, e.g.
+/// "roms/A.E._(USA).zip" -> "A.E.". Returns `None` if there's no file name to get the title from,
+/// e.g. for an empty string or a path ending in `..`.
+pub fn file_name_to_canonical_software_title(file_name: &str) -> Option<String> {
+    let file_name = std::path::Path::new(file_name)
+        .file_name()?
         .to_string_lossy();
+    let file_stem = strip_extension(&file_name);
 
     // strip underscores and dashes, replace with spaces
     let file_stem = file_stem.replace(&['_', '-'][..], " ");
 
-    get_canonical_software_title(&file_stem)
+    let title = get_canonical_software_title(&file_stem);
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name_to_canonical_software_title() {
+        let test_cases = &[
+            ("game.tar.gz", Some("Game")),
+            ("A.E. (USA).zip", Some("A.E.")),
+            (
+                "roms/Activision_Decathlon, The (USA).zip",
+                Some("The Activision Decathlon"),
+            ),
+            ("Donkey Kong (USA, Europe) (v1.1).a26", Some("Donkey Kong")),
+            ("", None),
+            ("roms/..", None),
+            ("(USA).zip", None),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(
+                file_name_to_canonical_software_title(input).as_deref(),
+                *expected,
+                "{}",
+                input
+            );
+        }
+    }
 }
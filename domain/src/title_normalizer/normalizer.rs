@@ -1,3 +1,5 @@
+use regex::Regex;
+
 use crate::title_normalizer::{
     case::title_case,
     rules::{
@@ -28,17 +30,115 @@ pub struct NormalizedTitle {
     pub search_keys: Vec<String>,
 }
 
-pub struct TitleNormalizer;
+/// Replacement rule applied to the title before whitespace is normalized and the case policy is
+/// applied.
+pub type NormalizationRule = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CasePolicy {
+    /// Capitalize words except small words like "of" and "the" in the middle of the title
+    #[default]
+    TitleCase,
+    /// Keep the case as in the input
+    Preserve,
+}
+
+/// Builder for a [`TitleNormalizer`] with custom rules. Starts with the default rules:
+/// `parentheticals` removing parenthetical tags and `articles` moving a trailing article to the
+/// start of the title.
+pub struct TitleNormalizerBuilder {
+    rules: Vec<(String, NormalizationRule)>,
+    case_policy: CasePolicy,
+}
+
+impl TitleNormalizerBuilder {
+    pub fn new() -> Self {
+        Self {
+            rules: vec![
+                (
+                    "parentheticals".to_string(),
+                    Box::new(remove_parentheticals) as NormalizationRule,
+                ),
+                ("articles".to_string(), Box::new(normalize_articles)),
+            ],
+            case_policy: CasePolicy::default(),
+        }
+    }
+
+    /// Add a rule applied after the existing rules. A rule with the same name is replaced in
+    /// place instead.
+    pub fn with_rule(
+        mut self,
+        name: &str,
+        rule: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let rule: NormalizationRule = Box::new(rule);
+        match self
+            .rules
+            .iter_mut()
+            .find(|(rule_name, _)| rule_name == name)
+        {
+            Some((_, existing)) => *existing = rule,
+            None => self.rules.push((name.to_string(), rule)),
+        }
+        self
+    }
+
+    /// Add a rule replacing all matches of `pattern` with `replacement`, see
+    /// [`Regex::replace_all`] for the replacement syntax.
+    pub fn with_replacement(self, name: &str, pattern: Regex, replacement: &str) -> Self {
+        let replacement = replacement.to_string();
+        self.with_rule(name, move |s| {
+            pattern.replace_all(s, replacement.as_str()).to_string()
+        })
+    }
+
+    /// Remove a rule, including a default rule.
+    pub fn without_rule(mut self, name: &str) -> Self {
+        self.rules.retain(|(rule_name, _)| rule_name != name);
+        self
+    }
+
+    pub fn with_case_policy(mut self, case_policy: CasePolicy) -> Self {
+        self.case_policy = case_policy;
+        self
+    }
+
+    pub fn build(self) -> TitleNormalizer {
+        TitleNormalizer {
+            rules: self.rules,
+            case_policy: self.case_policy,
+        }
+    }
+}
+
+impl Default for TitleNormalizerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct TitleNormalizer {
+    rules: Vec<(String, NormalizationRule)>,
+    case_policy: CasePolicy,
+}
 
 impl TitleNormalizer {
+    pub fn builder() -> TitleNormalizerBuilder {
+        TitleNormalizerBuilder::new()
+    }
+
     pub fn normalize(&self, input: &str) -> NormalizedTitle {
         let mut s = input.to_string();
 
-        s = remove_parentheticals(&s);
-        s = normalize_articles(&s);
+        for (_, rule) in &self.rules {
+            s = rule(&s);
+        }
         s = normalize_whitespace(&s);
 
-        s = title_case(&s);
+        if self.case_policy == CasePolicy::TitleCase {
+            s = title_case(&s);
+        }
 
         //s = capitalize_initials(&s);
 
@@ -51,6 +151,22 @@ impl TitleNormalizer {
             search_keys,
         }
     }
+
+    /// Get the search keys of a name: the normalized title lowercased with punctuation removed,
+    /// and the same without spaces if it has several words.
+    ///
+    /// For indexing, store each key of a title pointing to the title, and look up the keys of
+    /// the searched name. Differently formatted spellings of a title, e.g. "Bump 'n' Jump" and
+    /// "Bump'n'Jump" or "Game, The" and "The Game", share at least one key.
+    pub fn search_keys(&self, name: &str) -> Vec<String> {
+        self.normalize(name).search_keys
+    }
+}
+
+impl Default for TitleNormalizer {
+    fn default() -> Self {
+        TitleNormalizerBuilder::new().build()
+    }
 }
 
 /*
@@ -231,11 +347,89 @@ mod tests {
             ),
         ];
 
-        let normalizer = TitleNormalizer;
+        let normalizer = TitleNormalizer::default();
 
         for (input, expected) in test_cases {
             let normalized = normalizer.normalize(input);
             assert_eq!(normalized, expected);
         }
     }
+
+    #[test]
+    fn test_search_keys_of_different_spellings_overlap() {
+        let normalizer = TitleNormalizer::default();
+        let test_cases = [
+            (
+                "Activision Decathlon, The (USA)",
+                "The Activision Decathlon",
+            ),
+            ("Bump 'n' Jump (USA, Europe)", "Bump'n'Jump"),
+            ("Cats & Dogs (Europe)", "cats and dogs"),
+            (
+                "Dr. Seuss - Fix-Up the Mix-Up Puzzler (USA)",
+                "Dr Seuss Fixup the Mixup Puzzler",
+            ),
+        ];
+        for (a, b) in test_cases {
+            let keys_a = normalizer.search_keys(a);
+            let keys_b = normalizer.search_keys(b);
+            assert!(
+                keys_a.iter().any(|key| keys_b.contains(key)),
+                "{:?} and {:?} don't overlap",
+                keys_a,
+                keys_b
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_replacement_rule() {
+        let input = "Ghosts & Goblins (USA)";
+        assert_eq!(
+            TitleNormalizer::default().normalize(input).canonical,
+            "Ghosts & Goblins"
+        );
+
+        let normalizer = TitleNormalizer::builder()
+            .with_replacement("ampersand", Regex::new(r"\s*&\s*").unwrap(), " and ")
+            .build();
+        let normalized = normalizer.normalize(input);
+        assert_eq!(normalized.canonical, "Ghosts and Goblins");
+        assert_eq!(
+            normalized.search_keys,
+            vec!["ghosts and goblins", "ghostsandgoblins"]
+        );
+    }
+
+    #[test]
+    fn test_override_rule_and_case_policy() {
+        let normalizer = TitleNormalizer::builder()
+            .with_rule("articles", |s| s.to_string())
+            .with_replacement("publisher suffix", Regex::new(r"\s+by Ocean$").unwrap(), "")
+            .with_case_policy(CasePolicy::Preserve)
+            .build();
+
+        assert_eq!(
+            normalizer
+                .normalize("Game, The by Ocean (Europe)")
+                .canonical,
+            "Game, The"
+        );
+        assert_eq!(
+            normalizer.normalize("the ninja of darkness").canonical,
+            "the ninja of darkness"
+        );
+    }
+
+    #[test]
+    fn test_without_rule() {
+        let normalizer = TitleNormalizer::builder()
+            .without_rule("parentheticals")
+            .build();
+
+        assert_eq!(
+            normalizer.normalize("Donkey Kong (USA)").canonical,
+            "Donkey Kong (USA)"
+        );
+    }
 }
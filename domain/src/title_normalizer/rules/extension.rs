@@ -1,5 +1,51 @@
+/// Extensions consisting of several parts that are stripped as a whole.
+const MULTI_PART_EXTENSIONS: &[&str] = &[".tar.gz", ".tar.bz2", ".tar.xz", ".tar.zst"];
+
+/// Longest single-part extension that is stripped, e.g. `.jpeg`.
+const MAX_EXTENSION_LENGTH: usize = 5;
+
+/// Strip the extension from a file name. Only short alphanumeric extensions are stripped, so dots
+/// that are part of the name, e.g. in `A.E. (USA)`, are kept.
 pub fn strip_extension(s: &str) -> String {
-    s.rsplit_once('.')
-        .map(|(name, _)| name.to_string())
-        .unwrap_or_else(|| s.to_string())
+    for extension in MULTI_PART_EXTENSIONS {
+        let Some(split) = s.len().checked_sub(extension.len()) else {
+            continue;
+        };
+        if split > 0 && s.is_char_boundary(split) && s[split..].eq_ignore_ascii_case(extension) {
+            return s[..split].to_string();
+        }
+    }
+    match s.rsplit_once('.') {
+        Some((name, extension))
+            if !name.is_empty()
+                && !extension.is_empty()
+                && extension.len() <= MAX_EXTENSION_LENGTH
+                && extension.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            name.to_string()
+        }
+        _ => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_extension() {
+        let test_cases = &[
+            ("game.zip", "game"),
+            ("game.tar.gz", "game"),
+            ("game.TAR.GZ", "game"),
+            ("A.E. (USA).zip", "A.E. (USA)"),
+            ("A.E. (USA)", "A.E. (USA)"),
+            ("Dr. Seuss", "Dr. Seuss"),
+            (".hidden", ".hidden"),
+            ("", ""),
+        ];
+        for (input, expected) in test_cases {
+            assert_eq!(strip_extension(input), *expected, "{}", input);
+        }
+    }
 }
@@ -8,4 +8,21 @@ pub enum ExecutableRunnerError {
     NoFileSelected,
     #[error("File not found")]
     FileNotFound,
+    #[error("Selected file {0} is not in the set of files")]
+    SelectedFileNotInSet(String),
+    /// `code` is `None` when the process was terminated by a signal. `stderr` holds the last
+    /// lines written to stderr.
+    #[error("Executable exited with {}: {stderr}", exit_code_text(.code))]
+    NonZeroExit { code: Option<i32>, stderr: String },
+    #[error("Executable timed out")]
+    TimedOut,
+    #[error("Executable cancelled")]
+    Cancelled,
+}
+
+fn exit_code_text(code: &Option<i32>) -> String {
+    match code {
+        Some(code) => format!("exit code {}", code),
+        None => "signal".to_string(),
+    }
 }
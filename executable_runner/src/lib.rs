@@ -1,6 +1,13 @@
-use async_process::Command;
-use core_types::ArgumentType;
+use async_process::{Child, ChildStderr, Command, Stdio};
+use async_std::io::{BufReader, prelude::BufReadExt};
+use core_types::{ArgumentPlaceholders, ArgumentType};
+use flume::Receiver;
+use futures::future::{self, Either};
+use std::collections::VecDeque;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::time::Duration;
 
 use error::ExecutableRunnerError;
 
@@ -12,18 +19,26 @@ pub mod ops;
 ///
 /// # arguments
 /// * `executable`: executable name (if it's found on system path) or the full path to the executable.
-/// * `arguments`: The arguments to pass to the executable.
+/// * `arguments`: The arguments to pass to the executable. `{file}`, `{dir}` and `{file_stem}`
+///   placeholders in argument values are replaced with the selected file, an argument with the
+///   `{files}` placeholder is repeated for each file in `file_names`. The file path is passed as
+///   the last argument unless `{file}` or `{files}` is used.
 /// * `file_names`: A vector of file names to be used with executable.
 /// * `selected_file_name`: The name of the entry point file of the set of file_names to be executed.
 /// * `source_path`: The path where the files are located.
+/// * `cancel_rx`: Kills the executable when a message is received.
 ///
 /// # returns
 /// * `Result<(), ExecutableRunnerError>`: Returns Ok if the executable runs successfully, or an error if it fails.
 ///
 /// # errors
 /// * `ExecutableRunnerError::NoFileSelected`: If no file is selected.
+/// * `ExecutableRunnerError::SelectedFileNotInSet`: If the selected file is not in `file_names`.
 /// * `ExecutableRunnerError::FileNotFound`: If the selected file is not found.
 /// * `ExecutableRunnerError::IoError`: If there is an IO error while running the executable.
+/// * `ExecutableRunnerError::NonZeroExit`: If the executable exits with a failure status.
+/// * `ExecutableRunnerError::TimedOut`: If the executable is killed after the timeout.
+/// * `ExecutableRunnerError::Cancelled`: If the executable is killed after cancellation.
 #[deprecated(note = "Use ExecutableRunnerOps trait instead")]
 pub async fn run_executable(
     executable: String,
@@ -31,10 +46,65 @@ pub async fn run_executable(
     file_names: &[String],      // list of files selected for running
     selected_file_name: String, // entry point file in possible set of files
     source_path: PathBuf,       // where to find files
+    cancel_rx: Option<Receiver<()>>,
+) -> Result<(), ExecutableRunnerError> {
+    run(
+        executable,
+        arguments,
+        file_names,
+        selected_file_name,
+        source_path,
+        &RunOptions {
+            verbose: true,
+            ..Default::default()
+        },
+        cancel_rx.as_ref(),
+    )
+    .await
+}
+
+/// Options for running an executable.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Write the output of the executable to the console. Otherwise stdout is discarded and
+    /// stderr is only returned on failure.
+    pub verbose: bool,
+    /// Kill the executable if it's still running after this time
+    pub timeout: Option<Duration>,
+    /// Environment variables set for the executable in addition to the inherited ones
+    pub env: Vec<(String, String)>,
+    /// Directory the executable is run in, the directory of the files if `None`
+    pub working_dir: Option<PathBuf>,
+    /// Command the executable is run through, e.g. `["flatpak", "run", "org.vice_emu.VICE"]` or
+    /// `["wine"]`. The executable and its arguments are appended after it.
+    pub launcher: Option<Vec<String>>,
+}
+
+/// Number of last stderr lines included in `ExecutableRunnerError::NonZeroExit`.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Time stderr is still read after the executable has exited.
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Runs the executable capturing its stderr, see [`RunOptions`]. The executable is killed when
+/// cancellation is signaled through `cancel_rx`.
+pub(crate) async fn run(
+    executable: String,
+    arguments: &[ArgumentType],
+    file_names: &[String],
+    selected_file_name: String,
+    source_path: PathBuf,
+    options: &RunOptions,
+    cancel_rx: Option<&Receiver<()>>,
 ) -> Result<(), ExecutableRunnerError> {
     if file_names.is_empty() {
         return Err(ExecutableRunnerError::NoFileSelected);
     }
+    if !file_names.contains(&selected_file_name) {
+        return Err(ExecutableRunnerError::SelectedFileNotInSet(
+            selected_file_name,
+        ));
+    }
     let file_path = Path::new(&source_path).join(&selected_file_name);
 
     tracing::debug!("Emulator executable: {}", executable);
@@ -45,49 +115,300 @@ pub async fn run_executable(
         return Err(ExecutableRunnerError::FileNotFound);
     }
 
-    let mut command = Command::new(&executable);
-
-    if arguments.is_empty() {
-        tracing::debug!("No arguments provided, running with file path as only argument.");
-        command.arg(&file_path).current_dir(&source_path);
-    } else {
-        tracing::debug!("Preparing to run executable with arguments {:?}", arguments);
+    let launcher = options.launcher.as_deref();
+    let tokens = command_tokens(
+        &executable,
+        launcher,
+        command_line_args(arguments, &file_path, file_names, &source_path),
+    );
+    let (program, args) = tokens
+        .split_first()
+        .expect("Command always contains the executable");
+    let mut command = std::process::Command::new(program);
+    // Run the executable in its own process group so that processes it forks are killed with it
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut command = Command::from(command);
+    command
+        .args(args)
+        .current_dir(options.working_dir.as_ref().unwrap_or(&source_path))
+        .envs(options.env.iter().map(|(name, value)| (name, value)))
+        .stdout(if options.verbose {
+            Stdio::inherit()
+        } else {
+            Stdio::null()
+        })
+        .stderr(Stdio::piped());
 
-        let mut args = Vec::new();
+    tracing::debug!(
+        "Command to execute: {}",
+        build_command_string(
+            &executable,
+            arguments,
+            file_names,
+            &selected_file_name,
+            &source_path,
+            launcher,
+        )
+    );
 
-        arguments.iter().for_each(|arg| match arg {
-            ArgumentType::Flag { name } => {
-                args.push(name.clone());
-            }
-            ArgumentType::FlagWithValue { name, value } => {
-                args.extend_from_slice(&[name.clone(), value.clone()]);
+    let mut child = command.spawn().map_err(|e| {
+        ExecutableRunnerError::IoError(format!("Failed to start executable: {}", e))
+    })?;
+    let stderr = child.stderr.take();
+    let wait = async {
+        let mut stderr_tail = StderrTail::new(options.verbose);
+        let status = match stderr {
+            Some(stderr) => {
+                let mut reader = BufReader::new(stderr);
+                // Processes forked by the executable, e.g. a viewer started by xdg-open, may keep
+                // stderr open after the executable has exited. Read stderr only until the status
+                // is available and then whatever is left within a grace period.
+                let status = {
+                    let read = pin!(stderr_tail.read(&mut reader));
+                    match future::select(read, pin!(child.status())).await {
+                        Either::Left((_, status)) => status.await,
+                        Either::Right((status, _)) => status,
+                    }
+                };
+                async_std::future::timeout(STDERR_DRAIN_TIMEOUT, stderr_tail.read(&mut reader))
+                    .await
+                    .ok();
+                status
             }
-            ArgumentType::FlagEqualsValue { name, value } => {
-                // TODO: check if this is working as expected
-                args.push(format!("{}={}", name, value));
+            None => child.status().await,
+        };
+        (stderr_tail.into_string(), status)
+    };
+    let wait = async {
+        match options.timeout {
+            Some(timeout) => async_std::future::timeout(timeout, wait).await.ok(),
+            None => Some(wait.await),
+        }
+    };
+    // The wait future borrows the child, so it has to be dropped before the child can be killed
+    let outcome = match future::select(pin!(wait), pin!(wait_for_cancel(cancel_rx))).await {
+        Either::Left((finished, _)) => finished.ok_or(ExecutableRunnerError::TimedOut),
+        Either::Right(_) => Err(ExecutableRunnerError::Cancelled),
+    };
+    let (stderr_tail, status) = match outcome {
+        Ok(finished) => finished,
+        Err(error) => {
+            if matches!(error, ExecutableRunnerError::TimedOut) {
+                tracing::error!(executable = %executable, "Executable timed out, killing it");
+            } else {
+                tracing::info!(executable = %executable, "Executable cancelled, killing it");
             }
+            kill(&mut child).await;
+            return Err(error);
+        }
+    };
+    let status = status.map_err(|e| {
+        ExecutableRunnerError::IoError(format!("Failed to get status of executable: {}", e))
+    })?;
+
+    if !status.success() {
+        tracing::error!(status = %status, stderr = %stderr_tail, "Executable failed");
+        return Err(ExecutableRunnerError::NonZeroExit {
+            code: status.code(),
+            stderr: stderr_tail,
         });
+    }
+
+    Ok(())
+}
 
-        command
-            .args(&args)
-            .arg(&file_path)
-            .current_dir(&source_path);
+/// Completes when cancellation is signaled. Never completes without a receiver or if the sender
+/// is dropped.
+async fn wait_for_cancel(cancel_rx: Option<&Receiver<()>>) {
+    match cancel_rx {
+        Some(cancel_rx) if cancel_rx.recv_async().await.is_ok() => {}
+        _ => future::pending().await,
     }
+}
 
-    tracing::debug!("Command to execute: {:?}", command);
+/// Kills the process group of the executable, or only the executable if that fails.
+async fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    let killed = {
+        // The process group id is the pid of the executable, see `run`
+        let result = unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+        if result != 0 {
+            tracing::warn!(
+                error = %std::io::Error::last_os_error(),
+                "Failed to kill process group of executable"
+            );
+        }
+        result == 0
+    };
+    #[cfg(not(unix))]
+    let killed = false;
+    if !killed && let Err(e) = child.kill() {
+        tracing::warn!(error = %e, "Failed to kill executable");
+    }
+    // Wait for the killed process so that it doesn't linger as a zombie
+    child.status().await.ok();
+}
 
-    let status = command.status().await.map_err(|e| {
-        ExecutableRunnerError::IoError(format!("Failed to get status of executable: {}", e))
-    })?;
+/// Last `STDERR_TAIL_LINES` lines of stderr of the executable.
+struct StderrTail {
+    lines: VecDeque<String>,
+    /// Bytes of a line that hasn't been completed yet
+    line: Vec<u8>,
+    verbose: bool,
+}
 
-    if !status.success() {
-        return Err(ExecutableRunnerError::IoError(format!(
-            "Emulator failed with status: {}",
-            status
-        )));
+impl StderrTail {
+    fn new(verbose: bool) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(STDERR_TAIL_LINES),
+            line: Vec::new(),
+            verbose,
+        }
     }
 
-    Ok(())
+    /// Read stderr until it's closed. Read lines are kept if the future is dropped before that.
+    async fn read(&mut self, reader: &mut BufReader<ChildStderr>) {
+        loop {
+            match reader.read_until(b'\n', &mut self.line).await {
+                Ok(0) => break,
+                Ok(_) if self.line.ends_with(b"\n") => self.push_line(),
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to read stderr of executable");
+                    break;
+                }
+            }
+        }
+    }
+
+    fn push_line(&mut self) {
+        let text = String::from_utf8_lossy(&self.line).trim_end().to_string();
+        self.line.clear();
+        if self.verbose {
+            eprintln!("{}", text);
+        }
+        if self.lines.len() == STDERR_TAIL_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(text);
+    }
+
+    fn into_string(mut self) -> String {
+        if !self.line.is_empty() {
+            self.push_line();
+        }
+        Vec::from(self.lines).join("\n")
+    }
+}
+
+/// Build the command line that running the executable would execute, with the arguments quoted
+/// for a POSIX shell. Nothing is spawned, so this can be used for showing or copying the command.
+///
+/// # arguments
+/// Same as for [`run_executable`], `launcher` as in [`RunOptions::launcher`].
+pub fn build_command_string(
+    executable: &str,
+    arguments: &[ArgumentType],
+    file_names: &[String],
+    selected_file_name: &str,
+    source_path: &Path,
+    launcher: Option<&[String]>,
+) -> String {
+    let file_path = source_path.join(selected_file_name);
+    command_tokens(
+        executable,
+        launcher,
+        command_line_args(arguments, &file_path, file_names, source_path),
+    )
+    .iter()
+    .map(|token| shell_quote(&token.to_string_lossy()))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// The program to run followed by its arguments: the launcher tokens if any, then the executable
+/// and its arguments.
+fn command_tokens(
+    executable: &str,
+    launcher: Option<&[String]>,
+    args: Vec<OsString>,
+) -> Vec<OsString> {
+    launcher
+        .unwrap_or_default()
+        .iter()
+        .map(OsString::from)
+        .chain(std::iter::once(OsString::from(executable)))
+        .chain(args)
+        .collect()
+}
+
+/// Quote the value with single quotes unless it only contains characters that are safe to use
+/// unquoted in a shell.
+fn shell_quote(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./=:,+@%".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Build the command line arguments with the placeholders substituted. The file path is
+/// appended as the last argument unless an argument refers to it with the `{file}` or `{files}`
+/// placeholder.
+fn command_line_args(
+    arguments: &[ArgumentType],
+    file_path: &Path,
+    file_names: &[String],
+    source_path: &Path,
+) -> Vec<OsString> {
+    let placeholders = ArgumentPlaceholders {
+        file: file_path.to_string_lossy().to_string(),
+        dir: source_path.to_string_lossy().to_string(),
+        file_stem: file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        files: file_names
+            .iter()
+            .map(|file_name| source_path.join(file_name).to_string_lossy().to_string())
+            .collect(),
+    };
+    let substituted: Vec<ArgumentType> = arguments
+        .iter()
+        .flat_map(|argument| argument.substitute_placeholders(&placeholders))
+        .collect();
+
+    let mut args: Vec<OsString> = expand_arguments(&substituted)
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+    if !arguments.iter().any(ArgumentType::has_file_placeholder) {
+        args.push(file_path.as_os_str().to_os_string());
+    }
+    args
+}
+
+/// Expand the arguments to command line arguments passed to the executable. Values are passed
+/// as single arguments as-is, so values containing spaces don't need quoting.
+fn expand_arguments(arguments: &[ArgumentType]) -> Vec<String> {
+    let mut args = Vec::new();
+    arguments.iter().for_each(|arg| match arg {
+        ArgumentType::Flag { name } => {
+            args.push(name.clone());
+        }
+        ArgumentType::FlagWithValue { name, value } => {
+            args.extend_from_slice(&[name.clone(), value.clone()]);
+        }
+        ArgumentType::FlagEqualsValue { name, value } => {
+            args.push(format!("{}={}", name, value));
+        }
+        ArgumentType::Positional { value } => {
+            args.push(value.clone());
+        }
+    });
+    args
 }
 
 #[cfg(test)]
@@ -115,8 +436,426 @@ mod tests {
             &file_names,
             selected_file_name,
             source_path,
+            None,
         )
         .await;
         assert!(result.is_ok(), "Executable run failed: {:?}", result);
     }
+
+    #[async_std::test]
+    async fn test_run_selected_file_not_in_set() {
+        let temp_dir = tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("disk1.d64"), "test data").unwrap();
+        std::fs::write(temp_dir.path().join("other.d64"), "test data").unwrap();
+
+        let result = run(
+            "echo".to_string(),
+            &[],
+            &["disk1.d64".to_string(), "disk2.d64".to_string()],
+            "other.d64".to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions::default(),
+            None,
+        )
+        .await;
+
+        assert!(
+            matches!(result, Err(ExecutableRunnerError::SelectedFileNotInSet(ref name)) if name == "other.d64"),
+            "{:?}",
+            result
+        );
+    }
+
+    #[async_std::test]
+    async fn test_run_returns_stderr_on_failure() {
+        let temp_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(temp_dir.path().join(file_name), "test data").unwrap();
+        let script = "for i in $(seq 1 30); do echo line$i >&2; done; echo output; exit 3";
+
+        let result = run(
+            "sh".to_string(),
+            &[ArgumentType::FlagWithValue {
+                name: "-c".to_string(),
+                value: script.to_string(),
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions::default(),
+            None,
+        )
+        .await;
+
+        let Err(ExecutableRunnerError::NonZeroExit { code, stderr }) = result else {
+            panic!("Expected NonZeroExit, got {:?}", result);
+        };
+        assert_eq!(code, Some(3));
+        // Only the last lines are kept
+        assert!(stderr.starts_with("line11\n"), "{}", stderr);
+        assert!(stderr.ends_with("line30"), "{}", stderr);
+        assert!(!stderr.contains("output"));
+    }
+
+    #[async_std::test]
+    async fn test_run_returns_when_forked_process_keeps_stderr_open() {
+        let temp_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(temp_dir.path().join(file_name), "test data").unwrap();
+        // Like xdg-open, the executable exits while the started process keeps stderr open
+        let script = "sleep 5 > /dev/null & echo failed >&2; exit 2";
+
+        let started = std::time::Instant::now();
+        let result = run(
+            "sh".to_string(),
+            &[ArgumentType::FlagWithValue {
+                name: "-c".to_string(),
+                value: script.to_string(),
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions::default(),
+            None,
+        )
+        .await;
+
+        assert!(started.elapsed() < Duration::from_secs(3));
+        let Err(ExecutableRunnerError::NonZeroExit { code, stderr }) = result else {
+            panic!("Expected NonZeroExit, got {:?}", result);
+        };
+        assert_eq!(code, Some(2));
+        assert_eq!(stderr, "failed");
+    }
+
+    #[async_std::test]
+    async fn test_run_kills_executable_after_timeout() {
+        let temp_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(temp_dir.path().join(file_name), "test data").unwrap();
+        let pid_file = temp_dir.path().join("pid");
+        let script = format!("echo $$ > {}; exec sleep 10", pid_file.display());
+
+        let started = std::time::Instant::now();
+        let result = run(
+            "sh".to_string(),
+            &[ArgumentType::FlagWithValue {
+                name: "-c".to_string(),
+                value: script,
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions {
+                timeout: Some(Duration::from_millis(500)),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+        assert!(matches!(result, Err(ExecutableRunnerError::TimedOut)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let pid = std::fs::read_to_string(pid_file).unwrap();
+        let process_exists = std::process::Command::new("kill")
+            .args(["-0", pid.trim()])
+            .status()
+            .unwrap()
+            .success();
+        assert!(!process_exists);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[async_std::test]
+    async fn test_run_kills_forked_processes_after_timeout() {
+        let temp_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(temp_dir.path().join(file_name), "test data").unwrap();
+        let pid_file = temp_dir.path().join("pid");
+        let script = format!(
+            "sleep 10 > /dev/null 2>&1 & echo $! > {}; wait",
+            pid_file.display()
+        );
+
+        let result = run(
+            "sh".to_string(),
+            &[ArgumentType::FlagWithValue {
+                name: "-c".to_string(),
+                value: script,
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions {
+                timeout: Some(Duration::from_millis(500)),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+        assert!(matches!(result, Err(ExecutableRunnerError::TimedOut)));
+        let pid = std::fs::read_to_string(pid_file).unwrap();
+        let stat_path = format!("/proc/{}/stat", pid.trim());
+        // The killed process is a zombie until it's reaped by its new parent
+        let is_running = || {
+            std::fs::read_to_string(&stat_path).is_ok_and(|stat| {
+                !stat
+                    .rsplit(')')
+                    .next()
+                    .unwrap_or_default()
+                    .starts_with(" Z")
+            })
+        };
+        for _ in 0..20 {
+            if !is_running() {
+                break;
+            }
+            async_std::task::sleep(Duration::from_millis(100)).await;
+        }
+        assert!(!is_running());
+    }
+
+    #[test]
+    fn test_command_line_args_with_files_placeholder() {
+        let file_names = vec![
+            "disk1.d64".to_string(),
+            "disk2.d64".to_string(),
+            "disk3.d64".to_string(),
+        ];
+        let arguments = core_types::parse_arguments("-flipname {files} -autostart {file}");
+        let args = command_line_args(
+            &arguments,
+            Path::new("/roms/disk1.d64"),
+            &file_names,
+            Path::new("/roms"),
+        );
+
+        assert_eq!(
+            args,
+            vec![
+                "-flipname",
+                "/roms/disk1.d64",
+                "-flipname",
+                "/roms/disk2.d64",
+                "-flipname",
+                "/roms/disk3.d64",
+                "-autostart",
+                "/roms/disk1.d64"
+            ]
+        );
+        for file_name in &file_names {
+            let path = OsString::from(format!("/roms/{}", file_name));
+            assert!(args.contains(&path), "{:?} not in {:?}", path, args);
+        }
+    }
+
+    #[test]
+    fn test_build_command_string() {
+        let file_names = vec!["game.d64".to_string()];
+        let command = build_command_string(
+            "x64sc",
+            &[
+                ArgumentType::Flag {
+                    name: "-autostart-warp".to_string(),
+                },
+                ArgumentType::FlagWithValue {
+                    name: "-config".to_string(),
+                    value: "my config.ini".to_string(),
+                },
+                ArgumentType::FlagEqualsValue {
+                    name: "--model".to_string(),
+                    value: "c64c".to_string(),
+                },
+            ],
+            &file_names,
+            "game.d64",
+            Path::new("/roms"),
+            None,
+        );
+
+        assert_eq!(
+            command,
+            "x64sc -autostart-warp -config 'my config.ini' --model=c64c /roms/game.d64"
+        );
+    }
+
+    #[test]
+    fn test_build_command_string_quotes_file_path() {
+        let file_names = vec!["Bob's Game.d64".to_string()];
+        let command = build_command_string(
+            "/opt/vice/bin/x64sc",
+            &[],
+            &file_names,
+            "Bob's Game.d64",
+            Path::new("/roms"),
+            None,
+        );
+
+        assert_eq!(command, "/opt/vice/bin/x64sc '/roms/Bob'\\''s Game.d64'");
+    }
+
+    #[test]
+    fn test_build_command_string_with_launcher() {
+        let file_names = vec!["game.d64".to_string()];
+        let launcher = ["flatpak", "run", "org.vice_emu.VICE"].map(String::from);
+        let command = build_command_string(
+            "x64sc",
+            &[ArgumentType::Flag {
+                name: "-autostart-warp".to_string(),
+            }],
+            &file_names,
+            "game.d64",
+            Path::new("/roms"),
+            Some(&launcher),
+        );
+
+        assert_eq!(
+            command,
+            "flatpak run org.vice_emu.VICE x64sc -autostart-warp /roms/game.d64"
+        );
+    }
+
+    #[async_std::test]
+    async fn test_run_with_launcher() {
+        let temp_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(temp_dir.path().join(file_name), "test data").unwrap();
+        // Launcher writing the command it's asked to run to a file
+        let launcher = ["sh", "-c", "echo \"$@\" > out.txt", "launcher"].map(String::from);
+
+        let result = run(
+            "x64sc".to_string(),
+            &[ArgumentType::Flag {
+                name: "-autostart-warp".to_string(),
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions {
+                launcher: Some(launcher.to_vec()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+        assert!(result.is_ok(), "Executable run failed: {:?}", result);
+        let output = std::fs::read_to_string(temp_dir.path().join("out.txt")).unwrap();
+        assert_eq!(
+            output.trim(),
+            format!(
+                "x64sc -autostart-warp {}",
+                temp_dir.path().join(file_name).display()
+            )
+        );
+    }
+
+    #[async_std::test]
+    async fn test_run_cancelled() {
+        let temp_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(temp_dir.path().join(file_name), "test data").unwrap();
+        let (cancel_tx, cancel_rx) = flume::unbounded();
+
+        async_std::task::spawn(async move {
+            async_std::task::sleep(Duration::from_millis(200)).await;
+            cancel_tx.send(()).unwrap();
+        });
+        let started = std::time::Instant::now();
+        let result = run(
+            "sh".to_string(),
+            &[ArgumentType::FlagWithValue {
+                name: "-c".to_string(),
+                value: "exec sleep 10".to_string(),
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            temp_dir.path().to_path_buf(),
+            &RunOptions::default(),
+            Some(&cancel_rx),
+        )
+        .await;
+
+        assert!(matches!(result, Err(ExecutableRunnerError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[async_std::test]
+    async fn test_run_with_env_and_working_dir() {
+        let source_dir = tempdir().unwrap();
+        let working_dir = tempdir().unwrap();
+        let file_name = "test.d64";
+        std::fs::write(source_dir.path().join(file_name), "test data").unwrap();
+
+        let result = run(
+            "sh".to_string(),
+            &[ArgumentType::FlagWithValue {
+                name: "-c".to_string(),
+                value: "printenv SDL_VIDEODRIVER > out.txt; pwd >> out.txt".to_string(),
+            }],
+            &[file_name.to_string()],
+            file_name.to_string(),
+            source_dir.path().to_path_buf(),
+            &RunOptions {
+                env: vec![("SDL_VIDEODRIVER".to_string(), "wayland".to_string())],
+                working_dir: Some(working_dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await;
+
+        assert!(result.is_ok(), "{:?}", result);
+        let output = std::fs::read_to_string(working_dir.path().join("out.txt")).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "wayland");
+        assert_eq!(
+            Path::new(lines[1]).canonicalize().unwrap(),
+            working_dir.path().canonicalize().unwrap()
+        );
+        assert!(!source_dir.path().join("out.txt").exists());
+    }
+
+    #[test]
+    fn test_expand_arguments() {
+        let arguments =
+            core_types::parse_arguments(r#"-cart1 "my cart.crt" -cart2 b.crt --mode=pal -warp x"#);
+        assert_eq!(
+            expand_arguments(&arguments),
+            vec![
+                "-cart1",
+                "my cart.crt",
+                "-cart2",
+                "b.crt",
+                "--mode=pal",
+                "-warp",
+                "x"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_line_args_appends_file() {
+        let file_path = Path::new("/roms/game.d64");
+        let arguments = core_types::parse_arguments("-autostart-warp -rompath {dir}");
+        assert_eq!(
+            command_line_args(&arguments, file_path, &[], Path::new("/roms")),
+            vec!["-autostart-warp", "-rompath", "/roms", "/roms/game.d64"]
+        );
+        assert_eq!(
+            command_line_args(&[], file_path, &[], Path::new("/roms")),
+            vec!["/roms/game.d64"]
+        );
+    }
+
+    #[test]
+    fn test_command_line_args_with_file_placeholder() {
+        let file_path = Path::new("/roms/game.zip");
+        let arguments = core_types::parse_arguments("{file_stem} -cart {file} -rompath {dir}");
+        assert_eq!(
+            command_line_args(&arguments, file_path, &[], Path::new("/roms")),
+            vec!["game", "-cart", "/roms/game.zip", "-rompath", "/roms"]
+        );
+    }
 }
@@ -1,9 +1,10 @@
 use crate::error::ExecutableRunnerError;
-#[allow(deprecated)]
-use crate::run_executable;
+use crate::{RunOptions, run};
 use core_types::ArgumentType;
+use flume::Receiver;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 // TODO: this should be more generic, like ExternalExecutableRunnerOps
 /// Trait for executable runner operations.
@@ -20,6 +21,7 @@ pub trait ExecutableRunnerOps: Send + Sync {
     /// * `file_names` - Vector of file names to be used with executable
     /// * `selected_file_name` - Entry point file in the set of files
     /// * `source_path` - Path where the files are located
+    /// * `cancel_rx` - Kills the executable when a message is received
     ///
     /// # Returns
     /// * `Ok(())` on successful execution
@@ -31,15 +33,57 @@ pub trait ExecutableRunnerOps: Send + Sync {
         file_names: &[String],
         selected_file_name: String,
         source_path: PathBuf,
+        cancel_rx: Option<Receiver<()>>,
     ) -> Result<(), ExecutableRunnerError>;
 }
 
 /// Default implementation that performs actual executable execution.
-pub struct DefaultExecutableRunnerOps;
+///
+/// The last lines of stderr are returned in `ExecutableRunnerError::NonZeroExit` when the
+/// executable fails. Output is written to the console unless verbose mode is turned off.
+pub struct DefaultExecutableRunnerOps {
+    options: RunOptions,
+}
 
 impl DefaultExecutableRunnerOps {
     pub fn new() -> Self {
-        Self
+        Self {
+            options: RunOptions {
+                verbose: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Write the output of the executable to the console, enabled by default.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.options.verbose = verbose;
+        self
+    }
+
+    /// Kill the executable and return `ExecutableRunnerError::TimedOut` if it runs longer than
+    /// `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.options.env.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Run executables in `working_dir` instead of the directory of the files.
+    pub fn with_working_dir(mut self, working_dir: PathBuf) -> Self {
+        self.options.working_dir = Some(working_dir);
+        self
+    }
+
+    /// Run executables through a launcher such as `["flatpak", "run", "org.vice_emu.VICE"]` or
+    /// `["wine"]`.
+    pub fn with_launcher(mut self, launcher: Vec<String>) -> Self {
+        self.options.launcher = Some(launcher);
+        self
     }
 }
 
@@ -58,14 +102,16 @@ impl ExecutableRunnerOps for DefaultExecutableRunnerOps {
         file_names: &[String],
         selected_file_name: String,
         source_path: PathBuf,
+        cancel_rx: Option<Receiver<()>>,
     ) -> Result<(), ExecutableRunnerError> {
-        #[allow(deprecated)]
-        run_executable(
+        run(
             executable,
             arguments,
             file_names,
             selected_file_name,
             source_path,
+            &self.options,
+            cancel_rx.as_ref(),
         )
         .await
     }
@@ -110,6 +156,7 @@ pub struct ExecutableRunCall {
 ///         &["game.rom".to_string()],
 ///         "game.rom".to_string(),
 ///         PathBuf::from("/games"),
+///         None,
 ///     ).await;
 ///     assert!(result.is_ok());
 ///
@@ -177,6 +224,7 @@ impl ExecutableRunnerOps for MockExecutableRunnerOps {
         file_names: &[String],
         selected_file_name: String,
         source_path: PathBuf,
+        _cancel_rx: Option<Receiver<()>>,
     ) -> Result<(), ExecutableRunnerError> {
         let call = ExecutableRunCall {
             executable: executable.clone(),
@@ -217,6 +265,7 @@ mod tests {
                 &["game.rom".to_string()],
                 "game.rom".to_string(),
                 PathBuf::from("/games"),
+                None,
             )
             .await;
 
@@ -245,6 +294,7 @@ mod tests {
                 &["game.rom".to_string()],
                 "game.rom".to_string(),
                 PathBuf::from("/games"),
+                None,
             )
             .await;
 
@@ -271,6 +321,7 @@ mod tests {
             &["game1.rom".to_string()],
             "game1.rom".to_string(),
             PathBuf::from("/games"),
+            None,
         )
         .await
         .unwrap();
@@ -284,6 +335,7 @@ mod tests {
             &["game2.rom".to_string()],
             "game2.rom".to_string(),
             PathBuf::from("/other"),
+            None,
         )
         .await
         .unwrap();
@@ -1,4 +1,4 @@
-use crate::{export_files, export_files_zipped, FileExportError, FileSetExportModel};
+use crate::{export_files, export_files_zipped, ExportResult, FileExportError, FileSetExportModel};
 use std::sync::{Arc, Mutex};
 
 /// Trait for file export operations.
@@ -12,9 +12,9 @@ pub trait FileExportOps: Send + Sync {
     /// * `export_model` - Configuration model containing source paths, output mappings, and checksums
     ///
     /// # Returns
-    /// * `Ok(())` on successful export
+    /// * `Ok(ExportResult)` with the per-file outcomes
     /// * `Err(FileExportError)` if export fails
-    fn export(&self, export_model: &FileSetExportModel) -> Result<(), FileExportError>;
+    fn export(&self, export_model: &FileSetExportModel) -> Result<ExportResult, FileExportError>;
 
     /// Exports files from zstd archive to a single zip file.
     ///
@@ -22,20 +22,26 @@ pub trait FileExportOps: Send + Sync {
     /// * `export_model` - Configuration model containing source paths, output mappings, and checksums
     ///
     /// # Returns
-    /// * `Ok(())` on successful export
+    /// * `Ok(ExportResult)` with the per-file outcomes
     /// * `Err(FileExportError)` if export fails
-    fn export_zipped(&self, export_model: &FileSetExportModel) -> Result<(), FileExportError>;
+    fn export_zipped(
+        &self,
+        export_model: &FileSetExportModel,
+    ) -> Result<ExportResult, FileExportError>;
 }
 
 /// Default implementation that performs actual file export operations.
 pub struct DefaultFileExportOps;
 
 impl FileExportOps for DefaultFileExportOps {
-    fn export(&self, export_model: &FileSetExportModel) -> Result<(), FileExportError> {
+    fn export(&self, export_model: &FileSetExportModel) -> Result<ExportResult, FileExportError> {
         export_files(export_model)
     }
 
-    fn export_zipped(&self, export_model: &FileSetExportModel) -> Result<(), FileExportError> {
+    fn export_zipped(
+        &self,
+        export_model: &FileSetExportModel,
+    ) -> Result<ExportResult, FileExportError> {
         export_files_zipped(export_model)
     }
 }
@@ -62,7 +68,7 @@ pub struct ExportCall {
 ///
 /// ```
 /// use file_export::file_export_ops::{FileExportOps, MockFileExportOps};
-/// use file_export::{FileSetExportModel, OutputFile};
+/// use file_export::{ExportContainer, FileSetExportModel, OutputFile};
 /// use std::collections::HashMap;
 /// use std::path::PathBuf;
 /// use core_types::Sha1Checksum;
@@ -83,6 +89,10 @@ pub struct ExportCall {
 ///     source_file_path: PathBuf::from("/source"),
 ///     extract_files: false,
 ///     exported_zip_file_name: "test.zip".to_string(),
+///     skip_existing: false,
+///     container: ExportContainer::Zip,
+///     zip_compression: None,
+///     fail_fast: true,
 ///     output_dir: PathBuf::from("/output"),
 /// };
 ///
@@ -150,7 +160,7 @@ impl MockFileExportOps {
 }
 
 impl FileExportOps for MockFileExportOps {
-    fn export(&self, export_model: &FileSetExportModel) -> Result<(), FileExportError> {
+    fn export(&self, export_model: &FileSetExportModel) -> Result<ExportResult, FileExportError> {
         let call = ExportCall {
             output_file_names: export_model
                 .output_mapping
@@ -169,10 +179,13 @@ impl FileExportOps for MockFileExportOps {
                     .unwrap_or_else(|| "Mock export failed".to_string()),
             ));
         }
-        Ok(())
+        Ok(exported_result(export_model))
     }
 
-    fn export_zipped(&self, export_model: &FileSetExportModel) -> Result<(), FileExportError> {
+    fn export_zipped(
+        &self,
+        export_model: &FileSetExportModel,
+    ) -> Result<ExportResult, FileExportError> {
         let call = ExportCall {
             output_file_names: export_model
                 .output_mapping
@@ -191,7 +204,19 @@ impl FileExportOps for MockFileExportOps {
                     .unwrap_or_else(|| "Mock export zipped failed".to_string()),
             ));
         }
-        Ok(())
+        Ok(exported_result(export_model))
+    }
+}
+
+/// Result of a mocked export where all files of the model were exported.
+fn exported_result(export_model: &FileSetExportModel) -> ExportResult {
+    ExportResult {
+        exported: export_model
+            .output_mapping
+            .values()
+            .map(|f| f.output_file_name.clone())
+            .collect(),
+        ..Default::default()
     }
 }
 
@@ -203,7 +228,7 @@ mod tests {
 
     use crate::{
         file_export_ops::{FileExportOps, MockFileExportOps},
-        FileExportError, FileSetExportModel, OutputFile,
+        ExportContainer, FileExportError, FileSetExportModel, OutputFile,
     };
 
     #[test]
@@ -224,12 +249,16 @@ mod tests {
             source_file_path: PathBuf::from("/source"),
             extract_files: false,
             exported_zip_file_name: "test.zip".to_string(),
+            skip_existing: false,
+            container: ExportContainer::Zip,
+            zip_compression: None,
+            fail_fast: true,
             output_dir: PathBuf::from("/output"),
         };
 
         // Test successful export
-        let result = mock.export_zipped(&export_model);
-        assert!(result.is_ok());
+        let result = mock.export_zipped(&export_model).unwrap();
+        assert_eq!(result.exported, vec!["output_file.rom"]);
 
         // Verify the call was tracked
         assert_eq!(mock.total_calls(), 1);
@@ -259,6 +288,10 @@ mod tests {
             source_file_path: PathBuf::from("/source"),
             extract_files: true,
             exported_zip_file_name: "test.zip".to_string(),
+            skip_existing: false,
+            container: ExportContainer::Zip,
+            zip_compression: None,
+            fail_fast: true,
             output_dir: PathBuf::from("/output"),
         };
 
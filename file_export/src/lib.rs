@@ -2,11 +2,19 @@ pub mod file_export_ops;
 use std::{
     collections::HashMap,
     fs::File,
-    io::Read,
+    io::{Read, Seek, SeekFrom, Write},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
 };
 
-use core_types::Sha1Checksum;
+use core_types::{Sha1Checksum, events::ExportEvent};
+use flate2::{Compression, write::GzEncoder};
+use flume::{Receiver, Sender};
 use sha1::{Digest, Sha1};
 use zip::write::FileOptions;
 
@@ -14,6 +22,7 @@ use zip::write::FileOptions;
 pub enum FileExportError {
     ZipError(String),
     FileIoError(String),
+    Cancelled,
 }
 
 impl std::fmt::Display for FileExportError {
@@ -21,6 +30,7 @@ impl std::fmt::Display for FileExportError {
         match self {
             FileExportError::ZipError(err) => write!(f, "Zip error: {}", err),
             FileExportError::FileIoError(err) => write!(f, "File IO error: {}", err),
+            FileExportError::Cancelled => write!(f, "Export cancelled"),
         }
     }
 }
@@ -31,6 +41,37 @@ pub struct OutputFile {
     pub checksum: Sha1Checksum,
 }
 
+/// Container format used when files are exported without extracting them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportContainer {
+    #[default]
+    Zip,
+    TarGz,
+}
+
+/// Compression applied to the files added to an exported zip archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZipCompression {
+    /// Files are stored without compression. Useful for already compressed data.
+    Stored,
+    /// Files are compressed with deflate using the given level (0-9). `None` uses the default
+    /// level.
+    Deflated(Option<i64>),
+}
+
+impl ZipCompression {
+    fn file_options(self) -> FileOptions<'static, ()> {
+        match self {
+            ZipCompression::Stored => {
+                FileOptions::default().compression_method(zip::CompressionMethod::Stored)
+            }
+            ZipCompression::Deflated(level) => FileOptions::default()
+                .compression_method(zip::CompressionMethod::Deflated)
+                .compression_level(level),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct FileSetExportModel {
     pub output_mapping: HashMap<String, OutputFile>,
@@ -38,18 +79,114 @@ pub struct FileSetExportModel {
     pub extract_files: bool,
     pub exported_zip_file_name: String,
     pub output_dir: PathBuf,
+    /// When exporting extracted files, skip files that already exist in the output directory
+    /// with a matching checksum.
+    pub skip_existing: bool,
+    pub container: ExportContainer,
+    /// Compression used for zip exports. `None` uses the zip crate defaults.
+    pub zip_compression: Option<ZipCompression>,
+    /// When `true`, export is aborted on the first failed file. Otherwise the remaining files
+    /// are exported and failures are collected to [`ExportResult::failed`].
+    pub fail_fast: bool,
+}
+
+/// Per-file outcome of an export. Files are identified by their output file names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportResult {
+    pub exported: Vec<String>,
+    /// Files that already existed in the output directory with a matching checksum.
+    pub skipped: Vec<String>,
+    /// Failed files with their error messages.
+    pub failed: Vec<(String, String)>,
+}
+
+impl ExportResult {
+    fn add_outcome(&mut self, output_file_name: &str, outcome: FileOutcome) {
+        match outcome {
+            FileOutcome::Exported => self.exported.push(output_file_name.to_string()),
+            FileOutcome::Skipped => self.skipped.push(output_file_name.to_string()),
+        }
+    }
+
+    /// Records a failed file, or returns the error when export should be aborted.
+    fn add_failure(
+        &mut self,
+        output_file_name: &str,
+        error: FileExportError,
+        fail_fast: bool,
+    ) -> Result<(), FileExportError> {
+        if fail_fast {
+            return Err(error);
+        }
+        tracing::warn!(file = output_file_name, error = %error, "Failed exporting file");
+        self.failed
+            .push((output_file_name.to_string(), error.to_string()));
+        Ok(())
+    }
+}
+
+/// Files an export would write, see [`plan_export`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportPlan {
+    /// Output file names with their decompressed sizes when known from the zstd frame header.
+    pub files: Vec<(String, Option<u64>)>,
+    /// Archive file names whose source `.zst` file doesn't exist.
+    pub missing_sources: Vec<String>,
+}
+
+impl ExportPlan {
+    /// Sum of the known decompressed sizes.
+    pub fn known_total_size(&self) -> u64 {
+        self.files.iter().filter_map(|(_, size)| *size).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileOutcome {
+    Exported,
+    Skipped,
 }
 
 pub fn export_files_zipped_or_non_zipped(
     export_model: &FileSetExportModel,
-) -> Result<(), FileExportError> {
+) -> Result<ExportResult, FileExportError> {
     if export_model.extract_files {
-        export_files(export_model)
-    } else {
-        export_files_zipped(export_model)
+        return export_files(export_model);
+    }
+    match export_model.container {
+        ExportContainer::Zip => export_files_zipped(export_model),
+        ExportContainer::TarGz => export_files_tar_gz(export_model),
     }
 }
 
+/// Resolves which files an export would write without writing anything. Only the zstd frame
+/// headers of the source files are read.
+pub fn plan_export(export_model: &FileSetExportModel) -> Result<ExportPlan, FileExportError> {
+    let mut plan = ExportPlan::default();
+    for (archive_file_name, output_file) in &export_model.output_mapping {
+        let file_path = export_model
+            .source_file_path
+            .join(archive_file_name)
+            .with_extension("zst");
+        if !file_path.exists() {
+            plan.missing_sources.push(archive_file_name.clone());
+            continue;
+        }
+        let decompressed_size = read_zstd_content_size(&file_path).map_err(|e| {
+            FileExportError::FileIoError(format!(
+                "Failed reading zstd frame header of {}: {}",
+                file_path.display(),
+                e
+            ))
+        })?;
+        plan.files
+            .push((output_file.output_file_name.clone(), decompressed_size));
+    }
+    plan.files.sort();
+    plan.missing_sources.sort();
+    Ok(plan)
+}
+
 /// Exports files from a given zstd archive directory to an output directory decompressed and with given output file name
 /// mapping. Files are also checked for their SHA1 checksums provided in filename checksum map.
 ///
@@ -62,40 +199,192 @@ pub fn export_files_zipped_or_non_zipped(
 ///
 /// # Returns
 ///
-/// A `Result` indicating success or failure of the operation.
+/// An `ExportResult` with the per-file outcomes, or an error if the export was aborted.
 ///
-pub fn export_files(export_model: &FileSetExportModel) -> Result<(), FileExportError> {
+pub fn export_files(export_model: &FileSetExportModel) -> Result<ExportResult, FileExportError> {
+    export_files_with_progress(export_model, None, None)
+}
+
+/// Same as [`export_files`], but sends an `ExportEvent::FileExported` event after each exported
+/// file. Cancel messages are checked before each file, and a received message aborts the export
+/// with `FileExportError::Cancelled`.
+pub fn export_files_with_progress(
+    export_model: &FileSetExportModel,
+    progress_tx: Option<&Sender<ExportEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+) -> Result<ExportResult, FileExportError> {
+    export_files_with_decompressor(export_model, progress_tx, cancel_rx, &decompress_zstd_file)
+}
+
+/// Decompresses a source file to the output path.
+type Decompressor<'a> = dyn Fn(&Path, &Path) -> Result<(), Box<dyn std::error::Error>> + Sync + 'a;
+
+fn export_files_with_decompressor(
+    export_model: &FileSetExportModel,
+    progress_tx: Option<&Sender<ExportEvent>>,
+    cancel_rx: Option<&Receiver<()>>,
+    decompress: &Decompressor<'_>,
+) -> Result<ExportResult, FileExportError> {
     dbg!(
         "Exporting files with mapping {}",
         &export_model.output_mapping
     );
-    let mut output_file_names: Vec<String> = Vec::new();
-    for (archive_file_name, output_file) in &export_model.output_mapping {
-        output_file_names.push(output_file.output_file_name.clone());
-        // souce files are in zstd format
-        let file_path = export_model
-            .source_file_path
-            .join(archive_file_name)
-            .with_extension("zst");
-        let output_file_path = &export_model.output_dir.join(&output_file.output_file_name);
-        decompress_zstd_file(&file_path, output_file_path).map_err(|err| {
-            FileExportError::ZipError(format!("Failed decompressing zstd file: {}", err))
-        })?;
+    let total = export_model.output_mapping.len();
+    let mut export_result = ExportResult::default();
+    for (index, (archive_file_name, output_file)) in export_model.output_mapping.iter().enumerate()
+    {
+        if cancel_rx.is_some_and(|rx| rx.try_recv().is_ok()) {
+            return Err(FileExportError::Cancelled);
+        }
+        match export_file(export_model, archive_file_name, output_file, decompress) {
+            Ok(outcome) => export_result.add_outcome(&output_file.output_file_name, outcome),
+            Err(e) => {
+                export_result.add_failure(
+                    &output_file.output_file_name,
+                    e,
+                    export_model.fail_fast,
+                )?;
+                continue;
+            }
+        }
 
-        check_file_checksum(
-            output_file_path,
-            &export_model
-                .output_mapping
-                .get(archive_file_name)
-                .unwrap()
-                .checksum,
-        )
-        .map_err(|e| {
-            FileExportError::FileIoError(format!(
-                "Checksum verification failed for file: {}. Error: {}",
-                archive_file_name, e
-            ))
-        })?;
+        if let Some(tx) = progress_tx
+            && let Err(e) = tx.send(ExportEvent::FileExported {
+                name: output_file.output_file_name.clone(),
+                index: index + 1,
+                total,
+            })
+        {
+            tracing::warn!(error = %e, "Failed to send export progress event");
+        }
+    }
+    Ok(export_result)
+}
+
+/// Same as [`export_files`], but exports files in parallel with at most `max_workers` threads.
+/// When `max_workers` is `None`, the number of available CPUs is used.
+///
+/// With `fail_fast` no new files are started after the first failure, files already being
+/// exported are finished. The file names in the result are sorted.
+pub fn export_files_parallel(
+    export_model: &FileSetExportModel,
+    max_workers: Option<NonZeroUsize>,
+) -> Result<ExportResult, FileExportError> {
+    let entries: Vec<(&String, &OutputFile)> = export_model.output_mapping.iter().collect();
+    let max_workers = max_workers
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+    let worker_count = max_workers.min(entries.len());
+    let next_entry = AtomicUsize::new(0);
+    // Export result and the error aborting the export
+    let state: Mutex<(ExportResult, Option<FileExportError>)> = Mutex::default();
+
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| {
+                while let Some((archive_file_name, output_file)) =
+                    entries.get(next_entry.fetch_add(1, Ordering::Relaxed))
+                {
+                    if state.lock().unwrap().1.is_some() {
+                        break;
+                    }
+                    let outcome = export_file(
+                        export_model,
+                        archive_file_name,
+                        output_file,
+                        &decompress_zstd_file,
+                    );
+                    let (export_result, abort_error) = &mut *state.lock().unwrap();
+                    let output_file_name = &output_file.output_file_name;
+                    match outcome {
+                        Ok(outcome) => export_result.add_outcome(output_file_name, outcome),
+                        Err(e) => {
+                            if let Err(e) = export_result.add_failure(
+                                output_file_name,
+                                e,
+                                export_model.fail_fast,
+                            ) {
+                                abort_error.get_or_insert(e);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let (mut export_result, abort_error) = state.into_inner().unwrap();
+    if let Some(e) = abort_error {
+        return Err(e);
+    }
+    export_result.exported.sort();
+    export_result.skipped.sort();
+    export_result.failed.sort();
+    Ok(export_result)
+}
+
+/// Decompresses a single file to the output directory and verifies its checksum.
+fn export_file(
+    export_model: &FileSetExportModel,
+    archive_file_name: &str,
+    output_file: &OutputFile,
+    decompress: &Decompressor<'_>,
+) -> Result<FileOutcome, FileExportError> {
+    // souce files are in zstd format
+    let file_path = export_model
+        .source_file_path
+        .join(archive_file_name)
+        .with_extension("zst");
+    let output_file_path = &export_model.output_dir.join(&output_file.output_file_name);
+    if export_model.skip_existing
+        && output_file_path.exists()
+        && check_file_checksum(output_file_path, &output_file.checksum).unwrap_or(false)
+    {
+        tracing::info!(
+            output_file = %output_file_path.display(),
+            "File already present in output directory, skipping"
+        );
+        return Ok(FileOutcome::Skipped);
+    }
+    let result = decompress_and_check_file(
+        decompress,
+        &file_path,
+        output_file_path,
+        archive_file_name,
+        &output_file.checksum,
+    );
+    if result.is_err() && output_file_path.exists() {
+        // Don't leave a partial or corrupted file to the output directory
+        if let Err(e) = std::fs::remove_file(output_file_path) {
+            tracing::warn!(
+                output_file = %output_file_path.display(),
+                error = %e,
+                "Failed removing failed export file"
+            );
+        }
+    }
+    result.map(|()| FileOutcome::Exported)
+}
+
+fn decompress_and_check_file(
+    decompress: &Decompressor<'_>,
+    file_path: &Path,
+    output_file_path: &Path,
+    archive_file_name: &str,
+    checksum: &Sha1Checksum,
+) -> Result<(), FileExportError> {
+    decompress(file_path, output_file_path).map_err(|err| {
+        FileExportError::ZipError(format!("Failed decompressing zstd file: {}", err))
+    })?;
+
+    let is_valid = check_file_checksum(output_file_path, checksum).map_err(|e| {
+        FileExportError::FileIoError(format!(
+            "Checksum verification failed for file: {}. Error: {}",
+            archive_file_name, e
+        ))
+    })?;
+    if !is_valid {
+        return Err(checksum_mismatch_error(archive_file_name));
     }
     Ok(())
 }
@@ -112,15 +401,35 @@ pub fn export_files(export_model: &FileSetExportModel) -> Result<(), FileExportE
 ///
 /// # Returns
 ///
-/// A `Result` indicating success or failure of the operation.
-pub fn export_files_zipped(export_model: &FileSetExportModel) -> Result<(), FileExportError> {
+/// An `ExportResult` with the per-file outcomes, or an error if the export was aborted. Failed
+/// files are left out of the zip archive.
+pub fn export_files_zipped(
+    export_model: &FileSetExportModel,
+) -> Result<ExportResult, FileExportError> {
     let zip_path = export_model
         .output_dir
         .join(&export_model.exported_zip_file_name);
     let zip_file = File::create(zip_path)
         .map_err(|e| FileExportError::ZipError(format!("Failed creating zip file {}", e)))?;
-    let mut zip_writer = zip::ZipWriter::new(zip_file);
-    let file_options: FileOptions<'_, ()> = FileOptions::default();
+    export_files_to_writer(export_model, zip_file)
+}
+
+/// Same as [`export_files_zipped`], but the zip archive is written to the given writer instead
+/// of `output_dir`. This allows piping the export for example to a cloud storage upload without
+/// staging the archive on local disk.
+///
+/// Zip format requires the writer to be seekable. For non-seekable writers use
+/// [`export_files_tar_gz_to_writer`].
+pub fn export_files_to_writer<W: Write + Seek>(
+    export_model: &FileSetExportModel,
+    writer: W,
+) -> Result<ExportResult, FileExportError> {
+    let mut zip_writer = zip::ZipWriter::new(writer);
+    let file_options: FileOptions<'_, ()> = export_model
+        .zip_compression
+        .map(ZipCompression::file_options)
+        .unwrap_or_default();
+    let mut export_result = ExportResult::default();
 
     for (archive_file_name, output_file) in &export_model.output_mapping {
         let file_path = export_model
@@ -135,23 +444,24 @@ pub fn export_files_zipped(export_model: &FileSetExportModel) -> Result<(), File
             .map_err(|e| {
                 FileExportError::ZipError(format!("Failed starting the zip file: {}", e))
             })?;
-        decompress_zstd_to_writer(&file_path, &mut zip_writer).map_err(|e| {
-            FileExportError::ZipError(format!("Failed decompressing zstd to writer: {}", e))
-        })?;
-
-        // Verify checksum
-        if let Err(e) = check_file_checksum(
-            &file_path,
-            &export_model
-                .output_mapping
-                .get(archive_file_name)
-                .unwrap()
-                .checksum,
-        ) {
-            return Err(FileExportError::FileIoError(format!(
-                "Checksum verification failed for file: {}. Error: {}",
-                archive_file_name, e
-            )));
+        // Verify checksum of the decompressed content while it's written to the zip archive
+        match decompress_verified(&file_path, archive_file_name, output_file, &mut zip_writer) {
+            Ok(()) => {
+                export_result.add_outcome(&output_file.output_file_name, FileOutcome::Exported)
+            }
+            Err(e) => {
+                export_result.add_failure(
+                    &output_file.output_file_name,
+                    e,
+                    export_model.fail_fast,
+                )?;
+                zip_writer.abort_file().map_err(|e| {
+                    FileExportError::ZipError(format!(
+                        "Failed removing failed file from zip: {}",
+                        e
+                    ))
+                })?;
+            }
         }
     }
 
@@ -159,7 +469,78 @@ pub fn export_files_zipped(export_model: &FileSetExportModel) -> Result<(), File
         .finish()
         .map_err(|e| FileExportError::ZipError(format!("Failed finishing zip writer: {}", e)))?;
 
-    Ok(())
+    Ok(export_result)
+}
+
+/// Exports files from a given zstd archive directory to an output directory as a gzip compressed
+/// tar archive containing the files to be exported with given output file name mapping. Files are
+/// checked for their SHA1 checksums while they are decompressed.
+///
+/// Tar entry headers need the file size before the content, so each file is first decompressed to
+/// a temporary file and then appended to the archive.
+///
+/// # Arguments
+/// * `export_model` - The model containing the export configuration. Tarball is written to
+///   `output_dir` with the name `exported_zip_file_name`.
+///
+/// # Returns
+///
+/// An `ExportResult` with the per-file outcomes, or an error if the export was aborted. Failed
+/// files are left out of the tarball.
+pub fn export_files_tar_gz(
+    export_model: &FileSetExportModel,
+) -> Result<ExportResult, FileExportError> {
+    let tar_path = export_model
+        .output_dir
+        .join(&export_model.exported_zip_file_name);
+    let mut tar_file = File::create(tar_path)
+        .map_err(|e| FileExportError::FileIoError(format!("Failed creating tar.gz file {}", e)))?;
+    export_files_tar_gz_to_writer(export_model, &mut tar_file)
+}
+
+/// Same as [`export_files_tar_gz`], but the tarball is streamed to the given writer instead of
+/// `output_dir`. The writer doesn't need to be seekable.
+pub fn export_files_tar_gz_to_writer(
+    export_model: &FileSetExportModel,
+    writer: &mut dyn Write,
+) -> Result<ExportResult, FileExportError> {
+    let encoder = GzEncoder::new(writer, Compression::default());
+    let mut tar_builder = tar::Builder::new(encoder);
+    let mut export_result = ExportResult::default();
+
+    for (archive_file_name, output_file) in &export_model.output_mapping {
+        let file_path = export_model
+            .source_file_path
+            .join(archive_file_name)
+            .with_extension("zst");
+
+        let mut temp_file = tempfile::tempfile().map_err(|e| {
+            FileExportError::FileIoError(format!("Failed creating temporary file: {}", e))
+        })?;
+        if let Err(e) =
+            decompress_verified(&file_path, archive_file_name, output_file, &mut temp_file)
+        {
+            export_result.add_failure(&output_file.output_file_name, e, export_model.fail_fast)?;
+            continue;
+        }
+
+        temp_file.seek(SeekFrom::Start(0)).map_err(|e| {
+            FileExportError::FileIoError(format!("Failed rewinding temporary file: {}", e))
+        })?;
+        tar_builder
+            .append_file(&output_file.output_file_name, &mut temp_file)
+            .map_err(|e| {
+                FileExportError::FileIoError(format!("Failed appending file to tar: {}", e))
+            })?;
+        export_result.add_outcome(&output_file.output_file_name, FileOutcome::Exported);
+    }
+
+    tar_builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| FileExportError::FileIoError(format!("Failed finishing tar.gz: {}", e)))?;
+
+    Ok(export_result)
 }
 
 fn decompress_zstd_file(
@@ -182,6 +563,17 @@ fn decompress_zstd_file(
     Ok(())
 }
 
+/// Reads the decompressed content size from the zstd frame header. Returns `None` if the size
+/// wasn't stored when the file was compressed.
+fn read_zstd_content_size(file_path: &Path) -> std::io::Result<Option<u64>> {
+    // Maximum zstd frame header size
+    let mut header = Vec::with_capacity(18);
+    File::open(file_path)?.take(18).read_to_end(&mut header)?;
+    Ok(zstd::zstd_safe::get_frame_content_size(&header)
+        .ok()
+        .flatten())
+}
+
 fn decompress_zstd_to_writer(
     input_path: &Path,
     output_writer: &mut dyn std::io::Write,
@@ -192,6 +584,61 @@ fn decompress_zstd_to_writer(
     Ok(())
 }
 
+/// Decompresses a zstd file to the writer and verifies the checksum of the decompressed content.
+fn decompress_verified(
+    file_path: &Path,
+    archive_file_name: &str,
+    output_file: &OutputFile,
+    output_writer: &mut dyn Write,
+) -> Result<(), FileExportError> {
+    let mut sha1_writer = Sha1Writer::new(output_writer);
+    decompress_zstd_to_writer(file_path, &mut sha1_writer).map_err(|e| {
+        FileExportError::ZipError(format!("Failed decompressing zstd to writer: {}", e))
+    })?;
+    if sha1_writer.checksum() != output_file.checksum {
+        return Err(checksum_mismatch_error(archive_file_name));
+    }
+    Ok(())
+}
+
+fn checksum_mismatch_error(archive_file_name: &str) -> FileExportError {
+    FileExportError::FileIoError(format!(
+        "Checksum verification failed for file: {}. Checksum doesn't match",
+        archive_file_name
+    ))
+}
+
+/// Passes writes through and calculates SHA1 checksum of the written content.
+struct Sha1Writer<W: Write> {
+    inner: W,
+    hasher: Sha1,
+}
+
+impl<W: Write> Sha1Writer<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha1::new(),
+        }
+    }
+
+    fn checksum(self) -> Sha1Checksum {
+        self.hasher.finalize().into()
+    }
+}
+
+impl<W: Write> Write for Sha1Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let bytes_written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..bytes_written]);
+        Ok(bytes_written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 fn check_file_checksum(
     file_path: &Path,
     expected_checksum: &Sha1Checksum,
@@ -205,3 +652,502 @@ fn check_file_checksum(
     let calculated_checksum = calculated_checksum.as_slice();
     Ok(calculated_checksum == *expected_checksum)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const TEST_FILE_CONTENT: &[u8] = b"Hello, world!";
+    const ARCHIVE_FILE_NAME: &str = "archive_file";
+
+    fn sha1_of(content: &[u8]) -> Sha1Checksum {
+        Sha1::digest(content).into()
+    }
+
+    fn prepare_export_with_files(
+        files: &[(&str, &[u8])],
+    ) -> (tempfile::TempDir, FileSetExportModel) {
+        let temp_dir = tempdir().unwrap();
+        let source_dir = temp_dir.path().join("source");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let mut output_mapping = HashMap::new();
+        for (name, content) in files {
+            let archive_file_name = format!("archive_{}", name);
+            std::fs::write(
+                source_dir.join(&archive_file_name).with_extension("zst"),
+                zstd::encode_all(*content, 3).unwrap(),
+            )
+            .unwrap();
+            output_mapping.insert(
+                archive_file_name,
+                OutputFile {
+                    output_file_name: name.to_string(),
+                    checksum: sha1_of(content),
+                },
+            );
+        }
+        let export_model = FileSetExportModel {
+            output_mapping,
+            source_file_path: source_dir,
+            extract_files: true,
+            exported_zip_file_name: "game.zip".to_string(),
+            skip_existing: false,
+            container: ExportContainer::Zip,
+            zip_compression: None,
+            fail_fast: true,
+            output_dir,
+        };
+        (temp_dir, export_model)
+    }
+
+    fn prepare_export(
+        zst_content: &[u8],
+        checksum: Sha1Checksum,
+    ) -> (tempfile::TempDir, FileSetExportModel) {
+        let temp_dir = tempdir().unwrap();
+        let source_dir = temp_dir.path().join("source");
+        let output_dir = temp_dir.path().join("output");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+        std::fs::write(
+            source_dir.join(ARCHIVE_FILE_NAME).with_extension("zst"),
+            zst_content,
+        )
+        .unwrap();
+        let output_mapping = HashMap::from([(
+            ARCHIVE_FILE_NAME.to_string(),
+            OutputFile {
+                output_file_name: "game.rom".to_string(),
+                checksum,
+            },
+        )]);
+        let export_model = FileSetExportModel {
+            output_mapping,
+            source_file_path: source_dir,
+            extract_files: false,
+            exported_zip_file_name: "game.zip".to_string(),
+            skip_existing: false,
+            container: ExportContainer::Zip,
+            zip_compression: None,
+            fail_fast: true,
+            output_dir,
+        };
+        (temp_dir, export_model)
+    }
+
+    #[test]
+    fn test_export_files_zipped_verifies_decompressed_checksum() {
+        let compressed = zstd::encode_all(TEST_FILE_CONTENT, 3).unwrap();
+        let (_temp_dir, export_model) = prepare_export(&compressed, sha1_of(TEST_FILE_CONTENT));
+
+        let result = export_files_zipped(&export_model);
+
+        assert!(result.is_ok());
+        let zip_file = File::open(export_model.output_dir.join("game.zip")).unwrap();
+        let mut archive = zip::ZipArchive::new(zip_file).unwrap();
+        let mut content = Vec::new();
+        archive
+            .by_name("game.rom")
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+        assert_eq!(content, TEST_FILE_CONTENT);
+    }
+
+    #[test]
+    fn test_export_files_zipped_with_corrupted_content_returns_error() {
+        let compressed = zstd::encode_all(&b"Hello, w0rld!"[..], 3).unwrap();
+        let (_temp_dir, export_model) = prepare_export(&compressed, sha1_of(TEST_FILE_CONTENT));
+
+        let result = export_files_zipped(&export_model);
+
+        assert!(matches!(
+            result,
+            Err(FileExportError::FileIoError(message))
+                if message.contains("Checksum verification failed")
+        ));
+    }
+
+    #[test]
+    fn test_export_files_with_corrupted_content_returns_error() {
+        let compressed = zstd::encode_all(&b"Hello, w0rld!"[..], 3).unwrap();
+        let (_temp_dir, mut export_model) = prepare_export(&compressed, sha1_of(TEST_FILE_CONTENT));
+        export_model.extract_files = true;
+
+        let result = export_files_zipped_or_non_zipped(&export_model);
+
+        assert!(matches!(
+            result,
+            Err(FileExportError::FileIoError(message))
+                if message.contains("Checksum verification failed")
+        ));
+    }
+
+    #[test]
+    fn test_export_files_with_progress_sends_event_for_each_file() {
+        let (_temp_dir, export_model) = prepare_export_with_files(&[
+            ("disk1.d64", b"disk 1"),
+            ("disk2.d64", b"disk 2"),
+            ("disk3.d64", b"disk 3"),
+        ]);
+        let (progress_tx, progress_rx) = flume::unbounded();
+        let (_cancel_tx, cancel_rx) = flume::unbounded();
+
+        let result =
+            export_files_with_progress(&export_model, Some(&progress_tx), Some(&cancel_rx));
+
+        assert!(result.is_ok());
+        let events: Vec<ExportEvent> = progress_rx.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        for (i, event) in events.iter().enumerate() {
+            let ExportEvent::FileExported { index, total, .. } = event;
+            assert_eq!(*index, i + 1);
+            assert_eq!(*total, 3);
+        }
+        for name in ["disk1.d64", "disk2.d64", "disk3.d64"] {
+            assert!(export_model.output_dir.join(name).exists());
+        }
+    }
+
+    #[test]
+    fn test_export_files_with_progress_cancelled_before_first_file() {
+        let (_temp_dir, export_model) = prepare_export_with_files(&[
+            ("disk1.d64", b"disk 1"),
+            ("disk2.d64", b"disk 2"),
+            ("disk3.d64", b"disk 3"),
+        ]);
+        let (progress_tx, progress_rx) = flume::unbounded();
+        let (cancel_tx, cancel_rx) = flume::unbounded();
+        cancel_tx.send(()).unwrap();
+
+        let result =
+            export_files_with_progress(&export_model, Some(&progress_tx), Some(&cancel_rx));
+
+        assert!(matches!(result, Err(FileExportError::Cancelled)));
+        assert_eq!(progress_rx.try_iter().count(), 0);
+        let exported_count = std::fs::read_dir(&export_model.output_dir).unwrap().count();
+        assert_eq!(exported_count, 0);
+    }
+
+    #[test]
+    fn test_export_files_parallel_exports_all_files() {
+        let files: Vec<(String, Vec<u8>)> = (0..10)
+            .map(|i| (format!("disk{}.d64", i), format!("disk {}", i).into_bytes()))
+            .collect();
+        let file_refs: Vec<(&str, &[u8])> = files
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_slice()))
+            .collect();
+        let (_temp_dir, export_model) = prepare_export_with_files(&file_refs);
+
+        let result = export_files_parallel(&export_model, NonZeroUsize::new(3)).unwrap();
+
+        let mut expected_names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+        expected_names.sort();
+        assert_eq!(result.exported, expected_names);
+        assert!(result.skipped.is_empty());
+        for (name, content) in &files {
+            let exported = std::fs::read(export_model.output_dir.join(name)).unwrap();
+            assert_eq!(&exported, content);
+        }
+    }
+
+    #[test]
+    fn test_export_files_parallel_reports_all_failed_files() {
+        let (_temp_dir, mut export_model) = prepare_export_with_files(&[
+            ("disk1.d64", b"disk 1"),
+            ("disk2.d64", b"disk 2"),
+            ("disk3.d64", b"disk 3"),
+        ]);
+        export_model.fail_fast = false;
+        for output_file in export_model.output_mapping.values_mut() {
+            if output_file.output_file_name != "disk2.d64" {
+                output_file.checksum = [0; 20];
+            }
+        }
+
+        let result = export_files_parallel(&export_model, None).unwrap();
+
+        let failed_names: Vec<&str> = result
+            .failed
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(failed_names, vec!["disk1.d64", "disk3.d64"]);
+        assert_eq!(result.exported, vec!["disk2.d64"]);
+        assert!(export_model.output_dir.join("disk2.d64").exists());
+    }
+
+    #[test]
+    fn test_export_files_parallel_with_fail_fast_returns_error() {
+        let (_temp_dir, mut export_model) = prepare_export_with_files(&[("disk1.d64", b"disk 1")]);
+        for output_file in export_model.output_mapping.values_mut() {
+            output_file.checksum = [0; 20];
+        }
+
+        let result = export_files_parallel(&export_model, None);
+
+        assert!(matches!(result, Err(FileExportError::FileIoError(_))));
+    }
+
+    #[test]
+    fn test_export_files_parallel_skips_existing_files() {
+        let (_temp_dir, mut export_model) =
+            prepare_export_with_files(&[("disk1.d64", b"disk 1"), ("disk2.d64", b"disk 2")]);
+        export_model.skip_existing = true;
+        export_files(&export_model).unwrap();
+
+        let result = export_files_parallel(&export_model, NonZeroUsize::new(2)).unwrap();
+
+        assert_eq!(result.skipped, vec!["disk1.d64", "disk2.d64"]);
+        assert!(result.exported.is_empty());
+    }
+
+    #[test]
+    fn test_export_files_with_skip_existing_does_not_decompress_again() {
+        let (_temp_dir, mut export_model) =
+            prepare_export_with_files(&[("disk1.d64", b"disk 1"), ("disk2.d64", b"disk 2")]);
+        export_model.skip_existing = true;
+        export_files(&export_model).unwrap();
+        let decompressed_count = AtomicUsize::new(0);
+        let counting_decompressor = |input_path: &Path, output_path: &Path| {
+            decompressed_count.fetch_add(1, Ordering::Relaxed);
+            decompress_zstd_file(input_path, output_path)
+        };
+        let (progress_tx, progress_rx) = flume::unbounded();
+
+        let mut result = export_files_with_decompressor(
+            &export_model,
+            Some(&progress_tx),
+            None,
+            &counting_decompressor,
+        )
+        .unwrap();
+
+        assert_eq!(decompressed_count.load(Ordering::Relaxed), 0);
+        result.skipped.sort();
+        assert_eq!(result.skipped, vec!["disk1.d64", "disk2.d64"]);
+        assert!(result.exported.is_empty());
+        assert_eq!(progress_rx.try_iter().count(), 2);
+        assert_eq!(
+            std::fs::read(export_model.output_dir.join("disk1.d64")).unwrap(),
+            b"disk 1"
+        );
+    }
+
+    #[test]
+    fn test_export_files_with_skip_existing_overwrites_file_with_different_content() {
+        let (_temp_dir, mut export_model) = prepare_export_with_files(&[("disk1.d64", b"disk 1")]);
+        export_model.skip_existing = true;
+        let output_file_path = export_model.output_dir.join("disk1.d64");
+        std::fs::write(&output_file_path, b"modified").unwrap();
+
+        export_files(&export_model).unwrap();
+
+        assert_eq!(std::fs::read(output_file_path).unwrap(), b"disk 1");
+    }
+
+    #[test]
+    fn test_export_files_tar_gz_creates_tarball_with_output_file_names() {
+        let (_temp_dir, mut export_model) =
+            prepare_export_with_files(&[("disk1.d64", b"disk 1"), ("disk2.d64", b"disk 2")]);
+        export_model.exported_zip_file_name = "game.tar.gz".to_string();
+        export_model.extract_files = false;
+        export_model.container = ExportContainer::TarGz;
+
+        export_files_zipped_or_non_zipped(&export_model).unwrap();
+
+        let tar_file = File::open(export_model.output_dir.join("game.tar.gz")).unwrap();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(tar_file));
+        let mut entries: Vec<(String, Vec<u8>)> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let name = entry.path().unwrap().to_string_lossy().to_string();
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).unwrap();
+                (name, content)
+            })
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                ("disk1.d64".to_string(), b"disk 1".to_vec()),
+                ("disk2.d64".to_string(), b"disk 2".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_files_tar_gz_with_corrupted_content_returns_error() {
+        let compressed = zstd::encode_all(&b"Hello, w0rld!"[..], 3).unwrap();
+        let (_temp_dir, export_model) = prepare_export(&compressed, sha1_of(TEST_FILE_CONTENT));
+
+        let result = export_files_tar_gz(&export_model);
+
+        assert!(matches!(
+            result,
+            Err(FileExportError::FileIoError(message))
+                if message.contains("Checksum verification failed")
+        ));
+    }
+
+    #[test]
+    fn test_export_files_zipped_stored_is_larger_than_deflated() {
+        let content = b"compressible text content ".repeat(1000);
+        let export_zip_size = |zip_compression| {
+            let (_temp_dir, mut export_model) =
+                prepare_export_with_files(&[("readme.txt", content.as_slice())]);
+            export_model.zip_compression = Some(zip_compression);
+            export_files_zipped(&export_model).unwrap();
+            std::fs::metadata(export_model.output_dir.join("game.zip"))
+                .unwrap()
+                .len()
+        };
+
+        let stored_size = export_zip_size(ZipCompression::Stored);
+        let deflated_size = export_zip_size(ZipCompression::Deflated(Some(9)));
+
+        assert!(stored_size > content.len() as u64);
+        assert!(deflated_size < stored_size);
+    }
+
+    fn prepare_export_with_corrupted_file() -> (tempfile::TempDir, FileSetExportModel) {
+        let (temp_dir, mut export_model) =
+            prepare_export_with_files(&[("disk1.d64", b"disk 1"), ("disk2.d64", b"disk 2")]);
+        let corrupted = export_model
+            .output_mapping
+            .get_mut("archive_disk2.d64")
+            .unwrap();
+        corrupted.checksum = sha1_of(b"something else");
+        (temp_dir, export_model)
+    }
+
+    #[test]
+    fn test_export_files_fail_fast_aborts_on_corrupted_file() {
+        let (_temp_dir, mut export_model) = prepare_export_with_corrupted_file();
+        export_model.fail_fast = true;
+
+        let result = export_files(&export_model);
+
+        assert!(matches!(
+            result,
+            Err(FileExportError::FileIoError(message))
+                if message.contains("Checksum verification failed")
+        ));
+    }
+
+    #[test]
+    fn test_export_files_collects_failure_of_corrupted_file() {
+        let (_temp_dir, mut export_model) = prepare_export_with_corrupted_file();
+        export_model.fail_fast = false;
+
+        let result = export_files(&export_model).unwrap();
+
+        assert_eq!(result.exported, vec!["disk1.d64".to_string()]);
+        assert!(result.skipped.is_empty());
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, "disk2.d64");
+        assert!(result.failed[0].1.contains("Checksum verification failed"));
+        assert!(export_model.output_dir.join("disk1.d64").exists());
+        assert!(!export_model.output_dir.join("disk2.d64").exists());
+    }
+
+    #[test]
+    fn test_export_files_zipped_collects_failure_and_leaves_file_out_of_zip() {
+        let (_temp_dir, mut export_model) = prepare_export_with_corrupted_file();
+        export_model.fail_fast = false;
+
+        let result = export_files_zipped(&export_model).unwrap();
+
+        assert_eq!(result.exported, vec!["disk1.d64".to_string()]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, "disk2.d64");
+        let zip_file = File::open(export_model.output_dir.join("game.zip")).unwrap();
+        let archive = zip::ZipArchive::new(zip_file).unwrap();
+        assert_eq!(archive.file_names().collect::<Vec<_>>(), vec!["disk1.d64"]);
+    }
+
+    #[test]
+    fn test_export_files_to_writer_writes_zip_to_memory() {
+        let (_temp_dir, export_model) =
+            prepare_export_with_files(&[("disk1.d64", b"disk 1"), ("disk2.d64", b"disk 2")]);
+        let mut buffer = std::io::Cursor::new(Vec::new());
+
+        let result = export_files_to_writer(&export_model, &mut buffer).unwrap();
+
+        assert_eq!(result.exported.len(), 2);
+        assert!(!export_model.output_dir.join("game.zip").exists());
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(buffer.into_inner())).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name("disk2.d64")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(archive.len(), 2);
+        assert_eq!(content, "disk 2");
+    }
+
+    #[test]
+    fn test_export_files_tar_gz_to_writer_writes_to_vec() {
+        let (_temp_dir, export_model) = prepare_export_with_files(&[("disk1.d64", b"disk 1")]);
+        let mut buffer: Vec<u8> = Vec::new();
+
+        export_files_tar_gz_to_writer(&export_model, &mut buffer).unwrap();
+
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(buffer.as_slice()));
+        let mut entry = archive.entries().unwrap().next().unwrap().unwrap();
+        let mut content = String::new();
+        entry.read_to_string(&mut content).unwrap();
+        assert_eq!(entry.path().unwrap().to_str(), Some("disk1.d64"));
+        assert_eq!(content, "disk 1");
+    }
+
+    #[test]
+    fn test_plan_export_reports_sizes_and_missing_sources() {
+        let (temp_dir, mut export_model) = prepare_export_with_files(&[]);
+        let disk_path = temp_dir.path().join("disk1.d64");
+        std::fs::write(&disk_path, b"disk 1").unwrap();
+        // Source file is compressed the same way as on import
+        let imported_file = file_import::import_file(
+            &disk_path,
+            &export_model.source_file_path,
+            &core_types::FileType::Rom,
+            &file_import::ImportOptions::default(),
+        )
+        .unwrap()
+        .files
+        .remove(&sha1_of(b"disk 1"))
+        .unwrap();
+        for (archive_file_name, output_file_name) in [
+            (imported_file.archive_file_name.unwrap(), "disk1.d64"),
+            ("missing".to_string(), "disk2.d64"),
+        ] {
+            export_model.output_mapping.insert(
+                archive_file_name,
+                OutputFile {
+                    output_file_name: output_file_name.to_string(),
+                    checksum: sha1_of(b"disk"),
+                },
+            );
+        }
+
+        let plan = plan_export(&export_model).unwrap();
+
+        assert_eq!(plan.files, vec![("disk1.d64".to_string(), Some(6))]);
+        assert_eq!(plan.missing_sources, vec!["missing".to_string()]);
+        assert_eq!(plan.known_total_size(), 6);
+        assert!(
+            std::fs::read_dir(&export_model.output_dir)
+                .unwrap()
+                .next()
+                .is_none()
+        );
+    }
+}
@@ -4,7 +4,9 @@ use std::{
     fs::{self, File},
 };
 
-use file_export::{export_files, export_files_zipped, FileSetExportModel, OutputFile};
+use file_export::{
+    export_files, export_files_zipped, ExportContainer, FileSetExportModel, OutputFile,
+};
 use tempfile::tempdir;
 use utils::test_utils::get_sha1_and_size;
 
@@ -32,6 +34,10 @@ fn test_export_files() {
         source_file_path: input_dir,
         extract_files: false,
         exported_zip_file_name: TEST_OUTPUT_FILE_NAME.to_string(),
+        skip_existing: false,
+        container: ExportContainer::Zip,
+        zip_compression: None,
+        fail_fast: true,
         output_dir,
     };
 
@@ -61,6 +67,10 @@ fn test_export_files_zipped() {
         source_file_path: input_dir.clone(),
         extract_files: true,
         exported_zip_file_name: "exported_files.zip".to_string(),
+        skip_existing: false,
+        container: ExportContainer::Zip,
+        zip_compression: None,
+        fail_fast: true,
         output_dir,
     };
 
@@ -11,6 +11,18 @@ use zstd::Encoder;
 
 use crate::{FileSize, Sha1Checksum};
 
+/// Default size of the read buffer, 8 KB.
+pub const DEFAULT_BUFFER_SIZE: usize = 8192;
+
+/// Checksum and size of the written content, and size of the compressed output file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedOutput {
+    pub sha1_checksum: Sha1Checksum,
+    pub file_size: FileSize,
+    pub compressed_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompressionLevel {
     Default,
     Fast,
@@ -18,7 +30,7 @@ pub enum CompressionLevel {
 }
 
 impl CompressionLevel {
-    fn to_zstd_level(&self) -> i32 {
+    fn to_zstd_level(self) -> i32 {
         match self {
             CompressionLevel::Fast => 1,
             CompressionLevel::Default => 3,
@@ -27,19 +39,47 @@ impl CompressionLevel {
     }
 }
 
+/// Writes the content zstd compressed to `output_dir`. When `content_size` is given, it's stored
+/// to the zstd frame header so that the decompressed size can be read without decompressing.
+/// Writing fails if the content doesn't match the given size.
 pub fn output_zstd_compressed<R: Read>(
     output_dir: &Path,
     file: &mut R,
     archive_file_name: &str,
     compression_level: CompressionLevel,
-) -> Result<(Sha1Checksum, FileSize), Box<dyn std::error::Error>> {
+    buffer_size: usize,
+    content_size: Option<u64>,
+) -> Result<CompressedOutput, Box<dyn std::error::Error>> {
+    output_zstd_compressed_with_writer(
+        output_dir,
+        file,
+        archive_file_name,
+        compression_level,
+        buffer_size,
+        content_size,
+        |zstd_file| zstd_file,
+    )
+}
+
+/// Same as [`output_zstd_compressed`], but the created output file is wrapped with
+/// `wrap_output` before writing. Allows injecting write failures in tests.
+pub(crate) fn output_zstd_compressed_with_writer<R: Read, W: Write>(
+    output_dir: &Path,
+    file: &mut R,
+    archive_file_name: &str,
+    compression_level: CompressionLevel,
+    buffer_size: usize,
+    content_size: Option<u64>,
+    wrap_output: impl FnOnce(File) -> W,
+) -> Result<CompressedOutput, Box<dyn std::error::Error>> {
     let zstd_file_path = output_dir.join(archive_file_name).with_extension("zst");
     if let Some(parent) = zstd_file_path.parent() {
         create_dir_all(parent)?;
     }
-    let zstd_file = File::create(zstd_file_path)?;
-    let mut encoder = Encoder::new(zstd_file, compression_level.to_zstd_level())?;
-    let mut buffer = [0u8; 8192]; // 8 KB buffer
+    let zstd_file = File::create(&zstd_file_path)?;
+    let mut encoder = Encoder::new(wrap_output(zstd_file), compression_level.to_zstd_level())?;
+    encoder.set_pledged_src_size(content_size)?;
+    let mut buffer = vec![0u8; buffer_size];
     let mut hasher = Sha1::new();
     let mut size: u64 = 0;
 
@@ -52,7 +92,34 @@ pub fn output_zstd_compressed<R: Read>(
         hasher.update(&buffer[..bytes_read]);
         encoder.write_all(&buffer[..bytes_read])?;
     }
-    encoder.finish()?;
+    encoder.finish()?.flush()?;
+    let compressed_size = std::fs::metadata(&zstd_file_path)?.len();
+    let checksum: GenericArray<u8, U20> = hasher.finalize();
+    let checksum: Sha1Checksum = checksum.into();
+    Ok(CompressedOutput {
+        sha1_checksum: checksum,
+        file_size: size,
+        compressed_size,
+    })
+}
+
+/// Calculate SHA1 checksum and size of the content without writing it anywhere.
+pub fn read_sha1_and_size<R: Read>(
+    file: &mut R,
+    buffer_size: usize,
+) -> Result<(Sha1Checksum, FileSize), Box<dyn std::error::Error>> {
+    let mut buffer = vec![0u8; buffer_size];
+    let mut hasher = Sha1::new();
+    let mut size: u64 = 0;
+
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break; // EOF
+        }
+        size += bytes_read as u64;
+        hasher.update(&buffer[..bytes_read]);
+    }
     let checksum: GenericArray<u8, U20> = hasher.finalize();
     let checksum: Sha1Checksum = checksum.into();
     Ok((checksum, size))
@@ -107,15 +174,17 @@ mod tests {
 
         let (expected_checksum, expected_size) = get_sha1_and_size(TEST_FILE_CONTENT);
 
-        let (checksum, size) = output_zstd_compressed(
+        let output = output_zstd_compressed(
             output_path,
             &mut zip_file,
             TEST_ARCHIVE_FILE_NAME,
             CompressionLevel::Default,
+            DEFAULT_BUFFER_SIZE,
+            Some(expected_size),
         )
         .expect("Failed to write file");
-        assert_eq!(checksum, expected_checksum);
-        assert_eq!(size, expected_size);
+        assert_eq!(output.sha1_checksum, expected_checksum);
+        assert_eq!(output.file_size, expected_size);
 
         let output_data = fs::read(
             output_path
@@ -124,5 +193,69 @@ mod tests {
         )
         .expect("Failed to read file");
         assert!(!output_data.is_empty());
+        assert_eq!(output.compressed_size, output_data.len() as u64);
+    }
+
+    /// Generates content on the fly so that the test itself never holds the whole entry in
+    /// memory.
+    struct GeneratedReader {
+        remaining: u64,
+        position: u64,
+    }
+
+    impl Read for GeneratedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let count = buf.len().min(self.remaining as usize);
+            for byte in buf[..count].iter_mut() {
+                *byte = (self.position % 251) as u8;
+                self.position += 1;
+            }
+            self.remaining -= count as u64;
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_output_zstd_compressed_streams_large_content() {
+        const SIZE: u64 = 16 * 1024 * 1024 + 3;
+        let temp_dir = tempdir().unwrap();
+
+        let output = output_zstd_compressed(
+            temp_dir.path(),
+            &mut GeneratedReader {
+                remaining: SIZE,
+                position: 0,
+            },
+            TEST_ARCHIVE_FILE_NAME,
+            CompressionLevel::Fast,
+            DEFAULT_BUFFER_SIZE,
+            None,
+        )
+        .expect("Failed to write file");
+
+        let (expected_checksum, expected_size) = read_sha1_and_size(
+            &mut GeneratedReader {
+                remaining: SIZE,
+                position: 0,
+            },
+            DEFAULT_BUFFER_SIZE,
+        )
+        .unwrap();
+        assert_eq!(output.sha1_checksum, expected_checksum);
+        assert_eq!(output.file_size, expected_size);
+        assert_eq!(output.file_size, SIZE);
+
+        let zstd_file = File::open(
+            temp_dir
+                .path()
+                .join(TEST_ARCHIVE_FILE_NAME)
+                .with_extension("zst"),
+        )
+        .unwrap();
+        let mut decoder = zstd::Decoder::new(zstd_file).unwrap();
+        let (decompressed_checksum, decompressed_size) =
+            read_sha1_and_size(&mut decoder, DEFAULT_BUFFER_SIZE).unwrap();
+        assert_eq!(decompressed_checksum, expected_checksum);
+        assert_eq!(decompressed_size, SIZE);
     }
 }
@@ -1,13 +1,19 @@
 pub mod file_import_ops;
 pub mod file_outputter;
-use core_types::{FileSize, FileType, ImportedFile, Sha1Checksum};
+use core_types::{FileSize, FileType, ImportedFile, Sha1Checksum, events::ImportEvent};
 pub use file_import_ops::{FileImportOps, StdFileImportOps, mock};
-use file_outputter::{CompressionLevel, output_zstd_compressed};
+use file_outputter::{
+    CompressedOutput, CompressionLevel, DEFAULT_BUFFER_SIZE, output_zstd_compressed,
+    read_sha1_and_size,
+};
 use file_system::fs_ops::{FsOps, StdFsOps};
+use flume::Sender;
+use sevenz_rust::{Password, SevenZReader};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Display,
     fs::File,
+    io::Read,
     path::{Path, PathBuf},
 };
 use tempfile::tempdir;
@@ -16,12 +22,19 @@ use zip::ZipArchive;
 
 use uuid::Uuid;
 
+/// Throttles byte progress events, the same as 10 read buffers of 8 KB.
+const PROGRESS_REPORT_INTERVAL: u64 = 10 * 8192;
+
 #[derive(Debug, Clone)]
 pub enum FileImportError {
     ZipError(String),
     FileIoError(String),
     SelectionMismatch(String),
     InvalidFilePath(PathBuf),
+    InvalidConfig(String),
+    SevenZipError(String),
+    VerificationFailed(String),
+    DuplicateEntryName(String),
 }
 
 /// Used for filtering files that will be imported.
@@ -39,6 +52,149 @@ pub struct FileImportModel {
     pub output_dir: PathBuf,
     pub file_type: FileType,
     pub selected_entries: HashMap<Sha1Checksum, SelectedImportEntry>,
+    pub import_options: ImportOptions,
+}
+
+/// Options controlling how files are written to the collection.
+#[derive(Debug, Default, Clone)]
+pub struct ImportOptions {
+    /// Files already in the collection, archive file name keyed by checksum. Files with these
+    /// checksums are not written again, the existing archive file name is returned instead.
+    pub existing_files: HashMap<Sha1Checksum, String>,
+    pub config: ImportConfig,
+    pub naming: ImportNaming,
+}
+
+/// Result of importing files from a single source file.
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    /// Number of files written to the output directory.
+    pub imported: usize,
+    /// Number of files not written because they weren't selected, were duplicates or already
+    /// existed in the collection.
+    pub skipped: usize,
+    /// Total size of the written files before compression.
+    pub total_uncompressed_bytes: u64,
+    /// Total size of the written files after compression.
+    pub total_compressed_bytes: u64,
+    /// Imported files keyed by checksum, including the already existing files.
+    pub files: HashMap<Sha1Checksum, ImportedFile>,
+}
+
+impl ImportSummary {
+    fn add_written(&mut self, imported_file: ImportedFile, output: &CompressedOutput) {
+        self.imported += 1;
+        self.total_uncompressed_bytes += output.file_size;
+        self.total_compressed_bytes += output.compressed_size;
+        self.files
+            .insert(imported_file.sha1_checksum, imported_file);
+    }
+
+    fn add_existing(&mut self, imported_file: ImportedFile) {
+        self.skipped += 1;
+        self.files
+            .insert(imported_file.sha1_checksum, imported_file);
+    }
+
+    /// Files with different content must not share the same original file name, otherwise they
+    /// can't be told apart in the file set.
+    fn ensure_unique_name(
+        &self,
+        original_file_name: &str,
+        sha1_checksum: &Sha1Checksum,
+    ) -> Result<(), FileImportError> {
+        let is_duplicate = self.files.values().any(|f| {
+            f.original_file_name == original_file_name && f.sha1_checksum != *sha1_checksum
+        });
+        if is_duplicate {
+            return Err(FileImportError::DuplicateEntryName(
+                original_file_name.to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How `ImportedFile.original_file_name` is derived from the name of an archive entry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImportNaming {
+    /// Use only the file name, e.g. `roms/game/disk1.d64` becomes `disk1.d64`.
+    FlattenToBasename,
+    /// Use the full path inside the archive.
+    #[default]
+    PreservePath,
+}
+
+impl ImportNaming {
+    pub fn original_file_name(&self, entry_name: &str) -> String {
+        match self {
+            ImportNaming::FlattenToBasename => entry_name
+                .rsplit(['/', '\\'])
+                .find(|part| !part.is_empty())
+                .unwrap_or(entry_name)
+                .to_string(),
+            ImportNaming::PreservePath => entry_name.to_string(),
+        }
+    }
+}
+
+/// Tuning for reading and compressing the imported files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportConfig {
+    /// Size of the buffer used for reading the files, in bytes.
+    pub buffer_size: usize,
+    /// Compression level for all files. When `None`, level is chosen by file type.
+    pub compression_level: Option<CompressionLevel>,
+    /// Decompress each written file and check that its checksum matches the imported content.
+    pub verify_after_write: bool,
+}
+
+impl Default for ImportConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            compression_level: None,
+            verify_after_write: false,
+        }
+    }
+}
+
+impl ImportConfig {
+    pub fn validate(&self) -> Result<(), FileImportError> {
+        if self.buffer_size == 0 {
+            return Err(FileImportError::InvalidConfig(
+                "Buffer size must be greater than zero".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn compression_level_for(&self, file_type: &FileType) -> CompressionLevel {
+        self.compression_level
+            .unwrap_or_else(|| get_compression_level(file_type))
+    }
+}
+
+impl ImportOptions {
+    pub fn with_existing_file(
+        mut self,
+        sha1_checksum: Sha1Checksum,
+        archive_file_name: &str,
+    ) -> Self {
+        self.existing_files
+            .insert(sha1_checksum, archive_file_name.to_string());
+        self
+    }
+
+    pub fn with_config(mut self, config: ImportConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_naming(mut self, naming: ImportNaming) -> Self {
+        self.naming = naming;
+        self
+    }
 }
 
 impl Display for FileImportError {
@@ -48,6 +204,12 @@ impl Display for FileImportError {
             FileImportError::FileIoError(err) => write!(f, "File IO error: {}", err),
             FileImportError::SelectionMismatch(err) => write!(f, "Selection mismatch: {}", err),
             FileImportError::InvalidFilePath(path) => write!(f, "Invalid file path: {:?}", path),
+            FileImportError::InvalidConfig(err) => write!(f, "Invalid import config: {}", err),
+            FileImportError::SevenZipError(err) => write!(f, "7z error: {}", err),
+            FileImportError::VerificationFailed(err) => write!(f, "Verification failed: {}", err),
+            FileImportError::DuplicateEntryName(name) => {
+                write!(f, "Multiple entries with different content named: {}", name)
+            }
         }
     }
 }
@@ -86,53 +248,76 @@ pub fn import(
                 &file_import_model.output_dir,
                 &file_import_model.selected_entries,
                 &file_import_model.file_type,
+                &file_import_model.import_options,
             )?;
-            imported_files_map.extend(res);
+            imported_files_map.extend(res.files);
         } else {
             let res = import_file(
                 file_path,
                 &file_import_model.output_dir,
                 &file_import_model.file_type,
+                &file_import_model.import_options,
             )?;
-            imported_files_map.extend(res);
+            imported_files_map.extend(res.files);
         }
     }
     Ok(imported_files_map)
 }
 
 /// Import single-non zipped file.
+///
+/// If the checksum of the file is in `options.existing_files`, the file is not written and the
+/// existing archive file name is returned.
 pub fn import_file(
     file_path: &Path,
     output_dir: &Path,
     file_type: &FileType,
-) -> Result<HashMap<Sha1Checksum, ImportedFile>, FileImportError> {
+    options: &ImportOptions,
+) -> Result<ImportSummary, FileImportError> {
+    options.config.validate()?;
+    let mut summary = ImportSummary::default();
     let mut file = File::open(file_path)
         .map_err(|e| FileImportError::FileIoError(format!("Failed opening file: {}", e)))?;
     let file_name = file_path
         .file_name()
         .and_then(|name| name.to_str())
         .ok_or_else(|| FileImportError::FileIoError("Failed to get file name".to_string()))?;
+
+    if !options.existing_files.is_empty() {
+        let (sha1_checksum, file_size) = read_sha1_and_size(&mut file, options.config.buffer_size)
+            .map_err(|e| {
+                FileImportError::FileIoError(format!("Failed calculating checksum: {}", e))
+            })?;
+        if let Some(existing_file) =
+            existing_imported_file(options, sha1_checksum, file_size, file_name)
+        {
+            summary.add_existing(existing_file);
+            return Ok(summary);
+        }
+        file = File::open(file_path)
+            .map_err(|e| FileImportError::FileIoError(format!("Failed opening file: {}", e)))?;
+    }
+
     let archive_file_name = generate_archive_file_name();
-    let (sha1_checksum, file_size) = output_zstd_compressed(
+    let content_size = file.metadata().ok().map(|metadata| metadata.len());
+    let output = output_compressed_or_remove_partial(
+        &StdFsOps,
         output_dir,
         &mut file,
         &archive_file_name,
-        get_compression_level(file_type),
-    )
-    .map_err(|e| {
-        FileImportError::FileIoError(format!("Failed writing file to output directory: {}", e))
-    })?;
+        content_size,
+        file_type,
+        &options.config,
+    )?;
     let imported_file = ImportedFile {
         original_file_name: file_name.to_string(),
         archive_file_name: Some(archive_file_name.to_string()),
-        sha1_checksum,
-        file_size,
+        sha1_checksum: output.sha1_checksum,
+        file_size: output.file_size,
     };
+    summary.add_written(imported_file, &output);
 
-    let mut file_name_to_checksum_map: HashMap<Sha1Checksum, ImportedFile> = HashMap::new();
-    file_name_to_checksum_map.insert(sha1_checksum, imported_file);
-
-    Ok(file_name_to_checksum_map)
+    Ok(summary)
 }
 
 /// Reads the given zip file and imports only the selected checksum entries.
@@ -146,30 +331,57 @@ pub fn import_file(
 /// * `file_path` - The path to the zip file.
 /// * `output_dir` - The directory where the files will be extracted.
 /// * `file_entries` - file entries to be imported from archive. Only these files will be processed
+/// * `options` - import options. Selected members with a checksum in `options.existing_files`
+///   are not written, the existing archive file name is returned for them.
 ///
 /// # Returns
 ///
-/// A `Result` containing a hash map with imported files keyed by checksum, or an error if the
-/// operation fails. The output file names will be provided in `file_entries`.
+/// A `Result` containing an [`ImportSummary`] with imported files keyed by checksum, or an error
+/// if the operation fails. The output file names will be provided in `file_entries`.
 ///
 pub fn import_files_from_zip(
     file_path: &Path,
     output_dir: &Path,
     file_entries: &HashMap<Sha1Checksum, SelectedImportEntry>,
     file_type: &FileType,
-) -> Result<HashMap<Sha1Checksum, ImportedFile>, FileImportError> {
+    options: &ImportOptions,
+) -> Result<ImportSummary, FileImportError> {
+    import_files_from_zip_with_progress(
+        file_path,
+        output_dir,
+        file_entries,
+        file_type,
+        options,
+        None,
+    )
+}
+
+/// Same as [`import_files_from_zip`], but reports progress of each ZIP member to `progress_tx`.
+///
+/// `FileStarted` and throttled `BytesProcessed` events are sent for every processed member,
+/// `FileCompleted` only for members that end up in the result. `ImportCompleted` is sent when
+/// the import succeeds.
+pub fn import_files_from_zip_with_progress(
+    file_path: &Path,
+    output_dir: &Path,
+    file_entries: &HashMap<Sha1Checksum, SelectedImportEntry>,
+    file_type: &FileType,
+    options: &ImportOptions,
+    progress_tx: Option<&Sender<ImportEvent>>,
+) -> Result<ImportSummary, FileImportError> {
     tracing::info!(
         fila_path = ?file_path,
         output_dir = ?output_dir,
         file_type = %file_type,
         "Importing files from zip"
     );
+    options.config.validate()?;
 
     let file = File::open(file_path)
         .map_err(|e| FileImportError::FileIoError(format!("Failed opening file: {}", e)))?;
     let mut archive = ZipArchive::new(file)
         .map_err(|e| FileImportError::ZipError(format!("Failed reading Zip file: {}", e)))?;
-    let mut file_name_to_checksum_map: HashMap<Sha1Checksum, ImportedFile> = HashMap::new();
+    let mut summary = ImportSummary::default();
     let temp_dir = tempdir().map_err(|e| {
         FileImportError::FileIoError(format!("Failed creating temporary directory: {}", e))
     })?;
@@ -181,52 +393,322 @@ pub fn import_files_from_zip(
         if !file.is_file() {
             continue;
         }
+        let member_name = file.name().to_string();
+        send_progress_event(
+            progress_tx,
+            ImportEvent::FileStarted {
+                name: member_name.clone(),
+            },
+        );
+
+        if !options.existing_files.is_empty() {
+            let (sha1_checksum, file_size) =
+                read_sha1_and_size(&mut file, options.config.buffer_size).map_err(|e| {
+                    FileImportError::ZipError(format!("Failed reading Zip file: {}", e))
+                })?;
+            let Some(file_entry) = file_entries.get(&sha1_checksum) else {
+                summary.skipped += 1;
+                continue;
+            };
+            if summary.files.contains_key(&sha1_checksum) {
+                summary.skipped += 1;
+                continue;
+            }
+            if let Some(existing_file) =
+                existing_imported_file(options, sha1_checksum, file_size, &file_entry.file_name)
+            {
+                summary.ensure_unique_name(&existing_file.original_file_name, &sha1_checksum)?;
+                summary.add_existing(existing_file);
+                send_progress_event(
+                    progress_tx,
+                    ImportEvent::FileCompleted {
+                        name: member_name,
+                        sha1: sha1_checksum,
+                    },
+                );
+                continue;
+            }
+            // Checksum was consumed by hashing, reopen the member for compression
+            drop(file);
+            file = archive.by_index(i).map_err(|e| {
+                FileImportError::ZipError(format!("Failed reading Zip file: {}", e))
+            })?;
+        }
 
         let archive_file_name = generate_archive_file_name();
         let staged_file_path = temp_dir
             .path()
             .join(&archive_file_name)
             .with_extension("zst");
-        let (sha1_checksum, file_size) = output_zstd_compressed(
+        let content_size = Some(file.size());
+        let mut progress_reader = ProgressReader::new(&mut file, &member_name, progress_tx);
+        let fs_ops = StdFsOps;
+        let output = output_compressed_or_remove_partial(
+            &fs_ops,
             temp_dir.path(),
-            &mut file,
+            &mut progress_reader,
             &archive_file_name,
-            get_compression_level(file_type),
-        )
-        .map_err(|e| {
-            FileImportError::FileIoError(format!("Failed writing file to output directory: {}", e))
-        })?;
-        let fs_ops = StdFsOps;
+            content_size,
+            file_type,
+            &options.config,
+        )?;
+        let sha1_checksum = output.sha1_checksum;
         let Some(file_entry) = file_entries.get(&sha1_checksum) else {
             remove_staged_file(&fs_ops, &staged_file_path)?;
+            summary.skipped += 1;
             continue;
         };
 
-        if file_name_to_checksum_map.contains_key(&sha1_checksum) {
+        if summary.files.contains_key(&sha1_checksum) {
             remove_staged_file(&fs_ops, &staged_file_path)?;
+            summary.skipped += 1;
             continue;
         }
 
+        let original_file_name = options.naming.original_file_name(&file_entry.file_name);
+        if let Err(e) = summary.ensure_unique_name(&original_file_name, &sha1_checksum) {
+            remove_staged_file(&fs_ops, &staged_file_path)?;
+            return Err(e);
+        }
         persist_staged_file(&fs_ops, &staged_file_path, output_dir)?;
 
         let imported_file = ImportedFile {
-            original_file_name: file_entry.file_name.clone(),
+            original_file_name,
             archive_file_name: Some(archive_file_name.to_string()),
             sha1_checksum,
-            file_size,
+            file_size: output.file_size,
         };
 
-        file_name_to_checksum_map.insert(sha1_checksum, imported_file);
+        summary.add_written(imported_file, &output);
+        send_progress_event(
+            progress_tx,
+            ImportEvent::FileCompleted {
+                name: member_name,
+                sha1: sha1_checksum,
+            },
+        );
     }
 
-    if !file_entries.is_empty() && file_name_to_checksum_map.is_empty() {
+    if !file_entries.is_empty() && summary.files.is_empty() {
         return Err(FileImportError::SelectionMismatch(format!(
             "No ZIP members in '{}' matched the selected SHA1 entries",
             file_path.display()
         )));
     }
 
-    Ok(file_name_to_checksum_map)
+    send_progress_event(
+        progress_tx,
+        ImportEvent::ImportCompleted {
+            total_files: summary.imported,
+            total_bytes: summary.total_uncompressed_bytes,
+        },
+    );
+    Ok(summary)
+}
+
+/// Reads the given 7z file and imports the entries whose names are in `file_name_filter`.
+///
+/// Entry names are full paths inside the archive. Like with zip files, each entry is staged to
+/// a temporary directory before it's persisted into the output directory.
+///
+/// # Returns
+///
+/// A `Result` containing an [`ImportSummary`] with imported files keyed by checksum, or an error
+/// if the operation fails.
+pub fn import_files_from_7z(
+    file_path: &Path,
+    output_dir: &Path,
+    file_name_filter: &HashSet<String>,
+    file_type: &FileType,
+    options: &ImportOptions,
+) -> Result<ImportSummary, FileImportError> {
+    tracing::info!(
+        file_path = ?file_path,
+        output_dir = ?output_dir,
+        file_type = %file_type,
+        "Importing files from 7z"
+    );
+    options.config.validate()?;
+
+    let mut archive = SevenZReader::open(file_path, Password::empty())
+        .map_err(|e| FileImportError::SevenZipError(format!("Failed reading 7z file: {}", e)))?;
+    let temp_dir = tempdir().map_err(|e| {
+        FileImportError::FileIoError(format!("Failed creating temporary directory: {}", e))
+    })?;
+    let fs_ops = StdFsOps;
+    let mut summary = ImportSummary::default();
+    let mut import_error: Option<FileImportError> = None;
+
+    archive
+        .for_each_entries(|entry, mut reader| {
+            if entry.is_directory() {
+                return Ok(true);
+            }
+            if !file_name_filter.contains(entry.name()) {
+                // Entries in a solid block are read from a shared stream, so skipped entries
+                // still need to be consumed.
+                std::io::copy(reader, &mut std::io::sink())?;
+                summary.skipped += 1;
+                return Ok(true);
+            }
+
+            let archive_file_name = generate_archive_file_name();
+            let staged_file_path = temp_dir
+                .path()
+                .join(&archive_file_name)
+                .with_extension("zst");
+            let result = output_compressed_or_remove_partial(
+                &fs_ops,
+                temp_dir.path(),
+                &mut reader,
+                &archive_file_name,
+                Some(entry.size()),
+                file_type,
+                &options.config,
+            )
+            .and_then(|output| {
+                if summary.files.contains_key(&output.sha1_checksum) {
+                    summary.skipped += 1;
+                    return remove_staged_file(&fs_ops, &staged_file_path);
+                }
+                if let Some(existing_file) = existing_imported_file(
+                    options,
+                    output.sha1_checksum,
+                    output.file_size,
+                    entry.name(),
+                ) {
+                    remove_staged_file(&fs_ops, &staged_file_path)?;
+                    summary.ensure_unique_name(
+                        &existing_file.original_file_name,
+                        &output.sha1_checksum,
+                    )?;
+                    summary.add_existing(existing_file);
+                    return Ok(());
+                }
+                let original_file_name = options.naming.original_file_name(entry.name());
+                if let Err(e) =
+                    summary.ensure_unique_name(&original_file_name, &output.sha1_checksum)
+                {
+                    remove_staged_file(&fs_ops, &staged_file_path)?;
+                    return Err(e);
+                }
+                persist_staged_file(&fs_ops, &staged_file_path, output_dir)?;
+                let imported_file = ImportedFile {
+                    original_file_name,
+                    archive_file_name: Some(archive_file_name.clone()),
+                    sha1_checksum: output.sha1_checksum,
+                    file_size: output.file_size,
+                };
+                summary.add_written(imported_file, &output);
+                Ok(())
+            });
+
+            match result {
+                Ok(()) => Ok(true),
+                Err(e) => {
+                    import_error = Some(e);
+                    Ok(false)
+                }
+            }
+        })
+        .map_err(|e| FileImportError::SevenZipError(format!("Failed reading 7z file: {}", e)))?;
+
+    if let Some(e) = import_error {
+        return Err(e);
+    }
+
+    if !file_name_filter.is_empty() && summary.files.is_empty() {
+        return Err(FileImportError::SelectionMismatch(format!(
+            "No 7z entries in '{}' matched the selected file names",
+            file_path.display()
+        )));
+    }
+
+    Ok(summary)
+}
+
+/// Writes the content zstd compressed to `output_dir`. If writing or the optional verification
+/// fails, the output file is removed before returning the error. `content_size` is stored to the
+/// zstd frame header when known.
+fn output_compressed_or_remove_partial<R: Read>(
+    ops: &dyn FsOps,
+    output_dir: &Path,
+    reader: &mut R,
+    archive_file_name: &str,
+    content_size: Option<u64>,
+    file_type: &FileType,
+    config: &ImportConfig,
+) -> Result<CompressedOutput, FileImportError> {
+    let output = output_zstd_compressed(
+        output_dir,
+        reader,
+        archive_file_name,
+        config.compression_level_for(file_type),
+        config.buffer_size,
+        content_size,
+    );
+    let output_file_path = output_dir.join(archive_file_name).with_extension("zst");
+    verify_output_or_remove_partial(ops, &output_file_path, config, output)
+}
+
+/// Verifies the written output file if enabled. If writing or the verification failed, the
+/// output file is removed before returning the error.
+fn verify_output_or_remove_partial(
+    ops: &dyn FsOps,
+    output_file_path: &Path,
+    config: &ImportConfig,
+    output: Result<CompressedOutput, Box<dyn std::error::Error>>,
+) -> Result<CompressedOutput, FileImportError> {
+    output
+        .or_else(|e| {
+            remove_staged_file(ops, output_file_path)?;
+            Err(FileImportError::FileIoError(format!(
+                "Failed writing file to output directory: {}",
+                e
+            )))
+        })
+        .and_then(|output| {
+            if config.verify_after_write
+                && let Err(e) =
+                    verify_zstd_output(output_file_path, &output.sha1_checksum, config.buffer_size)
+            {
+                remove_staged_file(ops, output_file_path)?;
+                return Err(e);
+            }
+            Ok(output)
+        })
+}
+
+/// Decompress the zstd file and compare the checksum of the content with the expected checksum.
+fn verify_zstd_output(
+    zstd_file_path: &Path,
+    expected_sha1_checksum: &Sha1Checksum,
+    buffer_size: usize,
+) -> Result<(), FileImportError> {
+    let file = File::open(zstd_file_path).map_err(|e| {
+        FileImportError::FileIoError(format!("Failed opening file for verification: {}", e))
+    })?;
+    let mut decoder = zstd::Decoder::new(file).map_err(|e| {
+        FileImportError::VerificationFailed(format!(
+            "Failed decompressing '{}': {}",
+            zstd_file_path.display(),
+            e
+        ))
+    })?;
+    let (sha1_checksum, _) = read_sha1_and_size(&mut decoder, buffer_size).map_err(|e| {
+        FileImportError::VerificationFailed(format!(
+            "Failed decompressing '{}': {}",
+            zstd_file_path.display(),
+            e
+        ))
+    })?;
+    if sha1_checksum != *expected_sha1_checksum {
+        return Err(FileImportError::VerificationFailed(format!(
+            "Checksum of '{}' doesn't match the imported content",
+            zstd_file_path.display()
+        )));
+    }
+    Ok(())
 }
 
 fn persist_staged_file(
@@ -275,6 +757,71 @@ fn remove_staged_file(ops: &dyn FsOps, staged_file_path: &Path) -> Result<(), Fi
     Ok(())
 }
 
+/// Passes reads through and reports the number of bytes read every `PROGRESS_REPORT_INTERVAL`
+/// bytes.
+struct ProgressReader<'a, R: Read> {
+    inner: R,
+    name: &'a str,
+    progress_tx: Option<&'a Sender<ImportEvent>>,
+    bytes_processed: u64,
+    last_event_reported: u64,
+}
+
+impl<'a, R: Read> ProgressReader<'a, R> {
+    fn new(inner: R, name: &'a str, progress_tx: Option<&'a Sender<ImportEvent>>) -> Self {
+        Self {
+            inner,
+            name,
+            progress_tx,
+            bytes_processed: 0,
+            last_event_reported: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.bytes_processed += bytes_read as u64;
+        if self.bytes_processed - self.last_event_reported >= PROGRESS_REPORT_INTERVAL {
+            self.last_event_reported = self.bytes_processed;
+            send_progress_event(
+                self.progress_tx,
+                ImportEvent::BytesProcessed {
+                    name: self.name.to_string(),
+                    bytes: self.bytes_processed,
+                },
+            );
+        }
+        Ok(bytes_read)
+    }
+}
+
+fn send_progress_event(progress_tx: Option<&Sender<ImportEvent>>, event: ImportEvent) {
+    if let Some(tx) = progress_tx
+        && let Err(e) = tx.send(event)
+    {
+        tracing::warn!(error = %e, "Failed to send import progress event");
+    }
+}
+
+fn existing_imported_file(
+    options: &ImportOptions,
+    sha1_checksum: Sha1Checksum,
+    file_size: FileSize,
+    file_name: &str,
+) -> Option<ImportedFile> {
+    options
+        .existing_files
+        .get(&sha1_checksum)
+        .map(|archive_file_name| ImportedFile {
+            original_file_name: options.naming.original_file_name(file_name),
+            archive_file_name: Some(archive_file_name.clone()),
+            sha1_checksum,
+            file_size,
+        })
+}
+
 // Import given file and store to interal file format.
 // If file is zipped, import each file individually. If also single non zipped files individually.
 // Checks file type, if file type is jpg or png,
@@ -292,6 +839,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::file_outputter::output_zstd_compressed_with_writer;
     use file_system::fs_ops::FsOpsCall;
     use file_system::fs_ops::{FsOpsOutcome, MockFsOps, MockFsOpsState};
     use tempfile::tempdir;
@@ -339,9 +887,10 @@ mod tests {
             &output_path,
             &selected_entries,
             &FileType::Rom,
+            &ImportOptions::default(),
         );
         assert!(result.is_ok());
-        let hash_map = result.unwrap();
+        let hash_map = result.unwrap().files;
         assert_eq!(hash_map.len(), 1);
 
         let imported_file = hash_map.get(&checksum).unwrap();
@@ -383,8 +932,10 @@ mod tests {
             &output_path,
             &selected_entries,
             &FileType::Rom,
+            &ImportOptions::default(),
         )
-        .unwrap();
+        .unwrap()
+        .files;
 
         assert_eq!(result.len(), 1);
         assert!(result.contains_key(&checksum));
@@ -421,6 +972,7 @@ mod tests {
             &output_path,
             &selected_entries,
             &FileType::Rom,
+            &ImportOptions::default(),
         );
 
         assert!(matches!(
@@ -462,9 +1014,10 @@ mod tests {
             &output_path,
             &selected_entries,
             &FileType::Rom,
+            &ImportOptions::default(),
         );
         assert!(result.is_ok());
-        let hash_map = result.unwrap();
+        let hash_map = result.unwrap().files;
         assert_eq!(hash_map.len(), 1);
         let imported_file = hash_map.get(&checksum).unwrap();
         assert_eq!(TEST_FILE_NAME, imported_file.original_file_name);
@@ -497,9 +1050,10 @@ mod tests {
             &output_path,
             &empty_selection,
             &FileType::Rom,
+            &ImportOptions::default(),
         );
         assert!(result.is_ok());
-        let hash_map = result.unwrap();
+        let hash_map = result.unwrap().files;
         assert_eq!(hash_map.len(), 0);
         assert!(get_dir_entries(output_path).is_empty());
     }
@@ -530,8 +1084,10 @@ mod tests {
             &output_path,
             &selected_entries,
             &FileType::Rom,
+            &ImportOptions::default(),
         )
-        .unwrap();
+        .unwrap()
+        .files;
         assert_eq!(result.len(), 1);
         assert!(result.contains_key(&checksum));
         // assert that there are no extra entries imported
@@ -543,6 +1099,748 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_import_files_from_zip_twice_with_existing_files_writes_no_new_files() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        zip_writer
+            .start_file("zip_member.bin", file_options)
+            .unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.finish().unwrap();
+
+        let (checksum, size) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let mut selected_entries = HashMap::new();
+        selected_entries.insert(
+            checksum,
+            SelectedImportEntry {
+                sha1_checksum: checksum,
+                file_name: TEST_FILE_NAME.to_string(),
+            },
+        );
+        let first_result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &ImportOptions::default(),
+        )
+        .unwrap()
+        .files;
+        let first_archive_file_name = first_result
+            .get(&checksum)
+            .unwrap()
+            .archive_file_name
+            .clone()
+            .unwrap();
+        assert_eq!(get_dir_entries(output_path.clone()).len(), 1);
+
+        let options =
+            ImportOptions::default().with_existing_file(checksum, &first_archive_file_name);
+        let second_result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &options,
+        )
+        .unwrap()
+        .files;
+
+        assert_eq!(second_result.len(), 1);
+        let imported_file = second_result.get(&checksum).unwrap();
+        assert_eq!(imported_file.original_file_name, TEST_FILE_NAME);
+        assert_eq!(
+            imported_file.archive_file_name.as_deref(),
+            Some(first_archive_file_name.as_str())
+        );
+        assert_eq!(imported_file.file_size, size);
+        // assert that second pass didn't write any new files
+        assert_eq!(get_dir_entries(output_path).len(), 1);
+    }
+
+    #[test]
+    fn test_import_files_from_zip_with_existing_files_imports_new_members() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        zip_writer.start_file("existing.bin", file_options).unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.start_file("new.bin", file_options).unwrap();
+        zip_writer.write_all(b"something else").unwrap();
+        zip_writer.finish().unwrap();
+
+        let (existing_checksum, _) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let (new_checksum, new_size) = get_sha1_and_size("something else");
+        let mut selected_entries = HashMap::new();
+        for (checksum, file_name) in [
+            (existing_checksum, "existing.bin"),
+            (new_checksum, "new.bin"),
+        ] {
+            selected_entries.insert(
+                checksum,
+                SelectedImportEntry {
+                    sha1_checksum: checksum,
+                    file_name: file_name.to_string(),
+                },
+            );
+        }
+        let options = ImportOptions::default().with_existing_file(existing_checksum, "existing");
+
+        let result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &options,
+        )
+        .unwrap()
+        .files;
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result
+                .get(&existing_checksum)
+                .unwrap()
+                .archive_file_name
+                .as_deref(),
+            Some("existing")
+        );
+        let new_file = result.get(&new_checksum).unwrap();
+        assert_eq!(new_file.file_size, new_size);
+        let new_file_path = output_path
+            .join(new_file.archive_file_name.clone().unwrap())
+            .with_extension("zst");
+        assert!(new_file_path.exists());
+        assert_eq!(get_dir_entries(output_path).len(), 1);
+    }
+
+    #[test]
+    fn test_import_file_with_existing_file_writes_nothing() {
+        let (temp_dir, _, _, output_path) = prepare_zip_tests();
+        let file_path = temp_dir.path().join("input").join(TEST_FILE_NAME);
+        fs::write(&file_path, TEST_FILE_CONTENT).unwrap();
+        let (checksum, size) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let options = ImportOptions::default().with_existing_file(checksum, "existing");
+
+        let result = import_file(&file_path, &output_path, &FileType::Rom, &options)
+            .unwrap()
+            .files;
+
+        let imported_file = result.get(&checksum).unwrap();
+        assert_eq!(imported_file.original_file_name, TEST_FILE_NAME);
+        assert_eq!(imported_file.archive_file_name.as_deref(), Some("existing"));
+        assert_eq!(imported_file.file_size, size);
+        assert!(get_dir_entries(output_path).is_empty());
+    }
+
+    #[test]
+    fn test_import_files_from_zip_with_progress_sends_completed_event_for_each_imported_entry() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        let large_content = vec![7u8; 3 * PROGRESS_REPORT_INTERVAL as usize];
+        zip_writer.start_file("first.bin", file_options).unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.start_file("second.bin", file_options).unwrap();
+        zip_writer.write_all(&large_content).unwrap();
+        zip_writer
+            .start_file("unselected.bin", file_options)
+            .unwrap();
+        zip_writer.write_all(b"something else").unwrap();
+        zip_writer.finish().unwrap();
+
+        let (first_checksum, _) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let second_checksum: Sha1Checksum = {
+            use sha1::{Digest, Sha1};
+            Sha1::digest(&large_content).into()
+        };
+        let mut selected_entries = HashMap::new();
+        for (checksum, file_name) in [
+            (first_checksum, "first.bin"),
+            (second_checksum, "second.bin"),
+        ] {
+            selected_entries.insert(
+                checksum,
+                SelectedImportEntry {
+                    sha1_checksum: checksum,
+                    file_name: file_name.to_string(),
+                },
+            );
+        }
+        let (tx, rx) = flume::unbounded();
+
+        let result = import_files_from_zip_with_progress(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &ImportOptions::default(),
+            Some(&tx),
+        )
+        .unwrap()
+        .files;
+        assert_eq!(result.len(), 2);
+
+        let events: Vec<ImportEvent> = rx.try_iter().collect();
+        let completed: Vec<&ImportEvent> = events
+            .iter()
+            .filter(|e| matches!(e, ImportEvent::FileCompleted { .. }))
+            .collect();
+        assert_eq!(
+            completed,
+            vec![
+                &ImportEvent::FileCompleted {
+                    name: "first.bin".to_string(),
+                    sha1: first_checksum,
+                },
+                &ImportEvent::FileCompleted {
+                    name: "second.bin".to_string(),
+                    sha1: second_checksum,
+                },
+            ]
+        );
+        let started_count = events
+            .iter()
+            .filter(|e| matches!(e, ImportEvent::FileStarted { .. }))
+            .count();
+        assert_eq!(started_count, 3);
+        let bytes_events: Vec<u64> = events
+            .iter()
+            .filter_map(|e| match e {
+                ImportEvent::BytesProcessed { name, bytes } if name == "second.bin" => Some(*bytes),
+                _ => None,
+            })
+            .collect();
+        // progress is reported once per interval, not for every read
+        assert!(!bytes_events.is_empty());
+        assert!(bytes_events.len() <= 3);
+        assert!(matches!(
+            events.last(),
+            Some(ImportEvent::ImportCompleted { total_files: 2, .. })
+        ));
+    }
+
+    /// Fails after returning some content, simulating a read error in the middle of the input.
+    struct FailingReader {
+        reads: usize,
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.reads > 0 {
+                return Err(std::io::Error::other("simulated read failure"));
+            }
+            self.reads += 1;
+            let count = buf.len().min(TEST_FILE_CONTENT.len());
+            buf[..count].copy_from_slice(&TEST_FILE_CONTENT.as_bytes()[..count]);
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_output_compressed_or_remove_partial_removes_partial_file_on_failure() {
+        let temp_dir = tempdir().unwrap();
+        let output_path = temp_dir.path().join("output");
+        let mut reader = FailingReader { reads: 0 };
+
+        let result = output_compressed_or_remove_partial(
+            &StdFsOps,
+            &output_path,
+            &mut reader,
+            "archive_file_name",
+            None,
+            &FileType::Rom,
+            &ImportConfig::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(FileImportError::FileIoError(message))
+                if message.contains("simulated read failure")
+        ));
+        assert!(get_dir_entries(output_path).is_empty());
+    }
+
+    /// Writes the first byte to the output file and fails after that, simulating e.g. a disk
+    /// full error in the middle of writing the output file.
+    struct FailingWriter {
+        inner: File,
+    }
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.inner.metadata()?.len() > 0 {
+                return Err(std::io::Error::other("simulated write failure"));
+            }
+            self.inner.write(&buf[..1])
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    #[test]
+    fn test_verify_output_or_remove_partial_removes_partially_written_file() {
+        let temp_dir = tempdir().unwrap();
+        let output_path = temp_dir.path().join("output");
+        let output = output_zstd_compressed_with_writer(
+            &output_path,
+            &mut TEST_FILE_CONTENT.as_bytes(),
+            "archive_file_name",
+            CompressionLevel::Default,
+            DEFAULT_BUFFER_SIZE,
+            None,
+            |inner| FailingWriter { inner },
+        );
+        let output_file_path = output_path.join("archive_file_name.zst");
+        assert_eq!(std::fs::metadata(&output_file_path).unwrap().len(), 1);
+
+        let result = verify_output_or_remove_partial(
+            &StdFsOps,
+            &output_file_path,
+            &ImportConfig::default(),
+            output,
+        );
+
+        assert!(matches!(
+            result,
+            Err(FileImportError::FileIoError(message))
+                if message.contains("simulated write failure")
+        ));
+        assert!(get_dir_entries(output_path).is_empty());
+    }
+
+    #[test]
+    fn test_output_compressed_or_remove_partial_when_removal_fails_returns_error() {
+        let fs_mock_state = Arc::new(Mutex::new(MockFsOpsState {
+            outcome: FsOpsOutcome {
+                exists_result: Some(Ok(true)),
+                remove_result: Some(Err(std::io::Error::other("remove error"))),
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+        let fs_ops = MockFsOps::new(Arc::clone(&fs_mock_state));
+        let temp_dir = tempdir().unwrap();
+        let mut reader = FailingReader { reads: 0 };
+
+        let result = output_compressed_or_remove_partial(
+            &fs_ops,
+            temp_dir.path(),
+            &mut reader,
+            "archive_file_name",
+            None,
+            &FileType::Rom,
+            &ImportConfig::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(FileImportError::FileIoError(message))
+                if message.contains("Failed removing staged file")
+        ));
+        let guard = fs_mock_state.lock().unwrap();
+        assert_eq!(
+            guard.calls[1],
+            FsOpsCall::Remove {
+                path: temp_dir.path().join("archive_file_name.zst")
+            }
+        );
+    }
+
+    #[test]
+    fn test_import_files_from_zip_with_large_buffer_and_fixed_compression_level() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        let content = vec![3u8; 3 * 1024 * 1024 + 17];
+        zip_writer.start_file("large.bin", file_options).unwrap();
+        zip_writer.write_all(&content).unwrap();
+        zip_writer.finish().unwrap();
+
+        let checksum: Sha1Checksum = {
+            use sha1::{Digest, Sha1};
+            Sha1::digest(&content).into()
+        };
+        let mut selected_entries = HashMap::new();
+        selected_entries.insert(
+            checksum,
+            SelectedImportEntry {
+                sha1_checksum: checksum,
+                file_name: TEST_FILE_NAME.to_string(),
+            },
+        );
+        let options = ImportOptions::default().with_config(ImportConfig {
+            buffer_size: 1024 * 1024,
+            compression_level: Some(CompressionLevel::Fast),
+            verify_after_write: false,
+        });
+
+        let result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &options,
+        )
+        .unwrap()
+        .files;
+
+        let imported_file = result.get(&checksum).unwrap();
+        assert_eq!(imported_file.file_size, content.len() as u64);
+        let output_file_path = output_path
+            .join(imported_file.archive_file_name.clone().unwrap())
+            .with_extension("zst");
+        let decompressed = zstd::decode_all(File::open(output_file_path).unwrap()).unwrap();
+        assert_eq!(decompressed, content);
+    }
+
+    #[test]
+    fn test_import_files_from_zip_with_zero_buffer_size_returns_error() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        zip_writer.start_file(TEST_FILE_NAME, file_options).unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.finish().unwrap();
+        let options = ImportOptions::default().with_config(ImportConfig {
+            buffer_size: 0,
+            compression_level: None,
+            verify_after_write: false,
+        });
+
+        let result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &HashMap::new(),
+            &FileType::Rom,
+            &options,
+        );
+
+        assert!(matches!(result, Err(FileImportError::InvalidConfig(_))));
+        assert!(get_dir_entries(output_path).is_empty());
+    }
+
+    #[test]
+    fn test_import_files_from_zip_summary_counts_and_byte_totals() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        let large_content = vec![5u8; 64 * 1024];
+        zip_writer.start_file("first.bin", file_options).unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.start_file("second.bin", file_options).unwrap();
+        zip_writer.write_all(&large_content).unwrap();
+        zip_writer
+            .start_file("duplicate.bin", file_options)
+            .unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer
+            .start_file("unselected.bin", file_options)
+            .unwrap();
+        zip_writer.write_all(b"something else").unwrap();
+        zip_writer.finish().unwrap();
+
+        let (first_checksum, first_size) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let second_checksum: Sha1Checksum = {
+            use sha1::{Digest, Sha1};
+            Sha1::digest(&large_content).into()
+        };
+        let mut selected_entries = HashMap::new();
+        for (checksum, file_name) in [
+            (first_checksum, "first.bin"),
+            (second_checksum, "second.bin"),
+        ] {
+            selected_entries.insert(
+                checksum,
+                SelectedImportEntry {
+                    sha1_checksum: checksum,
+                    file_name: file_name.to_string(),
+                },
+            );
+        }
+
+        let summary = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &ImportOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(summary.files.len(), 2);
+        assert_eq!(
+            summary.total_uncompressed_bytes,
+            first_size + large_content.len() as u64
+        );
+        let written_bytes: u64 = get_dir_entries(output_path)
+            .iter()
+            .map(|entry| entry.metadata().unwrap().len())
+            .sum();
+        assert_eq!(summary.total_compressed_bytes, written_bytes);
+    }
+
+    #[test]
+    fn test_import_file_summary_when_file_exists_counts_skipped() {
+        let (temp_dir, _, _, output_path) = prepare_zip_tests();
+        let file_path = temp_dir.path().join("input").join(TEST_FILE_NAME);
+        fs::write(&file_path, TEST_FILE_CONTENT).unwrap();
+        let (checksum, size) = get_sha1_and_size(TEST_FILE_CONTENT);
+
+        let summary = import_file(
+            &file_path,
+            &output_path,
+            &FileType::Rom,
+            &ImportOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.skipped, 0);
+        assert_eq!(summary.total_uncompressed_bytes, size);
+        assert!(summary.total_compressed_bytes > 0);
+
+        let options = ImportOptions::default().with_existing_file(checksum, "existing");
+        let summary = import_file(&file_path, &output_path, &FileType::Rom, &options).unwrap();
+        assert_eq!(summary.imported, 0);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.total_uncompressed_bytes, 0);
+        assert_eq!(summary.total_compressed_bytes, 0);
+        assert!(summary.files.contains_key(&checksum));
+    }
+
+    #[test]
+    fn test_import_files_from_zip_with_same_name_and_different_content_returns_error() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        zip_writer
+            .start_file("disk1/game.d64", file_options)
+            .unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer
+            .start_file("disk2/game.d64", file_options)
+            .unwrap();
+        zip_writer.write_all(b"something else").unwrap();
+        zip_writer.finish().unwrap();
+
+        let mut selected_entries = HashMap::new();
+        for (name, content) in [
+            ("disk1/game.d64", TEST_FILE_CONTENT),
+            ("disk2/game.d64", "something else"),
+        ] {
+            let (checksum, _) = get_sha1_and_size(content);
+            selected_entries.insert(
+                checksum,
+                SelectedImportEntry {
+                    sha1_checksum: checksum,
+                    file_name: name.to_string(),
+                },
+            );
+        }
+        let options = ImportOptions::default().with_naming(ImportNaming::FlattenToBasename);
+
+        let result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::DiskImage,
+            &options,
+        );
+
+        assert!(matches!(
+            result,
+            Err(FileImportError::DuplicateEntryName(name)) if name == "game.d64"
+        ));
+    }
+
+    #[test]
+    fn test_import_files_from_zip_with_verify_after_write() {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        zip_writer.start_file(TEST_FILE_NAME, file_options).unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.finish().unwrap();
+        let (checksum, _) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let mut selected_entries = HashMap::new();
+        selected_entries.insert(
+            checksum,
+            SelectedImportEntry {
+                sha1_checksum: checksum,
+                file_name: TEST_FILE_NAME.to_string(),
+            },
+        );
+        let options = ImportOptions::default().with_config(ImportConfig {
+            verify_after_write: true,
+            ..Default::default()
+        });
+
+        let result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::Rom,
+            &options,
+        )
+        .unwrap()
+        .files;
+
+        assert!(result.contains_key(&checksum));
+        assert_eq!(get_dir_entries(output_path).len(), 1);
+    }
+
+    #[test]
+    fn test_verify_zstd_output_fails_when_output_is_corrupted() {
+        let temp_dir = tempdir().unwrap();
+        let checksum = output_compressed_or_remove_partial(
+            &StdFsOps,
+            temp_dir.path(),
+            &mut TEST_FILE_CONTENT.as_bytes(),
+            "archive_file_name",
+            Some(TEST_FILE_CONTENT.len() as u64),
+            &FileType::Rom,
+            &ImportConfig::default(),
+        )
+        .unwrap()
+        .sha1_checksum;
+        let output_file_path = temp_dir.path().join("archive_file_name.zst");
+        assert!(verify_zstd_output(&output_file_path, &checksum, DEFAULT_BUFFER_SIZE).is_ok());
+
+        // flip a byte in the compressed content, after the zstd frame header
+        let mut compressed = fs::read(&output_file_path).unwrap();
+        let index = compressed.len() - 4;
+        compressed[index] ^= 0xff;
+        fs::write(&output_file_path, compressed).unwrap();
+
+        let result = verify_zstd_output(&output_file_path, &checksum, DEFAULT_BUFFER_SIZE);
+        assert!(matches!(
+            result,
+            Err(FileImportError::VerificationFailed(_))
+        ));
+    }
+
+    fn import_nested_zip_entry(naming: ImportNaming) -> ImportedFile {
+        let (_temp_dir, mut zip_writer, zip_file_path, output_path) = prepare_zip_tests();
+        let file_options: FileOptions<'_, ()> = FileOptions::default();
+        zip_writer
+            .start_file("roms/game/disk1.d64", file_options)
+            .unwrap();
+        zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
+        zip_writer.finish().unwrap();
+        let (checksum, _) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let mut selected_entries = HashMap::new();
+        selected_entries.insert(
+            checksum,
+            SelectedImportEntry {
+                sha1_checksum: checksum,
+                file_name: "roms/game/disk1.d64".to_string(),
+            },
+        );
+
+        let mut result = import_files_from_zip(
+            &zip_file_path,
+            &output_path,
+            &selected_entries,
+            &FileType::DiskImage,
+            &ImportOptions::default().with_naming(naming),
+        )
+        .unwrap()
+        .files;
+        result.remove(&checksum).unwrap()
+    }
+
+    #[test]
+    fn test_import_files_from_zip_preserve_path_naming() {
+        let imported_file = import_nested_zip_entry(ImportNaming::PreservePath);
+        assert_eq!(imported_file.original_file_name, "roms/game/disk1.d64");
+    }
+
+    #[test]
+    fn test_import_files_from_zip_flatten_to_basename_naming() {
+        let imported_file = import_nested_zip_entry(ImportNaming::FlattenToBasename);
+        assert_eq!(imported_file.original_file_name, "disk1.d64");
+    }
+
+    #[test]
+    fn test_import_naming_flatten_to_basename() {
+        let naming = ImportNaming::FlattenToBasename;
+        assert_eq!(naming.original_file_name("disk1.d64"), "disk1.d64");
+        assert_eq!(naming.original_file_name("roms\\disk1.d64"), "disk1.d64");
+        assert_eq!(
+            ImportNaming::default().original_file_name("roms/disk1.d64"),
+            "roms/disk1.d64"
+        );
+    }
+
+    fn create_test_7z(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = sevenz_rust::SevenZWriter::create(path).unwrap();
+        for (name, content) in entries {
+            let mut entry = sevenz_rust::SevenZArchiveEntry::new();
+            entry.name = name.to_string();
+            entry.has_stream = true;
+            writer.push_archive_entry(entry, Some(*content)).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_import_files_from_7z_imports_only_filtered_entries() {
+        let temp_dir = tempdir().unwrap();
+        let archive_path = temp_dir.path().join("test.7z");
+        let output_path = temp_dir.path().join("output");
+        create_test_7z(
+            &archive_path,
+            &[
+                ("skipped.bin", b"something else"),
+                ("dir/selected.bin", TEST_FILE_CONTENT.as_bytes()),
+            ],
+        );
+        let (checksum, size) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let filter = HashSet::from(["dir/selected.bin".to_string()]);
+
+        let result = import_files_from_7z(
+            &archive_path,
+            &output_path,
+            &filter,
+            &FileType::Rom,
+            &ImportOptions::default(),
+        )
+        .unwrap()
+        .files;
+
+        assert_eq!(result.len(), 1);
+        let imported_file = result.get(&checksum).unwrap();
+        assert_eq!(imported_file.original_file_name, "dir/selected.bin");
+        assert_eq!(imported_file.file_size, size);
+        let output_file_path = output_path
+            .join(imported_file.archive_file_name.clone().unwrap())
+            .with_extension("zst");
+        let decompressed = zstd::decode_all(File::open(output_file_path).unwrap()).unwrap();
+        assert_eq!(decompressed, TEST_FILE_CONTENT.as_bytes());
+        assert_eq!(get_dir_entries(output_path).len(), 1);
+    }
+
+    #[test]
+    fn test_import_files_from_7z_returns_selection_mismatch_when_no_match_found() {
+        let temp_dir = tempdir().unwrap();
+        let archive_path = temp_dir.path().join("test.7z");
+        let output_path = temp_dir.path().join("output");
+        create_test_7z(
+            &archive_path,
+            &[(TEST_FILE_NAME, TEST_FILE_CONTENT.as_bytes())],
+        );
+        let filter = HashSet::from(["missing.bin".to_string()]);
+
+        let result = import_files_from_7z(
+            &archive_path,
+            &output_path,
+            &filter,
+            &FileType::Rom,
+            &ImportOptions::default(),
+        );
+
+        assert!(matches!(result, Err(FileImportError::SelectionMismatch(_))));
+        assert!(get_dir_entries(output_path).is_empty());
+    }
+
     fn test_paths() -> (PathBuf, PathBuf, PathBuf) {
         let staged_file_path = PathBuf::from("/temp/archive_file_name.zst");
         let output_dir = PathBuf::from("/output/");
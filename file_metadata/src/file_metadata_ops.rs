@@ -25,6 +25,12 @@ pub trait FileMetadataOps: Send + Sync {
         &self,
         file_path: &Path,
     ) -> Result<HashMap<Sha1Checksum, ReadFile>, FileMetadataError>;
+
+    /// List files inside an ISO9660 disc image (.iso or raw .bin) with their checksums
+    ///
+    /// The listing is informational only, the files are not extracted.
+    fn read_disc_image_listing(&self, file_path: &Path)
+    -> Result<Vec<ReadFile>, FileMetadataError>;
 }
 
 /// Standard implementation using actual file system operations
@@ -36,7 +42,7 @@ impl FileMetadataOps for StdFileMetadataOps {
         &self,
         file_path: &Path,
     ) -> Result<HashMap<Sha1Checksum, ReadFile>, FileMetadataError> {
-        crate::read_zip_contents_with_checksums(&file_path.to_path_buf())
+        crate::read_zip_contents_with_checksums(&file_path.to_path_buf(), true)
     }
 
     fn read_file_checksum(
@@ -45,6 +51,13 @@ impl FileMetadataOps for StdFileMetadataOps {
     ) -> Result<HashMap<Sha1Checksum, ReadFile>, FileMetadataError> {
         crate::read_file_checksum(&file_path.to_path_buf())
     }
+
+    fn read_disc_image_listing(
+        &self,
+        file_path: &Path,
+    ) -> Result<Vec<ReadFile>, FileMetadataError> {
+        crate::iso9660::read_iso9660_listing(file_path)
+    }
 }
 
 #[cfg(test)]
@@ -69,6 +82,7 @@ pub mod mock {
     pub struct MockFileMetadataOps {
         zip_contents: Arc<Mutex<HashMap<Sha1Checksum, ReadFile>>>,
         file_checksums: Arc<Mutex<HashMap<Sha1Checksum, ReadFile>>>,
+        disc_image_listing: Arc<Mutex<Vec<ReadFile>>>,
         should_fail: Arc<Mutex<bool>>,
     }
 
@@ -93,6 +107,11 @@ pub mod mock {
                 .insert(checksum, read_file);
         }
 
+        /// Add a file entry to be returned by read_disc_image_listing
+        pub fn add_disc_image_entry(&self, read_file: ReadFile) {
+            self.disc_image_listing.lock().unwrap().push(read_file);
+        }
+
         /// Make all operations fail with an error
         pub fn set_should_fail(&self, should_fail: bool) {
             *self.should_fail.lock().unwrap() = should_fail;
@@ -134,5 +153,18 @@ pub mod mock {
             }
             Ok(map.clone())
         }
+
+        fn read_disc_image_listing(
+            &self,
+            file_path: &Path,
+        ) -> Result<Vec<ReadFile>, FileMetadataError> {
+            if *self.should_fail.lock().unwrap() {
+                return Err(FileMetadataError::Iso9660Error {
+                    path: file_path.to_path_buf(),
+                    message: "Mock error".to_string(),
+                });
+            }
+            Ok(self.disc_image_listing.lock().unwrap().clone())
+        }
     }
 }
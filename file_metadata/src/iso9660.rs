@@ -0,0 +1,392 @@
+//! Read the internal file listing of ISO9660 disc images.
+//!
+//! Supports both cooked `.iso` images (2048 byte sectors) and raw `.bin` images
+//! (2352 byte Mode 1 or Mode 2 Form 1 sectors). Files are hashed directly from the image
+//! without extracting them.
+
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use core_types::ReadFile;
+
+use crate::{FileMetadataError, FileMetadataReader, hash_reader};
+
+const SECTOR_DATA_SIZE: usize = 2048;
+const RAW_SECTOR_SIZE: u64 = 2352;
+const RAW_SECTOR_SYNC: [u8; 12] = [
+    0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00,
+];
+const VOLUME_DESCRIPTOR_START: u64 = 16;
+const VOLUME_DESCRIPTOR_PRIMARY: u8 = 1;
+const VOLUME_DESCRIPTOR_TERMINATOR: u8 = 255;
+const STANDARD_IDENTIFIER: &[u8] = b"CD001";
+const ROOT_DIRECTORY_RECORD_OFFSET: usize = 156;
+const DIRECTORY_FLAG: u8 = 0x02;
+/// Guards against malformed images with cyclic or absurdly deep directory trees.
+const MAX_DIRECTORY_DEPTH: usize = 32;
+
+/// Layout of sectors in the image file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SectorLayout {
+    sector_size: u64,
+    data_offset: u64,
+}
+
+impl SectorLayout {
+    const COOKED: Self = Self {
+        sector_size: SECTOR_DATA_SIZE as u64,
+        data_offset: 0,
+    };
+
+    fn position(&self, lba: u64) -> u64 {
+        lba * self.sector_size + self.data_offset
+    }
+}
+
+struct DirectoryRecord {
+    name: String,
+    extent: u32,
+    data_length: u32,
+    is_directory: bool,
+}
+
+/// Lists the files inside an ISO9660 disc image with their SHA1 checksums and sizes.
+///
+/// The listing is informational: file names are full paths inside the image, e.g.
+/// `DATA/README.TXT`.
+pub struct Iso9660FileMetadataReader {
+    path: PathBuf,
+}
+
+impl Iso9660FileMetadataReader {
+    pub fn new(path: &Path) -> Result<Self, FileMetadataError> {
+        if !path.exists() {
+            return Err(FileMetadataError::FileNotFound(path.to_path_buf()));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl FileMetadataReader for Iso9660FileMetadataReader {
+    fn read_metadata(&self) -> Result<Vec<ReadFile>, FileMetadataError> {
+        read_iso9660_listing(&self.path)
+    }
+}
+
+/// Check whether the path has an extension of a disc image that may contain an ISO9660
+/// filesystem.
+pub fn is_disc_image_file_name(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("iso") || ext.eq_ignore_ascii_case("bin"))
+}
+
+/// Enumerate the files of the ISO9660 filesystem in the image and calculate sha1 checksum and
+/// size for each file.
+pub fn read_iso9660_listing(file_path: &Path) -> Result<Vec<ReadFile>, FileMetadataError> {
+    let mut file = File::open(file_path).map_err(|e| FileMetadataError::FileIoError {
+        path: file_path.to_path_buf(),
+        message: format!("Failed opening file: {}", e),
+    })?;
+    let layout = detect_sector_layout(&mut file, file_path)?;
+    let mut image = IsoImage {
+        file,
+        layout,
+        file_path,
+    };
+    let root = image.read_root_directory_record()?;
+
+    let mut read_files = Vec::new();
+    let mut visited = HashSet::new();
+    image.read_directory(&root, "", 0, &mut visited, &mut read_files)?;
+    Ok(read_files)
+}
+
+fn iso_error(file_path: &Path, message: impl Into<String>) -> FileMetadataError {
+    FileMetadataError::Iso9660Error {
+        path: file_path.to_path_buf(),
+        message: message.into(),
+    }
+}
+
+fn io_error(file_path: &Path, e: std::io::Error) -> FileMetadataError {
+    FileMetadataError::FileIoError {
+        path: file_path.to_path_buf(),
+        message: format!("Failed reading file: {}", e),
+    }
+}
+
+/// Raw images start each sector with a sync pattern followed by a header whose last byte is
+/// the sector mode.
+fn detect_sector_layout(
+    file: &mut File,
+    file_path: &Path,
+) -> Result<SectorLayout, FileMetadataError> {
+    let mut header = [0u8; 16];
+    file.seek(SeekFrom::Start(VOLUME_DESCRIPTOR_START * RAW_SECTOR_SIZE))
+        .map_err(|e| io_error(file_path, e))?;
+    if file.read_exact(&mut header).is_err() || header[..12] != RAW_SECTOR_SYNC {
+        return Ok(SectorLayout::COOKED);
+    }
+    match header[15] {
+        1 => Ok(SectorLayout {
+            sector_size: RAW_SECTOR_SIZE,
+            data_offset: 16,
+        }),
+        // Mode 2 Form 1 has an additional 8 byte subheader
+        2 => Ok(SectorLayout {
+            sector_size: RAW_SECTOR_SIZE,
+            data_offset: 24,
+        }),
+        mode => Err(iso_error(
+            file_path,
+            format!("Unsupported sector mode {}", mode),
+        )),
+    }
+}
+
+fn parse_directory_record(bytes: &[u8]) -> Option<DirectoryRecord> {
+    let length = *bytes.first()? as usize;
+    if length < 34 || bytes.len() < length {
+        return None;
+    }
+    let name_length = bytes[32] as usize;
+    if 33 + name_length > length {
+        return None;
+    }
+    let raw_name = &bytes[33..33 + name_length];
+    Some(DirectoryRecord {
+        name: normalize_name(raw_name),
+        extent: u32::from_le_bytes(bytes[2..6].try_into().ok()?),
+        data_length: u32::from_le_bytes(bytes[10..14].try_into().ok()?),
+        is_directory: bytes[25] & DIRECTORY_FLAG != 0,
+    })
+}
+
+/// Strip the `;1` version suffix and the trailing dot of extensionless file names.
+fn normalize_name(raw_name: &[u8]) -> String {
+    let name = String::from_utf8_lossy(raw_name);
+    let name = name.split(';').next().unwrap_or_default();
+    name.strip_suffix('.').unwrap_or(name).to_string()
+}
+
+struct IsoImage<'a> {
+    file: File,
+    layout: SectorLayout,
+    file_path: &'a Path,
+}
+
+impl IsoImage<'_> {
+    fn read_sector(&mut self, lba: u64) -> Result<[u8; SECTOR_DATA_SIZE], FileMetadataError> {
+        let mut sector = [0u8; SECTOR_DATA_SIZE];
+        self.file
+            .seek(SeekFrom::Start(self.layout.position(lba)))
+            .map_err(|e| io_error(self.file_path, e))?;
+        self.file
+            .read_exact(&mut sector)
+            .map_err(|e| io_error(self.file_path, e))?;
+        Ok(sector)
+    }
+
+    fn read_root_directory_record(&mut self) -> Result<DirectoryRecord, FileMetadataError> {
+        let mut lba = VOLUME_DESCRIPTOR_START;
+        loop {
+            let sector = self.read_sector(lba).map_err(|_| {
+                iso_error(self.file_path, "No ISO9660 primary volume descriptor found")
+            })?;
+            if &sector[1..6] != STANDARD_IDENTIFIER {
+                return Err(iso_error(self.file_path, "Not an ISO9660 image"));
+            }
+            match sector[0] {
+                VOLUME_DESCRIPTOR_PRIMARY => {
+                    return parse_directory_record(&sector[ROOT_DIRECTORY_RECORD_OFFSET..])
+                        .ok_or_else(|| iso_error(self.file_path, "Invalid root directory record"));
+                }
+                VOLUME_DESCRIPTOR_TERMINATOR => {
+                    return Err(iso_error(
+                        self.file_path,
+                        "No ISO9660 primary volume descriptor found",
+                    ));
+                }
+                _ => lba += 1,
+            }
+        }
+    }
+
+    fn read_directory(
+        &mut self,
+        directory: &DirectoryRecord,
+        parent_path: &str,
+        depth: usize,
+        visited: &mut HashSet<u32>,
+        read_files: &mut Vec<ReadFile>,
+    ) -> Result<(), FileMetadataError> {
+        if depth > MAX_DIRECTORY_DEPTH || !visited.insert(directory.extent) {
+            return Err(iso_error(self.file_path, "Invalid directory structure"));
+        }
+
+        let sector_count = (directory.data_length as u64).div_ceil(SECTOR_DATA_SIZE as u64);
+        let mut entries = Vec::new();
+        for i in 0..sector_count {
+            let sector = self.read_sector(directory.extent as u64 + i)?;
+            let mut offset = 0;
+            // Records never span sectors; a zero length marks the padding at the end of a sector
+            while offset < sector.len() && sector[offset] != 0 {
+                let record = parse_directory_record(&sector[offset..])
+                    .ok_or_else(|| iso_error(self.file_path, "Invalid directory record"))?;
+                offset += sector[offset] as usize;
+                // Skip the self and parent entries
+                if record.name == "\0" || record.name == "\u{1}" {
+                    continue;
+                }
+                entries.push(record);
+            }
+        }
+
+        for entry in entries {
+            let entry_path = if parent_path.is_empty() {
+                entry.name.clone()
+            } else {
+                format!("{}/{}", parent_path, entry.name)
+            };
+            if entry.is_directory {
+                self.read_directory(&entry, &entry_path, depth + 1, visited, read_files)?;
+            } else {
+                let file_path = self.file_path;
+                let mut extent_reader = ExtentReader {
+                    image: self,
+                    lba: entry.extent as u64,
+                    sector_offset: 0,
+                    remaining: entry.data_length as u64,
+                };
+                let (sha1_checksum, file_size) = hash_reader(&mut extent_reader, file_path)?;
+                read_files.push(ReadFile {
+                    file_name: entry_path,
+                    sha1_checksum,
+                    file_size,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the contents of a file extent, skipping the headers and error correction data of raw
+/// sectors.
+struct ExtentReader<'a, 'b> {
+    image: &'a mut IsoImage<'b>,
+    lba: u64,
+    sector_offset: usize,
+    remaining: u64,
+}
+
+impl Read for ExtentReader<'_, '_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+        let sector = self
+            .image
+            .read_sector(self.lba)
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        let available = &sector[self.sector_offset..];
+        let count = buf.len().min(available.len()).min(self.remaining as usize);
+        buf[..count].copy_from_slice(&available[..count]);
+        self.sector_offset += count;
+        if self.sector_offset == sector.len() {
+            self.lba += 1;
+            self.sector_offset = 0;
+        }
+        self.remaining -= count as u64;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core_types::sha1_bytes_to_hex_string;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    const TEST_ISO: &str = "example-data/test_disc.iso";
+
+    fn assert_test_disc_listing(listing: &[ReadFile]) {
+        assert_eq!(listing.len(), 3);
+        let system_cnf = listing
+            .iter()
+            .find(|f| f.file_name == "SYSTEM.CNF")
+            .unwrap();
+        assert_eq!(system_cnf.file_size, 29);
+        assert_eq!(
+            sha1_bytes_to_hex_string(&system_cnf.sha1_checksum),
+            "632235bee75cc315cd3a76b98291ad496d5bc3df"
+        );
+        let executable = listing
+            .iter()
+            .find(|f| f.file_name == "SLUS_000.01")
+            .unwrap();
+        assert_eq!(executable.file_size, 1);
+        assert_eq!(
+            sha1_bytes_to_hex_string(&executable.sha1_checksum),
+            "85e53271e14006f0265921d02d4d736cdc580b0b"
+        );
+        let readme = listing
+            .iter()
+            .find(|f| f.file_name == "DATA/README.TXT")
+            .unwrap();
+        assert_eq!(readme.file_size, 13);
+        assert_eq!(
+            sha1_bytes_to_hex_string(&readme.sha1_checksum),
+            "943a702d06f34599aee1f8da8ef9f7296031d699"
+        );
+    }
+
+    #[test]
+    fn test_read_iso9660_listing() {
+        let reader = Iso9660FileMetadataReader::new(Path::new(TEST_ISO)).unwrap();
+        let listing = reader.read_metadata().unwrap();
+        assert_test_disc_listing(&listing);
+    }
+
+    #[test]
+    fn test_read_iso9660_listing_raw_mode1_bin() {
+        // Wrap each 2048 byte sector of the test image into a raw Mode 1 sector
+        let iso = std::fs::read(TEST_ISO).unwrap();
+        let mut bin = Vec::new();
+        for sector in iso.chunks(SECTOR_DATA_SIZE) {
+            bin.extend_from_slice(&RAW_SECTOR_SYNC);
+            bin.extend_from_slice(&[0, 0, 0, 1]);
+            bin.extend_from_slice(sector);
+            bin.extend_from_slice(&[0u8; 288]);
+        }
+        let temp_dir = tempdir().unwrap();
+        let bin_path = temp_dir.path().join("test_disc.bin");
+        std::fs::write(&bin_path, bin).unwrap();
+
+        let listing = read_iso9660_listing(&bin_path).unwrap();
+        assert_test_disc_listing(&listing);
+    }
+
+    #[test]
+    fn test_read_iso9660_listing_not_an_iso() {
+        let result = read_iso9660_listing(Path::new("example-data/one_byte_255.bin"));
+        assert!(matches!(
+            result,
+            Err(FileMetadataError::Iso9660Error { .. })
+        ));
+    }
+
+    #[test]
+    fn test_is_disc_image_file_name() {
+        assert!(is_disc_image_file_name(Path::new("game.iso")));
+        assert!(is_disc_image_file_name(Path::new("game (Track 1).BIN")));
+        assert!(!is_disc_image_file_name(Path::new("game.cue")));
+    }
+}
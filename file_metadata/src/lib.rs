@@ -1,10 +1,11 @@
 pub mod file_metadata_ops;
+pub mod iso9660;
 pub mod reader_factory;
 
 use std::{
     collections::HashMap,
     fs::File,
-    io::Read,
+    io::{BufReader, Read, Seek, SeekFrom},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -15,13 +16,16 @@ use sha1::{
     digest::{consts::U20, generic_array::GenericArray},
 };
 
+use flate2::read::GzDecoder;
+use tar::{Archive, EntryType};
 use utils::file_util::{self};
-use zip::ZipArchive;
+use zip::{ZipArchive, result::ZipError};
 
 /// Supported file types for metadata extraction
 pub enum FileType {
     Single,
     Zip,
+    Tar,
     // TODO 7z,
 }
 
@@ -42,6 +46,15 @@ pub enum FileMetadataError {
     #[error("Zip error for {path}: {message}")]
     ZipError { path: PathBuf, message: String },
 
+    #[error("Archive is password-protected: {0}")]
+    EncryptedArchive(PathBuf),
+
+    #[error("ISO9660 error for {path}: {message}")]
+    Iso9660Error { path: PathBuf, message: String },
+
+    #[error("Tar error for {path}: {message}")]
+    TarError { path: PathBuf, message: String },
+
     #[error("Unsupported file format: {0}")]
     UnsupportedFormat(PathBuf),
 
@@ -49,6 +62,29 @@ pub enum FileMetadataError {
     GeneralError { path: PathBuf, message: String },
 }
 
+/// Metadata of a file in an archive, including how it's stored in the archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntryInfo {
+    pub read_file: ReadFile,
+    /// Size of the entry in the archive, None if the format doesn't tell
+    pub compressed_size: Option<u64>,
+    /// Compression method of the entry, e.g. "Stored" or "Deflated"
+    pub compression_method: Option<String>,
+}
+
+impl ArchiveEntryInfo {
+    /// Ratio of compressed size to uncompressed size. Returns None if the compressed size is
+    /// unknown or the entry is empty.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        match self.compressed_size {
+            Some(compressed_size) if self.read_file.file_size > 0 => {
+                Some(compressed_size as f64 / self.read_file.file_size as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Trait for reading file metadata from various sources
 pub trait FileMetadataReader: Send + Sync {
     /// Read metadata for all files in this source
@@ -118,6 +154,7 @@ fn read_file_checksum(
 
 pub struct ZipFileMetadataReader {
     path: PathBuf,
+    skip_resource_forks: bool,
 }
 
 impl ZipFileMetadataReader {
@@ -127,13 +164,28 @@ impl ZipFileMetadataReader {
         }
         Ok(Self {
             path: path.to_path_buf(),
+            skip_resource_forks: true,
         })
     }
+
+    /// Set whether macOS `__MACOSX/` and `._` AppleDouble entries are skipped. Defaults to true.
+    pub fn with_skip_resource_forks(mut self, skip_resource_forks: bool) -> Self {
+        self.skip_resource_forks = skip_resource_forks;
+        self
+    }
+}
+
+impl ZipFileMetadataReader {
+    /// Read metadata for all files in the archive including compressed sizes and compression
+    /// methods.
+    pub fn read_entry_infos(&self) -> Result<Vec<ArchiveEntryInfo>, FileMetadataError> {
+        read_zip_entry_infos(&self.path, self.skip_resource_forks)
+    }
 }
 
 impl FileMetadataReader for ZipFileMetadataReader {
     fn read_metadata(&self) -> Result<Vec<ReadFile>, FileMetadataError> {
-        let entries = read_zip_contents_with_checksums(&self.path)?;
+        let entries = read_zip_contents_with_checksums(&self.path, self.skip_resource_forks)?;
         Ok(entries.into_values().collect())
     }
 }
@@ -143,13 +195,28 @@ impl FileMetadataReader for ZipFileMetadataReader {
 /// # Arguments
 ///
 /// * `file_path` - The path to the zip file.
+/// * `skip_resource_forks` - Skip macOS `__MACOSX/` and `._` AppleDouble entries.
 ///
 /// # Returns
 ///
 /// A `Result` containing hash map from sha1 key to ImportFile with file name, sha1 checksum and size from files in the archive or an error if the operation fails.
 fn read_zip_contents_with_checksums(
     file_path: &PathBuf,
+    skip_resource_forks: bool,
 ) -> Result<HashMap<Sha1Checksum, ReadFile>, FileMetadataError> {
+    let entries = read_zip_entry_infos(file_path, skip_resource_forks)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| (entry.read_file.sha1_checksum, entry.read_file))
+        .collect())
+}
+
+/// Read the files of a zip archive with their checksums, sizes, compressed sizes and
+/// compression methods.
+fn read_zip_entry_infos(
+    file_path: &PathBuf,
+    skip_resource_forks: bool,
+) -> Result<Vec<ArchiveEntryInfo>, FileMetadataError> {
     let file = File::open(file_path).map_err(|e| FileMetadataError::FileIoError {
         path: file_path.clone(),
         message: format!("Failed opening file: {}", e),
@@ -159,41 +226,179 @@ fn read_zip_contents_with_checksums(
         message: format!("Failed reading Zip file: {}", e),
     })?;
 
-    let mut sha1_to_file_name_map: HashMap<Sha1Checksum, ReadFile> = HashMap::new();
+    let mut entries = Vec::new();
 
     for i in 0..archive.len() {
         let mut file = archive
             .by_index(i)
-            .map_err(|e| FileMetadataError::ZipError {
-                path: file_path.clone(),
-                message: format!("Failed reading Zip file: {}", e),
-            })?;
+            .map_err(|e| map_zip_entry_error(file_path, e))?;
+        if skip_resource_forks && is_macos_resource_fork(file.name()) {
+            continue;
+        }
         if file.is_file() {
-            let mut buffer = [0u8; 8192]; // 8 KB buffer
-            let mut hasher = Sha1::new();
-            let mut size: u64 = 0;
-            loop {
-                let bytes_read =
-                    file.read(&mut buffer)
-                        .map_err(|e| FileMetadataError::FileIoError {
-                            path: file_path.clone(),
-                            message: format!("Failed reading file: {}", e),
-                        })?;
-                if bytes_read == 0 {
-                    break; // EOF
-                }
-                size += bytes_read as u64;
-                hasher.update(&buffer[..bytes_read]);
-            }
-            let sha1_checksum: GenericArray<u8, U20> = hasher.finalize();
-            let sha1_checksum: Sha1Checksum = sha1_checksum.into();
-            let read_file = ReadFile {
-                file_name: file.name().to_string(),
-                sha1_checksum,
-                file_size: size,
-            };
-            sha1_to_file_name_map.insert(sha1_checksum, read_file);
+            let (sha1_checksum, size) = hash_reader(&mut file, file_path)?;
+            entries.push(ArchiveEntryInfo {
+                read_file: ReadFile {
+                    file_name: file.name().to_string(),
+                    sha1_checksum,
+                    file_size: size,
+                },
+                compressed_size: Some(file.compressed_size()),
+                compression_method: Some(file.compression().to_string()),
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+fn map_zip_entry_error(file_path: &Path, error: ZipError) -> FileMetadataError {
+    match error {
+        ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) | ZipError::InvalidPassword => {
+            FileMetadataError::EncryptedArchive(file_path.to_path_buf())
+        }
+        e => FileMetadataError::ZipError {
+            path: file_path.to_path_buf(),
+            message: format!("Failed reading Zip file: {}", e),
+        },
+    }
+}
+
+/// Check whether a zip entry is macOS metadata: anything under `__MACOSX/` or an AppleDouble
+/// `._` file.
+fn is_macos_resource_fork(entry_name: &str) -> bool {
+    entry_name.starts_with("__MACOSX/")
+        || entry_name
+            .rsplit('/')
+            .next()
+            .is_some_and(|base_name| base_name.starts_with("._"))
+}
+
+/// Calculate sha1 checksum and size by streaming the reader through an 8 KB buffer.
+pub(crate) fn hash_reader<R: Read>(
+    reader: &mut R,
+    file_path: &Path,
+) -> Result<(Sha1Checksum, u64), FileMetadataError> {
+    let mut buffer = [0u8; 8192]; // 8 KB buffer
+    let mut hasher = Sha1::new();
+    let mut size: u64 = 0;
+    loop {
+        let bytes_read = reader
+            .read(&mut buffer)
+            .map_err(|e| FileMetadataError::FileIoError {
+                path: file_path.to_path_buf(),
+                message: format!("Failed reading file: {}", e),
+            })?;
+        if bytes_read == 0 {
+            break; // EOF
+        }
+        size += bytes_read as u64;
+        hasher.update(&buffer[..bytes_read]);
+    }
+    let sha1_checksum: GenericArray<u8, U20> = hasher.finalize();
+    Ok((sha1_checksum.into(), size))
+}
+
+/// Read metadata of regular files in a tar archive, optionally gzip compressed.
+pub struct TarFileMetadataReader {
+    path: PathBuf,
+}
+
+impl TarFileMetadataReader {
+    pub fn new(path: &Path) -> Result<Self, FileMetadataError> {
+        if !path.exists() {
+            return Err(FileMetadataError::FileNotFound(path.to_path_buf()));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl FileMetadataReader for TarFileMetadataReader {
+    fn read_metadata(&self) -> Result<Vec<ReadFile>, FileMetadataError> {
+        let entries = read_tar_contents_with_checksums(&self.path)?;
+        Ok(entries.into_values().collect())
+    }
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Check whether the file is gzip compressed, either by `.gz`/`.tgz` extension or by magic bytes.
+fn is_gzip_file(file: &mut File, file_path: &Path) -> Result<bool, FileMetadataError> {
+    if matches!(
+        file_path.extension().and_then(|s| s.to_str()),
+        Some("gz") | Some("tgz")
+    ) {
+        return Ok(true);
+    }
+    let io_error = |e: std::io::Error| FileMetadataError::FileIoError {
+        path: file_path.to_path_buf(),
+        message: format!("Failed reading file: {}", e),
+    };
+    let mut magic = [0u8; 2];
+    let is_gzip = match file.read_exact(&mut magic) {
+        Ok(()) => magic == GZIP_MAGIC,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => false,
+        Err(e) => return Err(io_error(e)),
+    };
+    file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+    Ok(is_gzip)
+}
+
+/// Get the contents of a tar file and calculate sha1 checksum and size for each regular file.
+///
+/// Gzip compressed archives are decompressed transparently. Directories, symlinks and other
+/// non-regular entries are skipped.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the tar file.
+///
+/// # Returns
+///
+/// A `Result` containing hash map from sha1 key to ImportFile with file name, sha1 checksum and size from files in the archive or an error if the operation fails.
+fn read_tar_contents_with_checksums(
+    file_path: &PathBuf,
+) -> Result<HashMap<Sha1Checksum, ReadFile>, FileMetadataError> {
+    let mut file = File::open(file_path).map_err(|e| FileMetadataError::FileIoError {
+        path: file_path.clone(),
+        message: format!("Failed opening file: {}", e),
+    })?;
+    let reader: Box<dyn Read> = if is_gzip_file(&mut file, file_path)? {
+        Box::new(GzDecoder::new(BufReader::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    let mut archive = Archive::new(reader);
+
+    let tar_error = |e: std::io::Error| FileMetadataError::TarError {
+        path: file_path.clone(),
+        message: format!("Failed reading Tar file: {}", e),
+    };
+
+    let mut sha1_to_file_name_map: HashMap<Sha1Checksum, ReadFile> = HashMap::new();
+
+    for entry in archive.entries().map_err(tar_error)? {
+        let mut entry = entry.map_err(tar_error)?;
+        if !matches!(
+            entry.header().entry_type(),
+            EntryType::Regular | EntryType::Continuous
+        ) {
+            continue;
         }
+        let file_name = entry
+            .path()
+            .map_err(tar_error)?
+            .to_string_lossy()
+            .to_string();
+        let (sha1_checksum, size) = hash_reader(&mut entry, file_path)?;
+        let read_file = ReadFile {
+            file_name,
+            sha1_checksum,
+            file_size: size,
+        };
+        sha1_to_file_name_map.insert(sha1_checksum, read_file);
     }
 
     Ok(sha1_to_file_name_map)
@@ -331,6 +536,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_zip_file_metadata_reader_skips_macos_resource_forks() {
+        let test_file_path = Path::new("example-data/with_macosx_entries.zip");
+        let reader = ZipFileMetadataReader::new(test_file_path).unwrap();
+        let metadata = reader.read_metadata().unwrap();
+        assert_eq!(metadata.len(), 1);
+        assert_eq!(metadata[0].file_name, "one_byte_255.bin");
+        assert_eq!(
+            sha1_bytes_to_hex_string(&metadata[0].sha1_checksum),
+            "85e53271e14006f0265921d02d4d736cdc580b0b"
+        );
+    }
+
+    #[test]
+    fn test_zip_file_metadata_reader_keeps_macos_resource_forks_when_disabled() {
+        let test_file_path = Path::new("example-data/with_macosx_entries.zip");
+        let reader = ZipFileMetadataReader::new(test_file_path)
+            .unwrap()
+            .with_skip_resource_forks(false);
+        let metadata = reader.read_metadata().unwrap();
+        assert_eq!(metadata.len(), 3);
+    }
+
     #[test]
     fn test_zip_file_metadata_reader_nonexistent_file() {
         let test_file_path = Path::new("example-data/nonexistent_file.zip");
@@ -358,6 +586,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_tar_file_metadata_reader_gzipped_tar() {
+        let test_file_path = Path::new("example-data/multiple_files.tar.gz");
+        let reader = TarFileMetadataReader::new(test_file_path).unwrap();
+        let metadata = reader.read_metadata().unwrap();
+        // directory and symlink entries are skipped
+        assert_eq!(metadata.len(), 2);
+        let one_byte_255_file = metadata
+            .iter()
+            .find(|f| f.file_name == "roms/one_byte_255.bin")
+            .unwrap();
+        assert_eq!(one_byte_255_file.file_size, 1);
+        assert_eq!(
+            sha1_bytes_to_hex_string(&one_byte_255_file.sha1_checksum),
+            "85e53271e14006f0265921d02d4d736cdc580b0b"
+        );
+
+        let empty_file = metadata
+            .iter()
+            .find(|f| f.file_name == "roms/empty.bin")
+            .unwrap();
+        assert_eq!(empty_file.file_size, 0);
+        assert_eq!(
+            sha1_bytes_to_hex_string(&empty_file.sha1_checksum),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+    }
+
+    #[test]
+    fn test_tar_file_metadata_reader_gzip_detected_by_magic_bytes() {
+        let temp_dir = tempdir().unwrap();
+        let tar_file_path = temp_dir.path().join("renamed.tar");
+        std::fs::copy("example-data/multiple_files.tar.gz", &tar_file_path).unwrap();
+
+        let reader = TarFileMetadataReader::new(&tar_file_path).unwrap();
+        let metadata = reader.read_metadata().unwrap();
+        assert_eq!(metadata.len(), 2);
+    }
+
+    #[test]
+    fn test_read_tar_contents_with_checksums() {
+        let temp_dir = tempdir().unwrap();
+        let tar_file_path = temp_dir.path().join(TEST_TAR_ARCHIVE_NAME);
+        let tar_file = File::create(&tar_file_path).unwrap();
+        let mut builder = tar::Builder::new(tar_file);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(TEST_FILE_CONTENT.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, TEST_FILE_NAME, TEST_FILE_CONTENT.as_bytes())
+            .unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        let hash_map = read_tar_contents_with_checksums(&tar_file_path).unwrap();
+        assert_eq!(hash_map.len(), 1);
+        let (checksum, _) = get_sha1_and_size(TEST_FILE_CONTENT);
+        let expected_file = ReadFile {
+            file_name: TEST_FILE_NAME.to_string(),
+            sha1_checksum: checksum,
+            file_size: TEST_FILE_CONTENT.len() as u64,
+        };
+        assert_eq!(hash_map[&checksum], expected_file);
+    }
+
+    #[test]
+    fn test_tar_file_metadata_reader_invalid_tar() {
+        let temp_dir = tempdir().unwrap();
+        let tar_file_path = temp_dir.path().join("invalid.tar.gz");
+        std::fs::write(&tar_file_path, b"not a tarball").unwrap();
+
+        let reader = TarFileMetadataReader::new(&tar_file_path).unwrap();
+        assert!(reader.read_metadata().is_err());
+    }
+
+    #[test]
+    fn test_zip_file_metadata_reader_encrypted_zip() {
+        let test_file_path = Path::new("example-data/encrypted.zip");
+        let reader = ZipFileMetadataReader::new(test_file_path).unwrap();
+        let result = reader.read_metadata();
+        match result {
+            Err(FileMetadataError::EncryptedArchive(path)) => {
+                assert_eq!(path, test_file_path);
+            }
+            other => panic!("Expected EncryptedArchive error, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_mock_file_metadata_reader() {
         let mock_metadata = vec![
@@ -396,7 +713,52 @@ mod tests {
         assert_eq!(metadata, mock_metadata);
     }
 
+    #[test]
+    fn test_zip_file_metadata_reader_entry_infos() {
+        let temp_dir = tempdir().unwrap();
+        let zip_file_path = temp_dir.path().join(TEST_ZIP_ARCHIVE_NAME);
+        let zip_file = File::create(&zip_file_path).unwrap();
+        let mut zip_writer = zip::ZipWriter::new(zip_file);
+        let content = TEST_FILE_CONTENT.repeat(100);
+        let stored_options: FileOptions<'_, ()> =
+            FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip_writer.start_file("stored.txt", stored_options).unwrap();
+        zip_writer.write_all(content.as_bytes()).unwrap();
+        let deflated_options: FileOptions<'_, ()> =
+            FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip_writer
+            .start_file("deflated.txt", deflated_options)
+            .unwrap();
+        zip_writer.write_all(content.as_bytes()).unwrap();
+        zip_writer.finish().unwrap();
+
+        let reader = ZipFileMetadataReader::new(&zip_file_path).unwrap();
+        let entries = reader.read_entry_infos().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let stored = entries
+            .iter()
+            .find(|e| e.read_file.file_name == "stored.txt")
+            .unwrap();
+        assert_eq!(stored.compression_method.as_deref(), Some("Stored"));
+        assert_eq!(stored.compressed_size, Some(content.len() as u64));
+        assert_eq!(stored.compression_ratio(), Some(1.0));
+
+        let deflated = entries
+            .iter()
+            .find(|e| e.read_file.file_name == "deflated.txt")
+            .unwrap();
+        assert_eq!(deflated.compression_method.as_deref(), Some("Deflated"));
+        assert!(deflated.compressed_size.unwrap() < content.len() as u64);
+        assert_eq!(deflated.read_file.file_size, content.len() as u64);
+        assert_eq!(
+            deflated.read_file.sha1_checksum,
+            stored.read_file.sha1_checksum
+        );
+    }
+
     const TEST_ZIP_ARCHIVE_NAME: &str = "test.zip";
+    const TEST_TAR_ARCHIVE_NAME: &str = "test.tar";
     const TEST_FILE_NAME: &str = "test_file";
     const TEST_FILE_CONTENT: &str = "Hello, world!";
 
@@ -413,7 +775,7 @@ mod tests {
         zip_writer.write_all(TEST_FILE_CONTENT.as_bytes()).unwrap();
         zip_writer.finish().unwrap();
 
-        let result = read_zip_contents_with_checksums(&zip_file_path);
+        let result = read_zip_contents_with_checksums(&zip_file_path, true);
         assert!(result.is_ok());
         let hash_map = result.unwrap();
         assert_eq!(hash_map.len(), 1);
@@ -4,7 +4,7 @@ use utils::file_util::is_zip_file;
 
 use crate::{
     FileMetadataError, FileMetadataReader, FileType, SingleFileMetadataReader,
-    ZipFileMetadataReader,
+    TarFileMetadataReader, ZipFileMetadataReader,
 };
 
 /// Type alias for factory function
@@ -17,6 +17,7 @@ pub fn create_metadata_reader(
     match detect_file_type(path)? {
         FileType::Single => Ok(Box::new(SingleFileMetadataReader::new(path)?)),
         FileType::Zip => Ok(Box::new(ZipFileMetadataReader::new(path)?)),
+        FileType::Tar => Ok(Box::new(TarFileMetadataReader::new(path)?)),
         // TODO: FileType::7z, etc.
     }
 }
@@ -24,6 +25,9 @@ pub fn create_metadata_reader(
 // TODO: this could be extended to detect more file types in the future and moved to a
 // separate module or crate if needed.
 fn detect_file_type(path: &Path) -> Result<FileType, FileMetadataError> {
+    if is_tar_file_name(path) {
+        return Ok(FileType::Tar);
+    }
     match path.extension().and_then(|s| s.to_str()) {
         Some("zip") => match is_zip_file(path) {
             Ok(true) => Ok(FileType::Zip),
@@ -37,6 +41,15 @@ fn detect_file_type(path: &Path) -> Result<FileType, FileMetadataError> {
     }
 }
 
+fn is_tar_file_name(path: &Path) -> bool {
+    let file_name = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    file_name.ends_with(".tar") || file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::FileMetadataError;
@@ -76,6 +89,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_metadata_reader_tar_gz_file() {
+        let test_file_path = Path::new("example-data/multiple_files.tar.gz");
+        let reader = create_metadata_reader(test_file_path).unwrap();
+        let metadata = reader.read_metadata().unwrap();
+        assert_eq!(metadata.len(), 2);
+        assert!(
+            metadata
+                .iter()
+                .any(|f| f.file_name == "roms/one_byte_255.bin")
+        );
+    }
+
     #[test]
     fn test_create_metadata_reader_invalid_zip() {
         let test_file_path = Path::new("example-data/invalid.zip");
@@ -18,6 +18,11 @@ pub fn get_logs_dir() -> PathBuf {
     get_default_data_dir().join("logs")
 }
 
+/// Returns path to encrypted credentials file located in default data dir for application.
+pub fn get_credentials_file_path() -> PathBuf {
+    get_default_data_dir().join("credentials.enc")
+}
+
 fn get_default_data_dir() -> std::path::PathBuf {
     let project_dirs = get_project_dirs();
     let data_dir = project_dirs.data_local_dir();
@@ -1,32 +1,191 @@
 use async_std::io::{ReadExt, WriteExt};
 use async_std::{
     channel::{Receiver, Sender},
-    fs::File,
+    fs::{File, OpenOptions},
 };
 use core_types::events::HttpDownloadEvent;
+use core_types::{Sha1Checksum, sha1_bytes_to_hex_string};
+use sha1::{Digest, Sha1};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+#[cfg(test)]
+mod test_server;
+
 #[derive(Error, Debug)]
 pub enum DownloadError {
     #[error("HTTP request failed: {0}")]
     RequestFailed(String),
+    #[error("HTTP error: {0}")]
+    HttpStatus(u16),
     #[error("File IO error: {0}")]
     FileIoError(String),
     #[error("Download cancelled by user")]
     Cancelled,
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("Download exceeds the size limit of {0} bytes")]
+    SizeLimitExceeded(u64),
+    #[error("All mirrors failed: {}", .0.join("; "))]
+    AllMirrorsFailed(Vec<String>),
+    #[error("Download timed out")]
+    TimedOut,
 }
 
 pub struct DownloadResult {
     pub file_path: PathBuf,
 }
 
+impl DownloadError {
+    /// Connection failures, stalled connections and temporary server errors, which may succeed
+    /// when retried.
+    fn is_transient(&self) -> bool {
+        match self {
+            DownloadError::RequestFailed(_) | DownloadError::TimedOut => true,
+            DownloadError::HttpStatus(status) => matches!(status, 502..=504),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DownloadOptions {
+    /// When set, the downloaded file is verified against the checksum and deleted on mismatch
+    pub expected_sha1: Option<Sha1Checksum>,
+    pub retry: RetryConfig,
+    /// Download is aborted and the partial file deleted when it grows over the limit
+    pub max_bytes: Option<u64>,
+    /// Additional request headers, e.g. `User-Agent` required by a mirror
+    pub headers: Vec<(String, String)>,
+    /// Username and password for HTTP basic authentication
+    pub basic_auth: Option<(String, String)>,
+    pub progress: ProgressConfig,
+    /// Maximum duration of the whole download, the partial file is deleted when it is exceeded
+    pub timeout: Option<Duration>,
+    /// Maximum time to wait for the response and each chunk of the body, guards against stalled
+    /// connections
+    pub read_timeout: Option<Duration>,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            expected_sha1: None,
+            retry: RetryConfig::default(),
+            max_bytes: None,
+            headers: vec![],
+            basic_auth: None,
+            progress: ProgressConfig::default(),
+            timeout: None,
+            read_timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// Headers whose values are credentials and must not end up in logs.
+const SENSITIVE_HEADERS: [&str; 4] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+];
+
+// Credentials are redacted from the debug output
+impl std::fmt::Debug for DownloadOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                if SENSITIVE_HEADERS.contains(&name.to_lowercase().as_str()) {
+                    (name.as_str(), "<redacted>")
+                } else {
+                    (name.as_str(), value.as_str())
+                }
+            })
+            .collect();
+        f.debug_struct("DownloadOptions")
+            .field("expected_sha1", &self.expected_sha1)
+            .field("retry", &self.retry)
+            .field("max_bytes", &self.max_bytes)
+            .field("headers", &headers)
+            .field("progress", &self.progress)
+            .field("timeout", &self.timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field(
+                "basic_auth",
+                &self
+                    .basic_auth
+                    .as_ref()
+                    .map(|(username, _)| (username, "<redacted>")),
+            )
+            .finish()
+    }
+}
+
+/// Progress is reported when either the downloaded bytes or the elapsed time since the previous
+/// report reaches its threshold.
+#[derive(Debug, Clone)]
+pub struct ProgressConfig {
+    pub min_bytes: u64,
+    pub min_interval: Duration,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self {
+            min_bytes: 1024 * 1024,
+            min_interval: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Retrying of transient failures. The delay is doubled after each attempt.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryConfig {
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
+
 /// Download a file from a URL to a specified directory.
 ///
+/// The file is downloaded to `<file name>.part` and renamed when complete. If the `.part` file of
+/// an interrupted download exists in the directory, the download is resumed from the end of it
+/// with a `Range` request, guarded with `If-Range` by the `ETag` or `Last-Modified` of the
+/// response that started it. The download starts over if the server responds with the whole
+/// file, e.g. when it doesn't support range requests, or the returned range doesn't continue
+/// the partial file.
+///
+/// A file name from the `Content-Disposition` header is stripped of directories. If a file with
+/// the name already exists, the downloaded file is suffixed with a number (`file (1).zip`).
+///
+/// Connection failures, timeouts and 502, 503 and 504 responses are retried as configured in the
+/// options until the deadline of the whole download. A retry resumes from the last byte written
+/// by the failed attempt.
+///
 /// # Arguments
 ///
 /// * `url` - The URL to download from.
 /// * `target_dir` - The directory where the file will be saved.
+/// * `options` - Request headers and options for verifying, retrying and limiting the size of
+///   the download.
 /// * `progress_tx` - A channel sender to report download progress events.
 /// * `cancel_rx` - A channel receiver to listen for cancellation signals.
 ///
@@ -37,70 +196,267 @@ pub struct DownloadResult {
 pub async fn download_file(
     url: &str,
     target_dir: &Path,
+    options: &DownloadOptions,
     progress_tx: &Sender<HttpDownloadEvent>,
     cancel_rx: &Receiver<()>,
 ) -> Result<DownloadResult, DownloadError> {
-    match download_file_internal(url, target_dir, progress_tx, cancel_rx).await {
-        Ok(result) => Ok(result),
-        Err(e) => {
-            // Send Failed event before returning error
-            send_status_message(
-                progress_tx,
-                HttpDownloadEvent::Failed {
-                    error: e.to_string(),
-                },
-            )
-            .await;
+    let result = download_with_retries(url, target_dir, options, progress_tx, cancel_rx).await;
+    send_failed_event(progress_tx, result).await
+}
+
+/// Download a file trying the mirror URLs in order until the download succeeds.
+///
+/// Each failed mirror is reported with a `MirrorFailed` event. Cancellation stops the download
+/// without trying the remaining mirrors. See [`download_file`] for the arguments.
+///
+/// # Returns
+///
+/// The `DownloadResult` of the first successful mirror, or `DownloadError::AllMirrorsFailed`
+/// with the errors of all mirrors.
+pub async fn download_file_from_mirrors(
+    urls: &[String],
+    target_dir: &Path,
+    options: &DownloadOptions,
+    progress_tx: &Sender<HttpDownloadEvent>,
+    cancel_rx: &Receiver<()>,
+) -> Result<DownloadResult, DownloadError> {
+    let mut errors = Vec::new();
+    for url in urls {
+        match download_with_retries(url, target_dir, options, progress_tx, cancel_rx).await {
+            Ok(result) => return Ok(result),
+            Err(DownloadError::Cancelled) => {
+                return send_failed_event(progress_tx, Err(DownloadError::Cancelled)).await;
+            }
+            Err(e) => {
+                send_status_message(
+                    progress_tx,
+                    HttpDownloadEvent::MirrorFailed {
+                        url: url.clone(),
+                        error: e.to_string(),
+                    },
+                )
+                .await;
+                errors.push(format!("{}: {}", url, e));
+            }
+        }
+    }
+    send_failed_event(progress_tx, Err(DownloadError::AllMirrorsFailed(errors))).await
+}
+
+async fn download_with_retries(
+    url: &str,
+    target_dir: &Path,
+    options: &DownloadOptions,
+    progress_tx: &Sender<HttpDownloadEvent>,
+    cancel_rx: &Receiver<()>,
+) -> Result<DownloadResult, DownloadError> {
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+    // The file name is known before the request only when it's in the URL, otherwise the first
+    // response picks it. Retries keep writing to the same file.
+    let mut file_path = extract_filename_from_url(url)
+        .and_then(|name| sanitize_filename(&name))
+        .map(|file_name| target_dir.join(file_name));
+    let mut attempt = 0;
+    loop {
+        match download_file_internal(
+            url,
+            target_dir,
+            &mut file_path,
+            options,
+            deadline,
+            progress_tx,
+            cancel_rx,
+        )
+        .await
+        {
+            // Download that ran out of time isn't resumed, only timed out reads are retried
+            Err(DownloadError::TimedOut)
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) =>
+            {
+                if let Some(part_path) = file_path.as_deref().map(part_file_path)
+                    && part_path.exists()
+                {
+                    remove_partial_file(&part_path, "after timeout").await;
+                }
+                return Err(DownloadError::TimedOut);
+            }
             Err(e)
+                if e.is_transient()
+                    && attempt < options.retry.max_retries
+                    && deadline.is_none_or(|deadline| Instant::now() < deadline) =>
+            {
+                attempt += 1;
+                eprintln!("Download attempt failed, retrying: {}", e);
+                send_status_message(
+                    progress_tx,
+                    HttpDownloadEvent::Retrying {
+                        url: url.to_string(),
+                        attempt,
+                    },
+                )
+                .await;
+                async_std::task::sleep(options.retry.delay(attempt)).await;
+                if cancel_rx.try_recv().is_ok() {
+                    return Err(DownloadError::Cancelled);
+                }
+            }
+            result => return result,
         }
     }
 }
 
+/// Send Failed event before returning error
+async fn send_failed_event(
+    progress_tx: &Sender<HttpDownloadEvent>,
+    result: Result<DownloadResult, DownloadError>,
+) -> Result<DownloadResult, DownloadError> {
+    if let Err(e) = &result {
+        send_status_message(
+            progress_tx,
+            HttpDownloadEvent::Failed {
+                error: e.to_string(),
+            },
+        )
+        .await;
+    }
+    result
+}
+
 async fn download_file_internal(
     url: &str,
     target_dir: &Path,
+    file_path: &mut Option<PathBuf>,
+    options: &DownloadOptions,
+    deadline: Option<Instant>,
     progress_tx: &Sender<HttpDownloadEvent>,
     cancel_rx: &Receiver<()>,
 ) -> Result<DownloadResult, DownloadError> {
     let buffer_size = 8192; // 8KB buffer
-    let mut bytes_downloaded = 0;
-    let mut last_event_reported = 0;
-    let report_interval = 10 * buffer_size;
 
     // Create a client with default middleware (includes redirects)
     let client = surf::client().with(surf::middleware::Redirect::default());
 
-    let mut response = client
-        .get(url)
-        .await
-        .map_err(|e| DownloadError::RequestFailed(format!("Failed to send request: {}", e)))?;
+    // The download is written to a `.part` file and renamed when complete, so that an existing
+    // file is never mistaken for an interrupted download
+    let mut resume_offset = match file_path.as_deref() {
+        Some(file_path) => partial_file_size(&part_file_path(file_path)).await,
+        None => 0,
+    };
+    let validator = match file_path.as_deref() {
+        Some(file_path) if resume_offset > 0 => read_validator(&part_file_path(file_path)).await,
+        _ => None,
+    };
+
+    let mut response = send_request(
+        &client,
+        url,
+        options,
+        deadline,
+        resume_offset,
+        validator.as_deref(),
+    )
+    .await?;
 
-    if !response.status().is_success() {
-        return Err(DownloadError::RequestFailed(format!(
-            "HTTP error: {}",
-            response.status()
-        )));
+    // Partial file whose size is the total size of the file has all the content
+    let mut already_complete = false;
+    if resume_offset > 0 {
+        let (range_start, range_total) = parse_content_range(&response);
+        match response.status() {
+            surf::StatusCode::PartialContent if range_start == Some(resume_offset) => {}
+            surf::StatusCode::RequestedRangeNotSatisfiable
+                if range_total == Some(resume_offset) =>
+            {
+                already_complete = true;
+            }
+            // Range doesn't continue the partial file, which is then downloaded again
+            surf::StatusCode::PartialContent | surf::StatusCode::RequestedRangeNotSatisfiable => {
+                resume_offset = 0;
+                response = send_request(&client, url, options, deadline, 0, None).await?;
+            }
+            // Server ignored the range or the file has changed since the partial file was
+            // started (`If-Range`), the whole file is sent
+            _ => resume_offset = 0,
+        }
     }
 
-    // Extract total size from Content-Length header
-    let total_size = response
-        .header("Content-Length")
-        .and_then(|h| h.as_str().parse::<u64>().ok());
+    if !already_complete && !response.status().is_success() {
+        return Err(DownloadError::HttpStatus(response.status().into()));
+    }
 
-    let file_name = extract_filename_from_url(url)
-        .or_else(|| extract_filename_from_headers(&response))
-        .unwrap_or_else(|| "downloaded_file".to_string());
+    let resumed = resume_offset > 0;
+    let mut bytes_downloaded = resume_offset;
+    let mut last_event_reported = bytes_downloaded;
+    let mut last_reported_at = Instant::now();
 
-    let file_path = target_dir.join(&file_name);
+    // Extract total size from Content-Length header, which is the remaining size when resuming
+    let total_size = if already_complete {
+        Some(resume_offset)
+    } else {
+        response
+            .header("Content-Length")
+            .and_then(|h| h.as_str().parse::<u64>().ok())
+            .map(|content_length| bytes_downloaded + content_length)
+    };
 
-    let mut file = File::create(&file_path)
-        .await
-        .map_err(|e| DownloadError::FileIoError(format!("Failed to create file: {}", e)))?;
+    // Content-Length may be missing or wrong, so the limit is also checked while downloading
+    if let (Some(max_bytes), Some(total_size)) = (options.max_bytes, total_size)
+        && total_size > max_bytes
+    {
+        return Err(DownloadError::SizeLimitExceeded(max_bytes));
+    }
+
+    // Name from the URL or picked by an earlier attempt is reused, a name from the response
+    // must not overwrite existing files
+    let file_path = match file_path {
+        Some(file_path) => file_path.clone(),
+        None => {
+            let file_name = extract_filename_from_headers(&response)
+                .and_then(|name| sanitize_filename(&name))
+                .unwrap_or_else(|| "downloaded_file".to_string());
+            file_path
+                .insert(unique_file_path(target_dir, &file_name).await)
+                .clone()
+        }
+    };
+    let part_path = part_file_path(&file_path);
+
+    let mut file = if resumed {
+        OpenOptions::new()
+            .append(true)
+            .open(&part_path)
+            .await
+            .map_err(|e| DownloadError::FileIoError(format!("Failed to open file: {}", e)))?
+    } else {
+        write_validator(&part_path, response_validator(&response)).await;
+        File::create(&part_path)
+            .await
+            .map_err(|e| DownloadError::FileIoError(format!("Failed to create file: {}", e)))?
+    };
+
+    // Hash while writing, a resumed download includes the already downloaded part
+    let mut hasher = options.expected_sha1.map(|_| Sha1::new());
+    if let Some(hasher) = hasher.as_mut().filter(|_| resumed) {
+        hash_file(hasher, &part_path).await?;
+    }
 
     send_status_message(progress_tx, HttpDownloadEvent::Started { total_size }).await;
+    if resumed {
+        send_status_message(
+            progress_tx,
+            HttpDownloadEvent::Resumed {
+                url: url.to_string(),
+                offset: resume_offset,
+            },
+        )
+        .await;
+    }
 
-    // Take the body as an AsyncRead stream
-    let mut body = response.take_body();
+    // Take the body as an AsyncRead stream, body of a 416 response isn't part of the file
+    let mut body = if already_complete {
+        surf::Body::empty()
+    } else {
+        response.take_body()
+    };
 
     // Stream the response body in chunks
     let mut buffer = vec![0u8; buffer_size];
@@ -108,15 +464,14 @@ async fn download_file_internal(
     loop {
         // Check for cancellation
         if cancel_rx.try_recv().is_ok() {
-            if let Err(e) = async_std::fs::remove_file(&file_path).await {
-                eprintln!("Failed to remove partial file on cancellation: {}", e);
-            }
+            remove_partial_file(&part_path, "on cancellation").await;
             return Err(DownloadError::Cancelled);
         }
 
-        let bytes_read = body
-            .read(&mut buffer)
-            .await
+        // Partial file is kept on read failures and timeouts, so that a retry can resume it. It's
+        // removed when the deadline of the whole download is hit.
+        let bytes_read = with_timeout(next_timeout(options, deadline), body.read(&mut buffer))
+            .await?
             .map_err(|e| DownloadError::RequestFailed(format!("Failed to read chunk: {}", e)))?;
 
         if bytes_read == 0 {
@@ -127,10 +482,22 @@ async fn download_file_internal(
             .await
             .map_err(|e| DownloadError::FileIoError(format!("Failed to write chunk: {}", e)))?;
 
+        if let Some(hasher) = hasher.as_mut() {
+            hasher.update(&buffer[..bytes_read]);
+        }
+
         bytes_downloaded += bytes_read as u64;
 
-        if bytes_downloaded - last_event_reported >= report_interval as u64 {
+        if let Some(max_bytes) = options.max_bytes.filter(|&max| bytes_downloaded > max) {
+            remove_partial_file(&part_path, "over the size limit").await;
+            return Err(DownloadError::SizeLimitExceeded(max_bytes));
+        }
+
+        if bytes_downloaded - last_event_reported >= options.progress.min_bytes
+            || last_reported_at.elapsed() >= options.progress.min_interval
+        {
             last_event_reported = bytes_downloaded;
+            last_reported_at = Instant::now();
             send_status_message(
                 progress_tx,
                 HttpDownloadEvent::Progress { bytes_downloaded },
@@ -142,6 +509,31 @@ async fn download_file_internal(
     file.flush()
         .await
         .map_err(|e| DownloadError::FileIoError(format!("Failed to flush file: {}", e)))?;
+    drop(file);
+
+    if let (Some(expected), Some(hasher)) = (options.expected_sha1, hasher) {
+        let actual: Sha1Checksum = hasher.finalize().into();
+        if actual != expected {
+            remove_partial_file(&part_path, "with checksum mismatch").await;
+            return Err(DownloadError::ChecksumMismatch {
+                expected: sha1_bytes_to_hex_string(&expected),
+                actual: sha1_bytes_to_hex_string(&actual),
+            });
+        }
+        send_status_message(progress_tx, HttpDownloadEvent::Verified).await;
+    }
+
+    // The partial file is named after the URL so that it can be resumed, the completed file must
+    // not overwrite an existing file with the same name
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_path = unique_file_path(target_dir, &file_name).await;
+    async_std::fs::rename(&part_path, &file_path)
+        .await
+        .map_err(|e| DownloadError::FileIoError(format!("Failed to rename file: {}", e)))?;
+    write_validator(&part_path, None).await;
 
     send_status_message(
         progress_tx,
@@ -154,6 +546,152 @@ async fn download_file_internal(
     Ok(DownloadResult { file_path })
 }
 
+/// Send the request, asking for the content from `resume_offset` on when it's not 0. With the
+/// validator of the partial file the server sends the whole file instead if it has changed.
+async fn send_request(
+    client: &surf::Client,
+    url: &str,
+    options: &DownloadOptions,
+    deadline: Option<Instant>,
+    resume_offset: u64,
+    validator: Option<&str>,
+) -> Result<surf::Response, DownloadError> {
+    let mut request = client.get(url);
+    for (name, value) in &options.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    if let Some((username, password)) = &options.basic_auth {
+        let auth = surf::http::auth::BasicAuth::new(username, password);
+        request = request.header(auth.name(), auth.value());
+    }
+    if resume_offset > 0 {
+        request = request.header("Range", format!("bytes={}-", resume_offset));
+        if let Some(validator) = validator {
+            request = request.header("If-Range", validator);
+        }
+    }
+
+    with_timeout(next_timeout(options, deadline), request)
+        .await?
+        .map_err(|e| DownloadError::RequestFailed(format!("Failed to send request: {}", e)))
+}
+
+/// Start offset and total size from the `Content-Range` header, e.g. `bytes 8-19/20`, or
+/// `bytes */20` of a 416 response. Missing or unknown values are `None`.
+fn parse_content_range(response: &surf::Response) -> (Option<u64>, Option<u64>) {
+    let Some((range, total)) = response
+        .header("Content-Range")
+        .and_then(|value| value.as_str().trim().strip_prefix("bytes "))
+        .and_then(|value| value.split_once('/'))
+    else {
+        return (None, None);
+    };
+    let start = range
+        .split_once('-')
+        .and_then(|(start, _)| start.trim().parse().ok());
+    (start, total.trim().parse().ok())
+}
+
+/// `ETag` or `Last-Modified` of the response, sent in `If-Range` when resuming.
+fn response_validator(response: &surf::Response) -> Option<String> {
+    response
+        .header("ETag")
+        .or_else(|| response.header("Last-Modified"))
+        .map(|value| value.as_str().to_string())
+}
+
+/// Path of the partial file of an unfinished download, e.g. `game.zip.part`.
+fn part_file_path(file_path: &Path) -> PathBuf {
+    let mut part_path = file_path.as_os_str().to_owned();
+    part_path.push(".part");
+    PathBuf::from(part_path)
+}
+
+/// Validator of a partial file is stored next to it, so that the download can also be resumed
+/// safely after a restart.
+fn validator_file_path(part_path: &Path) -> PathBuf {
+    let mut validator_path = part_path.as_os_str().to_owned();
+    validator_path.push(".validator");
+    PathBuf::from(validator_path)
+}
+
+async fn read_validator(part_path: &Path) -> Option<String> {
+    async_std::fs::read_to_string(validator_file_path(part_path))
+        .await
+        .ok()
+        .filter(|validator| !validator.is_empty())
+}
+
+/// Store the validator of the partial file, or remove the stored one if there's none.
+async fn write_validator(part_path: &Path, validator: Option<String>) {
+    let validator_path = validator_file_path(part_path);
+    let result = match validator {
+        Some(validator) => async_std::fs::write(&validator_path, validator).await,
+        None => match async_std::fs::remove_file(&validator_path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        },
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to update download validator: {}", e);
+    }
+}
+
+/// Remove the partial file of a download that can't be resumed.
+async fn remove_partial_file(part_path: &Path, reason: &str) {
+    if let Err(e) = async_std::fs::remove_file(part_path).await {
+        eprintln!("Failed to remove partial file {}: {}", reason, e);
+    }
+    write_validator(part_path, None).await;
+}
+
+/// Time allowed for the next network operation: the read timeout limited by the time left until
+/// the deadline of the whole download.
+fn next_timeout(options: &DownloadOptions, deadline: Option<Instant>) -> Option<Duration> {
+    let time_left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    match (options.read_timeout, time_left) {
+        (Some(read_timeout), Some(time_left)) => Some(read_timeout.min(time_left)),
+        (read_timeout, time_left) => read_timeout.or(time_left),
+    }
+}
+
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = T>,
+) -> Result<T, DownloadError> {
+    match timeout {
+        Some(timeout) => async_std::future::timeout(timeout, future)
+            .await
+            .map_err(|_| DownloadError::TimedOut),
+        None => Ok(future.await),
+    }
+}
+
+async fn hash_file(hasher: &mut Sha1, file_path: &Path) -> Result<(), DownloadError> {
+    let mut file = File::open(file_path)
+        .await
+        .map_err(|e| DownloadError::FileIoError(format!("Failed to open file: {}", e)))?;
+    let mut buffer = vec![0u8; 8192];
+    loop {
+        let bytes_read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| DownloadError::FileIoError(format!("Failed to read file: {}", e)))?;
+        if bytes_read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+}
+
+/// Size of the `.part` file of an interrupted download, 0 if there's none.
+async fn partial_file_size(file_path: &Path) -> u64 {
+    async_std::fs::metadata(file_path)
+        .await
+        .map(|metadata| metadata.len())
+        .unwrap_or(0)
+}
+
 fn extract_filename_from_url(url: &str) -> Option<String> {
     url.split('/')
         .next_back()
@@ -174,6 +712,48 @@ fn extract_filename_from_headers(response: &surf::Response) -> Option<String> {
         .map(String::from)
 }
 
+/// Strip directories from a file name given by the server, so that the file can't be written
+/// outside of the target directory. Returns `None` if nothing usable is left.
+fn sanitize_filename(file_name: &str) -> Option<String> {
+    let file_name = file_name
+        .rsplit(['/', '\\'])
+        .next()?
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>();
+    let file_name = file_name.trim();
+    if file_name.is_empty() || file_name.chars().all(|c| c == '.') {
+        return None;
+    }
+    Some(file_name.to_string())
+}
+
+/// Path for the file in the directory, with a numeric suffix (`file (1).zip`) if the name is
+/// already taken.
+async fn unique_file_path(target_dir: &Path, file_name: &str) -> PathBuf {
+    let file_path = target_dir.join(file_name);
+    if async_std::fs::metadata(&file_path).await.is_err() {
+        return file_path;
+    }
+    let name = Path::new(file_name);
+    let stem = name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = name
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let mut suffix = 1;
+    loop {
+        let file_path = target_dir.join(format!("{} ({}){}", stem, suffix, extension));
+        if async_std::fs::metadata(&file_path).await.is_err() {
+            return file_path;
+        }
+        suffix += 1;
+    }
+}
+
 async fn send_status_message(progress_tx: &Sender<HttpDownloadEvent>, event: HttpDownloadEvent) {
     if let Err(err) = progress_tx.send(event).await {
         eprintln!("Failed to send download progress event: {}", err);
@@ -183,6 +763,8 @@ async fn send_status_message(progress_tx: &Sender<HttpDownloadEvent>, event: Htt
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use test_server::StubResponse;
 
     #[test]
     fn test_extract_filename_from_url() {
@@ -200,4 +782,714 @@ mod tests {
         );
         assert_eq!(extract_filename_from_url("https://example.com/"), None);
     }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "efm-http-downloader-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn collect_events(progress_rx: &Receiver<HttpDownloadEvent>) -> Vec<HttpDownloadEvent> {
+        std::iter::from_fn(|| progress_rx.try_recv().ok()).collect()
+    }
+
+    #[async_std::test]
+    async fn test_resume_partial_download() {
+        let content = b"0123456789abcdefghij";
+        let base_url = test_server::start(|request| match request.header("Range") {
+            Some("bytes=8-") => StubResponse::ok(&content[8..])
+                .with_status(206)
+                .with_header("Content-Range", "bytes 8-19/20"),
+            _ => StubResponse::ok(content),
+        })
+        .await;
+        let dir = temp_dir("resume");
+        std::fs::write(dir.join("game.dat.part"), &content[..8]).unwrap();
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        let events = collect_events(&progress_rx);
+        assert!(matches!(
+            events[0],
+            HttpDownloadEvent::Started {
+                total_size: Some(20)
+            }
+        ));
+        assert!(matches!(
+            events[1],
+            HttpDownloadEvent::Resumed { offset: 8, .. }
+        ));
+        assert!(!dir.join("game.dat.part").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_existing_file_is_not_resumed() {
+        let content = b"0123456789abcdefghij";
+        let base_url = test_server::start(|request| match request.header("Range") {
+            Some(_) => StubResponse::ok(b"").with_status(416),
+            None => StubResponse::ok(content),
+        })
+        .await;
+        let dir = temp_dir("existing");
+        std::fs::write(dir.join("game.dat"), b"previous version of the file").unwrap();
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.file_path, dir.join("game (1).dat"));
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert_eq!(
+            std::fs::read(dir.join("game.dat")).unwrap(),
+            b"previous version of the file"
+        );
+        assert!(
+            !collect_events(&progress_rx)
+                .iter()
+                .any(|event| matches!(event, HttpDownloadEvent::Resumed { .. }))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_complete_partial_file_is_not_downloaded_again() {
+        let content = b"0123456789abcdefghij";
+        let base_url = test_server::start(|request| match request.header("Range") {
+            Some("bytes=20-") => StubResponse::ok(b"")
+                .with_status(416)
+                .with_header("Content-Range", "bytes */20"),
+            _ => StubResponse::ok(b"").with_status(500),
+        })
+        .await;
+        let dir = temp_dir("complete");
+        std::fs::write(dir.join("game.dat.part"), content).unwrap();
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            expected_sha1: Some(Sha1::digest(content).into()),
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.file_path, dir.join("game.dat"));
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert!(!dir.join("game.dat.part").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_restart_when_range_does_not_continue_partial_file() {
+        let content = b"0123456789abcdefghij";
+        let base_url = test_server::start(|request| match request.header("Range") {
+            Some(_) => StubResponse::ok(&content[4..])
+                .with_status(206)
+                .with_header("Content-Range", "bytes 4-19/20"),
+            None => StubResponse::ok(content),
+        })
+        .await;
+        let dir = temp_dir("range-mismatch");
+        std::fs::write(dir.join("game.dat.part"), &content[..8]).unwrap();
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert!(
+            !collect_events(&progress_rx)
+                .iter()
+                .any(|event| matches!(event, HttpDownloadEvent::Resumed { .. }))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_restart_when_range_is_not_supported() {
+        let content = b"0123456789abcdefghij";
+        let base_url = test_server::start(|_| StubResponse::ok(content)).await;
+        let dir = temp_dir("restart");
+        std::fs::write(dir.join("game.dat.part"), b"stale").unwrap();
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert!(
+            !collect_events(&progress_rx)
+                .iter()
+                .any(|event| matches!(event, HttpDownloadEvent::Resumed { .. }))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_verify_checksum() {
+        let content = b"verified content";
+        let base_url = test_server::start(|_| StubResponse::ok(content)).await;
+        let dir = temp_dir("verify");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            expected_sha1: Some(Sha1::digest(content).into()),
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert!(
+            collect_events(&progress_rx)
+                .iter()
+                .any(|event| matches!(event, HttpDownloadEvent::Verified))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_checksum_mismatch_deletes_file() {
+        let base_url = test_server::start(|_| StubResponse::ok(b"corrupted content")).await;
+        let dir = temp_dir("mismatch");
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            expected_sha1: Some(Sha1::digest(b"expected content").into()),
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::ChecksumMismatch { .. })
+        ));
+        assert!(!dir.join("game.dat").exists());
+        assert!(!dir.join("game.dat.part").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_retry_transient_failures() {
+        // Redirect middleware sends each request twice, first two attempts get four requests
+        let requests = AtomicU32::new(0);
+        let base_url = test_server::start(move |_| match requests.fetch_add(1, Ordering::SeqCst) {
+            0..4 => StubResponse::ok(b"").with_status(503),
+            _ => StubResponse::ok(b"content"),
+        })
+        .await;
+        let dir = temp_dir("retry");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            retry: RetryConfig {
+                max_retries: 3,
+                base_delay: Duration::from_millis(1),
+            },
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), b"content");
+        let attempts: Vec<u32> = collect_events(&progress_rx)
+            .into_iter()
+            .filter_map(|event| match event {
+                HttpDownloadEvent::Retrying { attempt, .. } => Some(attempt),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attempts, vec![1, 2]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_client_error_is_not_retried() {
+        let base_url = test_server::start(|_| StubResponse::ok(b"").with_status(404)).await;
+        let dir = temp_dir("no-retry");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::HttpStatus(404))));
+        assert!(
+            !collect_events(&progress_rx)
+                .iter()
+                .any(|event| matches!(event, HttpDownloadEvent::Retrying { .. }))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_retry_delay_doubles() {
+        let retry = RetryConfig {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+        };
+
+        assert_eq!(retry.delay(1), Duration::from_millis(100));
+        assert_eq!(retry.delay(3), Duration::from_millis(400));
+    }
+
+    #[async_std::test]
+    async fn test_reject_download_over_size_limit() {
+        let base_url = test_server::start(|_| StubResponse::ok(&[0u8; 1000])).await;
+        let dir = temp_dir("size-limit");
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            max_bytes: Some(100),
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::SizeLimitExceeded(100))));
+        assert!(!dir.join("game.dat").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_abort_download_over_size_limit_without_content_length() {
+        let base_url =
+            test_server::start(|_| StubResponse::ok(&[0u8; 100_000]).without_content_length())
+                .await;
+        let dir = temp_dir("size-limit-streamed");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            max_bytes: Some(10_000),
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(DownloadError::SizeLimitExceeded(10_000))
+        ));
+        assert!(matches!(
+            collect_events(&progress_rx)[0],
+            HttpDownloadEvent::Started { total_size: None }
+        ));
+        assert!(!dir.join("game.dat").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("game.zip"), Some("game.zip".to_string()));
+        assert_eq!(
+            sanitize_filename("../../evil.zip"),
+            Some("evil.zip".to_string())
+        );
+        assert_eq!(
+            sanitize_filename("..\\..\\evil.zip"),
+            Some("evil.zip".to_string())
+        );
+        assert_eq!(sanitize_filename(".."), None);
+        assert_eq!(sanitize_filename("dir/"), None);
+    }
+
+    #[async_std::test]
+    async fn test_header_filename_traversal_and_collision() {
+        let base_url = test_server::start(|_| {
+            StubResponse::ok(b"content").with_header(
+                "Content-Disposition",
+                "attachment; filename=\"../../evil.zip\"",
+            )
+        })
+        .await;
+        let dir = temp_dir("sanitize");
+        std::fs::write(dir.join("evil.zip"), b"existing").unwrap();
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+
+        let result = download_file(
+            &format!("{}/download?id=1", base_url),
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.file_path, dir.join("evil (1).zip"));
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), b"content");
+        assert_eq!(std::fs::read(dir.join("evil.zip")).unwrap(), b"existing");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_retry_keeps_header_filename() {
+        let content: Vec<u8> = (0..50_000).map(|i| i as u8).collect();
+        let response_content = content.clone();
+        let base_url = test_server::start(move |request| {
+            let response = match request.header("Range") {
+                Some("bytes=20000-") => StubResponse::ok(&response_content[20_000..])
+                    .with_status(206)
+                    .with_header("Content-Range", "bytes 20000-49999/50000"),
+                _ => StubResponse::ok(&response_content).stall_after(20_000),
+            };
+            response.with_header("Content-Disposition", "attachment; filename=\"game.zip\"")
+        })
+        .await;
+        let dir = temp_dir("retry-header-filename");
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            read_timeout: Some(Duration::from_millis(200)),
+            retry: RetryConfig {
+                max_retries: 1,
+                base_delay: Duration::from_millis(1),
+            },
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/download?id=1", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result.file_path, dir.join("game.zip"));
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert!(!dir.join("game (1).zip").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_request_headers_and_basic_auth() {
+        let base_url = test_server::start(|request| {
+            let authorized = request.header("Authorization") == Some("Basic dXNlcjpzZWNyZXQ=")
+                && request.header("User-Agent") == Some("efm-test");
+            if authorized {
+                StubResponse::ok(b"content")
+            } else {
+                StubResponse::ok(b"").with_status(401)
+            }
+        })
+        .await;
+        let dir = temp_dir("auth");
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let url = format!("{}/game.dat", base_url);
+
+        let unauthorized = download_file(
+            &url,
+            &dir,
+            &DownloadOptions::default(),
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await;
+        assert!(matches!(unauthorized, Err(DownloadError::HttpStatus(401))));
+
+        let options = DownloadOptions {
+            headers: vec![("User-Agent".to_string(), "efm-test".to_string())],
+            basic_auth: Some(("user".to_string(), "secret".to_string())),
+            ..Default::default()
+        };
+        let result = download_file(&url, &dir, &options, &progress_tx, &cancel_rx)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), b"content");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_debug_output_redacts_credentials() {
+        let options = DownloadOptions {
+            headers: vec![
+                ("Authorization".to_string(), "Bearer token".to_string()),
+                ("User-Agent".to_string(), "efm-test".to_string()),
+            ],
+            basic_auth: Some(("user".to_string(), "secret".to_string())),
+            ..Default::default()
+        };
+
+        let debug = format!("{:?}", options);
+
+        assert!(!debug.contains("Bearer token"));
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("efm-test"));
+    }
+
+    #[async_std::test]
+    async fn test_progress_respects_byte_threshold() {
+        let base_url = test_server::start(|_| StubResponse::ok(&[0u8; 50_000])).await;
+        let dir = temp_dir("progress");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            progress: ProgressConfig {
+                min_bytes: 10_000,
+                min_interval: Duration::from_secs(3600),
+            },
+            ..Default::default()
+        };
+
+        download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        let events = collect_events(&progress_rx);
+        assert!(matches!(
+            events.first(),
+            Some(HttpDownloadEvent::Started { .. })
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(HttpDownloadEvent::Completed { .. })
+        ));
+        let progress: Vec<u64> = events
+            .iter()
+            .filter_map(|event| match event {
+                HttpDownloadEvent::Progress { bytes_downloaded } => Some(*bytes_downloaded),
+                _ => None,
+            })
+            .collect();
+        assert!(!progress.is_empty());
+        assert!(progress[0] >= 10_000);
+        assert!(progress.windows(2).all(|pair| pair[1] - pair[0] >= 10_000));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_fall_back_to_next_mirror() {
+        // Port of a closed listener refuses connections
+        let dead_url = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/game.dat", listener.local_addr().unwrap())
+        };
+        let live_url = format!(
+            "{}/game.dat",
+            test_server::start(|_| StubResponse::ok(b"content")).await
+        );
+        let dir = temp_dir("mirrors");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            retry: RetryConfig {
+                max_retries: 0,
+                base_delay: Duration::ZERO,
+            },
+            ..Default::default()
+        };
+
+        let result = download_file_from_mirrors(
+            &[dead_url.clone(), live_url],
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), b"content");
+        let failed_mirrors: Vec<String> = collect_events(&progress_rx)
+            .into_iter()
+            .filter_map(|event| match event {
+                HttpDownloadEvent::MirrorFailed { url, .. } => Some(url),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(failed_mirrors, vec![dead_url]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_stalled_download_times_out() {
+        let base_url =
+            test_server::start(|_| StubResponse::ok(&[0u8; 50_000]).stall_after(20_000)).await;
+        let dir = temp_dir("timeout");
+        let (progress_tx, _progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            timeout: Some(Duration::from_millis(500)),
+            read_timeout: None,
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await;
+
+        assert!(matches!(result, Err(DownloadError::TimedOut)));
+        assert!(!dir.join("game.dat").exists());
+        assert!(!dir.join("game.dat.part").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[async_std::test]
+    async fn test_stalled_download_is_retried_and_resumed() {
+        let content: Vec<u8> = (0..50_000).map(|i| i as u8).collect();
+        let response_content = content.clone();
+        // Range is only served when the partial file is of the same version of the file
+        let base_url = test_server::start(move |request| {
+            let response = match (request.header("Range"), request.header("If-Range")) {
+                (Some("bytes=20000-"), Some("\"v1\"")) => {
+                    StubResponse::ok(&response_content[20_000..])
+                        .with_status(206)
+                        .with_header("Content-Range", "bytes 20000-49999/50000")
+                }
+                _ => StubResponse::ok(&response_content).stall_after(20_000),
+            };
+            response.with_header("ETag", "\"v1\"")
+        })
+        .await;
+        let dir = temp_dir("timeout-retry");
+        let (progress_tx, progress_rx) = async_std::channel::unbounded();
+        let (_cancel_tx, cancel_rx) = async_std::channel::unbounded();
+        let options = DownloadOptions {
+            read_timeout: Some(Duration::from_millis(200)),
+            retry: RetryConfig {
+                max_retries: 1,
+                base_delay: Duration::from_millis(1),
+            },
+            ..Default::default()
+        };
+
+        let result = download_file(
+            &format!("{}/game.dat", base_url),
+            &dir,
+            &options,
+            &progress_tx,
+            &cancel_rx,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(std::fs::read(&result.file_path).unwrap(), content);
+        assert!(
+            collect_events(&progress_rx)
+                .iter()
+                .any(|event| matches!(event, HttpDownloadEvent::Resumed { offset: 20_000, .. }))
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_next_timeout_is_limited_by_deadline() {
+        let options = DownloadOptions {
+            read_timeout: Some(Duration::from_secs(60)),
+            ..Default::default()
+        };
+
+        let timeout = next_timeout(&options, Some(Instant::now() + Duration::from_secs(5)));
+
+        assert!(timeout.unwrap() <= Duration::from_secs(5));
+        assert_eq!(next_timeout(&options, None), Some(Duration::from_secs(60)));
+    }
 }
@@ -0,0 +1,128 @@
+//! Minimal HTTP/1.1 server for testing downloads against canned responses.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_std::io::{BufReader, WriteExt};
+use async_std::net::{TcpListener, TcpStream};
+use async_std::prelude::*;
+
+pub struct StubRequest {
+    /// Header names are lowercased
+    pub headers: HashMap<String, String>,
+}
+
+impl StubRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(String::as_str)
+    }
+}
+
+pub struct StubResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Without `Content-Length` the body is read until the connection is closed
+    pub content_length: bool,
+    /// Number of body bytes to send before stalling without closing the connection
+    pub stall_after: Option<usize>,
+}
+
+impl StubResponse {
+    pub fn ok(body: &[u8]) -> Self {
+        Self {
+            status: 200,
+            headers: vec![],
+            body: body.to_vec(),
+            content_length: true,
+            stall_after: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn without_content_length(mut self) -> Self {
+        self.content_length = false;
+        self
+    }
+
+    pub fn stall_after(mut self, bytes: usize) -> Self {
+        self.stall_after = Some(bytes);
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Start a server on a random local port answering every request with the handler. Returns the
+/// base URL of the server.
+pub async fn start<F>(handler: F) -> String
+where
+    F: Fn(&StubRequest) -> StubResponse + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let handler = Arc::new(handler);
+    async_std::task::spawn(async move {
+        let mut incoming = listener.incoming();
+        while let Some(Ok(stream)) = incoming.next().await {
+            let handler = Arc::clone(&handler);
+            async_std::task::spawn(async move {
+                handle_connection(stream, handler.as_ref()).await.ok();
+            });
+        }
+    });
+    format!("http://{}", address)
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    handler: &(dyn Fn(&StubRequest) -> StubResponse + Send + Sync),
+) -> std::io::Result<()> {
+    let mut lines = BufReader::new(stream.clone()).lines();
+    // Request line isn't needed, all paths get the same response. Connections closed without
+    // a request are ignored.
+    if lines.next().await.transpose()?.is_none() {
+        return Ok(());
+    }
+    let mut headers = HashMap::new();
+    while let Some(line) = lines.next().await {
+        let line = line?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let response = handler(&StubRequest { headers });
+    let mut head = format!("HTTP/1.1 {} Stub\r\nConnection: close\r\n", response.status);
+    if response.content_length {
+        head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+    }
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let mut stream = stream;
+    stream.write_all(head.as_bytes()).await?;
+    match response.stall_after {
+        Some(bytes) => {
+            stream.write_all(&response.body[..bytes]).await?;
+            stream.flush().await?;
+            std::future::pending().await
+        }
+        None => {
+            stream.write_all(&response.body).await?;
+            stream.flush().await
+        }
+    }
+}
@@ -13,6 +13,7 @@ use relm4::{
 use service::{
     app_services::{AppServices, create_app_services},
     cloud_sync::service::SyncResult,
+    file_set_deletion::model::FileDeletionResult,
     file_system_ops::{FileSystemOps, StdFileSystemOps},
     view_models::{Settings, SoftwareTitleListModel},
 };
@@ -66,6 +67,7 @@ pub enum CommandMsg {
     InitializationDone(InitResult),
     ExportFinished(Result<(), service::error::Error>),
     SyncToCloudCompleted(Result<SyncResult, service::error::Error>),
+    TrashPurged(Result<Vec<FileDeletionResult>, service::error::Error>),
 }
 
 struct Flags {
@@ -287,6 +289,12 @@ impl Component for AppModel {
             CommandMsg::SyncToCloudCompleted(result) => {
                 self.process_sync_to_cloud_completed(result, root)
             }
+            CommandMsg::TrashPurged(Ok(results)) => {
+                tracing::info!(files = results.len(), "Purged expired file sets from trash");
+            }
+            CommandMsg::TrashPurged(Err(e)) => {
+                tracing::error!(error = %e, "Failed to purge expired file sets from trash");
+            }
         }
     }
 
@@ -500,7 +508,7 @@ impl AppModel {
                         .text("Cloud sync in progress")
                         .secondary_text(
                             "A cloud sync operation is currently running. \
-                             Closing now will cancel the sync after the current file finishes uploading.\n\n\
+                             Closing now will cancel the sync after the current part of the file finishes uploading.\n\n\
                              Do you want to cancel the sync and close the application?"
                         )
                         .build();
@@ -574,6 +582,11 @@ impl AppModel {
             );
         }
 
+        let file_set_deletion = app_services.file_set_deletion();
+        sender.oneshot_command(async move {
+            CommandMsg::TrashPurged(file_set_deletion.purge_expired_file_sets().await)
+        });
+
         let software_title_list_init = SoftwareTitleListInit { app_services };
 
         let software_titles_list = SoftwareTitlesList::builder()
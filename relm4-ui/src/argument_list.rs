@@ -1,4 +1,4 @@
-use core_types::ArgumentType;
+use core_types::{ArgumentType, parse_arguments};
 use relm4::{
     Component, ComponentParts, ComponentSender,
     gtk::{
@@ -131,15 +131,12 @@ impl Component for ArgumentList {
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
         match msg {
             ArgumentListMsg::AddArgument(argument_string) => {
-                let argument = ArgumentType::try_from(argument_string.as_str());
-                match argument {
-                    Ok(argument) => {
+                let arguments = parse_arguments(&argument_string);
+                if !arguments.is_empty() {
+                    for argument in arguments {
                         self.list_view_wrapper.append(ArgumentListItem { argument });
-                        self.emit_arguments_changed(&sender);
-                    }
-                    Err(e) => {
-                        eprintln!("Error parsing command line argument: {}", e);
                     }
+                    self.emit_arguments_changed(&sender);
                 }
             }
             ArgumentListMsg::MoveArgumentUp => {
@@ -8,6 +8,7 @@ use crate::{
     list_item::ListItem,
     utils::dialog_utils::show_error_dialog,
 };
+use flume::{Sender, unbounded};
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller,
     gtk::{
@@ -43,6 +44,7 @@ pub enum DocumentViewerMsg {
     UpdateViewer(DocumentViewerListModel),
 
     StartViewer,
+    StopViewer,
     StartEdit,
     ConfirmDelete,
     DeleteConfirmed,
@@ -85,6 +87,10 @@ pub struct DocumentViewer {
     file_set: Option<FileSetViewModel>,
     selected_file: Option<FileSetFileInfoViewModel>,
     selected_viewer: Option<DocumentViewerViewModel>,
+    // kills the running viewer
+    viewer_cancel_tx: Option<Sender<()>>,
+    // a new viewer isn't started until the running one has finished
+    viewer_running: bool,
 }
 
 #[relm4::component(pub)]
@@ -136,7 +142,16 @@ impl Component for DocumentViewer {
                     set_label: "Start",
                     connect_clicked => DocumentViewerMsg::StartViewer,
                     #[watch]
-                    set_sensitive: model.selected_viewer.is_some() && model.selected_file.is_some(),
+                    set_sensitive: !model.viewer_running
+                        && model.selected_viewer.is_some()
+                        && model.selected_file.is_some(),
+                },
+
+                gtk::Button {
+                    set_label: "Stop",
+                    connect_clicked => DocumentViewerMsg::StopViewer,
+                    #[watch]
+                    set_sensitive: model.viewer_cancel_tx.is_some(),
                 },
             }
         }
@@ -190,6 +205,8 @@ impl Component for DocumentViewer {
             selected_viewer: None,
             viewer_form,
             confirm_dialog_controller,
+            viewer_cancel_tx: None,
+            viewer_running: false,
         };
 
         let file_list_view = &model.file_list_view_wrapper.view;
@@ -226,14 +243,16 @@ impl Component for DocumentViewer {
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match msg {
             DocumentViewerMsg::StartViewer => {
-                if let (Some(viewer), Some(selected_file), Some(file_set)) =
-                    (&self.selected_viewer, &self.selected_file, &self.file_set)
+                if !self.viewer_running
+                    && let (Some(viewer), Some(selected_file), Some(file_set)) =
+                        (&self.selected_viewer, &self.selected_file, &self.file_set)
                 {
                     let executable = viewer.executable.clone();
                     // TODO: create a viewer view model that has processed arguments already to
                     // correct format
                     let arguments = Vec::new(); // TODO: viewer.arguments.clone();
                     let executable_runner_service = self.app_services.runner().clone();
+                    let (cancel_tx, cancel_rx) = unbounded::<()>();
 
                     let executable_runner_model = ExecutableRunnerModel {
                         executable,
@@ -242,7 +261,10 @@ impl Component for DocumentViewer {
                         file_set_id: file_set.id,
                         initial_file: Some(selected_file.file_name.clone()),
                         skip_cleanup: !viewer.cleanup_temp_files, // Invert: cleanup=true means skip=false
+                        cancel_rx: Some(cancel_rx),
                     };
+                    self.viewer_cancel_tx = Some(cancel_tx);
+                    self.viewer_running = true;
 
                     sender.oneshot_command(async move {
                         let res = executable_runner_service
@@ -253,6 +275,13 @@ impl Component for DocumentViewer {
                     });
                 }
             }
+            DocumentViewerMsg::StopViewer => {
+                if let Some(cancel_tx) = self.viewer_cancel_tx.take()
+                    && let Err(e) = cancel_tx.try_send(())
+                {
+                    tracing::error!(error = ?e, "Failed to send cancel signal");
+                }
+            }
             DocumentViewerMsg::FileSelected => {
                 self.selected_file = self.get_selected_file_info();
             }
@@ -363,13 +392,21 @@ impl Component for DocumentViewer {
             }
             DocumentViewerCommandMsg::FinishedRunningViewer(Ok(())) => {
                 tracing::info!("Viewer executed successfully");
+                self.viewer_running = false;
+                self.viewer_cancel_tx = None;
                 root.close();
             }
             DocumentViewerCommandMsg::FinishedRunningViewer(Err(error)) => {
-                show_error_dialog(
-                    format!("An error occurred while running the viewer: {}", error),
-                    root,
-                );
+                self.viewer_running = false;
+                // The cancel sender has already been taken if the viewer was stopped
+                if self.viewer_cancel_tx.take().is_some() {
+                    show_error_dialog(
+                        format!("An error occurred while running the viewer: {}", error),
+                        root,
+                    );
+                } else {
+                    tracing::info!("Viewer stopped");
+                }
             }
             DocumentViewerCommandMsg::Deleted(Ok(_)) => {
                 tracing::info!("Viewer deleted successfully");
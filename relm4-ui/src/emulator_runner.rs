@@ -6,6 +6,7 @@ use crate::{
     utils::dialog_utils::show_error_dialog,
 };
 use domain::models::System;
+use flume::{Sender, unbounded};
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller,
     gtk::{
@@ -61,6 +62,7 @@ pub enum EmulatorRunnerMsg {
     Hide,
     Ignore,
     StartEmulator,
+    StopEmulator,
 }
 
 #[derive(Debug)]
@@ -97,6 +99,10 @@ pub struct EmulatorRunnerModel {
     selected_file: Option<FileSetFileInfoViewModel>,
     selected_system: Option<System>,
     selected_emulator: Option<EmulatorViewModel>,
+    // kills the running emulator
+    emulator_cancel_tx: Option<Sender<()>>,
+    // a new emulator isn't started until the running one has finished
+    emulator_running: bool,
 }
 
 #[relm4::component(pub)]
@@ -150,6 +156,13 @@ impl Component for EmulatorRunnerModel {
                     #[watch]
                     set_sensitive: model.can_launch_emulator(),
                 },
+
+                gtk::Button {
+                    set_label: "Stop Emulator",
+                    connect_clicked => EmulatorRunnerMsg::StopEmulator,
+                    #[watch]
+                    set_sensitive: model.emulator_cancel_tx.is_some(),
+                },
             }
         }
     }
@@ -206,6 +219,8 @@ impl Component for EmulatorRunnerModel {
             emulator_form,
             confirm_dialog_controller,
             selected_system: None,
+            emulator_cancel_tx: None,
+            emulator_running: false,
         };
 
         let file_list_view = &model.file_list_view_wrapper.view;
@@ -329,6 +344,13 @@ impl Component for EmulatorRunnerModel {
             EmulatorRunnerMsg::StartEmulator => {
                 self.start_emulator(&sender);
             }
+            EmulatorRunnerMsg::StopEmulator => {
+                if let Some(cancel_tx) = self.emulator_cancel_tx.take()
+                    && let Err(e) = cancel_tx.try_send(())
+                {
+                    tracing::error!(error = ?e, "Failed to send cancel signal");
+                }
+            }
             _ => {}
         }
     }
@@ -363,10 +385,18 @@ impl Component for EmulatorRunnerModel {
             }
             EmulatorRunnerCommandMsg::FinishedRunningEmulator(Ok(())) => {
                 tracing::info!("Emulator executed successfully");
+                self.emulator_running = false;
+                self.emulator_cancel_tx = None;
                 sender.input(EmulatorRunnerMsg::Hide);
             }
             EmulatorRunnerCommandMsg::FinishedRunningEmulator(Err(error)) => {
-                show_error_dialog(format!("Error running emulator: {:?}", error), root);
+                self.emulator_running = false;
+                // The cancel sender has already been taken if the emulator was stopped
+                if self.emulator_cancel_tx.take().is_some() {
+                    show_error_dialog(format!("Error running emulator: {:?}", error), root);
+                } else {
+                    tracing::info!("Emulator stopped");
+                }
             }
             EmulatorRunnerCommandMsg::EmulatorDeleted(Ok(deleted_id)) => {
                 tracing::info!(id = deleted_id, "Emulator deleted successfully");
@@ -392,7 +422,10 @@ impl EmulatorRunnerModel {
             file_set = ?self.file_set,
             "Checking if emulator can be launched"
         );
-        self.selected_emulator.is_some() && self.selected_file.is_some() && self.file_set.is_some()
+        !self.emulator_running
+            && self.selected_emulator.is_some()
+            && self.selected_file.is_some()
+            && self.file_set.is_some()
     }
     pub fn handle_file_selection(&mut self, index: u32) {
         let file_list_item = self.file_list_view_wrapper.get(index);
@@ -424,7 +457,10 @@ impl EmulatorRunnerModel {
         }
     }
 
-    pub fn start_emulator(&self, sender: &ComponentSender<Self>) {
+    pub fn start_emulator(&mut self, sender: &ComponentSender<Self>) {
+        if self.emulator_running {
+            return;
+        }
         if let (Some(emulator), Some(selected_file), Some(file_set)) =
             (&self.selected_emulator, &self.selected_file, &self.file_set)
         {
@@ -438,6 +474,7 @@ impl EmulatorRunnerModel {
             };
 
             let executable_runner_service = self.app_services.runner().clone();
+            let (cancel_tx, cancel_rx) = unbounded::<()>();
 
             let executable_runner_model = ExecutableRunnerModel {
                 executable,
@@ -448,7 +485,10 @@ impl EmulatorRunnerModel {
                 // Emulators block until closed, cleanup after
                 // TODO: make this configurable
                 skip_cleanup: false,
+                cancel_rx: Some(cancel_rx),
             };
+            self.emulator_cancel_tx = Some(cancel_tx);
+            self.emulator_running = true;
 
             sender.oneshot_command(async move {
                 let res = executable_runner_service
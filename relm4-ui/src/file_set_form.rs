@@ -182,6 +182,8 @@ pub struct FileSetFormModel {
     selected_files_in_picked_files: Vec<Sha1Checksum>,
     /// This contains newly picked files for import
     picked_files: Vec<FileImportSource>,
+    /// Files found inside picked disc images
+    disc_image_listing: Vec<ReadFile>,
     // Download progress tracking
     download_in_progress: bool,
     download_total_size: Option<u64>,
@@ -335,6 +337,25 @@ impl Component for FileSetFormModel {
                     files_list_box -> gtk::ListBox {}
                 },
 
+                gtk::Expander {
+                    set_label: Some("Disc image contents"),
+                    #[watch]
+                    set_visible: !model.disc_image_listing.is_empty(),
+
+                    gtk::Label {
+                        set_halign: gtk::Align::Start,
+                        set_selectable: true,
+                        set_margin_all: 5,
+                        #[watch]
+                        set_label: &model
+                            .disc_image_listing
+                            .iter()
+                            .map(|f| format!("{} ({})", f.file_name, format_bytes(f.file_size)))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    },
+                },
+
                 gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
                     set_spacing: 5,
@@ -445,6 +466,7 @@ impl Component for FileSetFormModel {
             selected_file_type: None,
             selected_files_in_picked_files: Vec::new(),
             picked_files: Vec::new(),
+            disc_image_listing: Vec::new(),
             download_in_progress: false,
             download_total_size: None,
             download_bytes: 0,
@@ -639,6 +661,7 @@ impl Component for FileSetFormModel {
                 self.file_set_file_name.clear();
                 self.source.clear();
                 self.files.guard().clear();
+                self.disc_image_listing.clear();
                 self.dropdown
                     .emit(DropDownMsg::SetSelected(selected_file_type));
                 root.show();
@@ -673,9 +696,22 @@ impl Component for FileSetFormModel {
                     self.download_bytes = 0;
                     tracing::info!(total_size = total_size, "Download started");
                 }
+                HttpDownloadEvent::Resumed { url, offset } => {
+                    self.download_bytes = offset;
+                    tracing::info!(url = url, offset = offset, "Download resumed");
+                }
                 HttpDownloadEvent::Progress { bytes_downloaded } => {
                     self.download_bytes = bytes_downloaded;
                 }
+                HttpDownloadEvent::Retrying { url, attempt } => {
+                    tracing::warn!(url = url, attempt = attempt, "Retrying download");
+                }
+                HttpDownloadEvent::MirrorFailed { url, error } => {
+                    tracing::warn!(url = url, error = error, "Download mirror failed");
+                }
+                HttpDownloadEvent::Verified => {
+                    tracing::info!("Download checksum verified");
+                }
                 HttpDownloadEvent::Completed { file_path } => {
                     self.download_in_progress = false;
                     self.download_bytes = 0;
@@ -717,6 +753,7 @@ impl Component for FileSetFormModel {
                         self.selected_item_type,
                     ));
                 self.files.guard().clear();
+                self.disc_image_listing.clear();
                 for file in file_set_view_model.files.iter() {
                     self.files.guard().push_back(ReadFile {
                         file_name: file.file_name.clone(),
@@ -778,6 +815,7 @@ impl Component for FileSetFormModel {
                 );
                 let import_model = prepare_result.import_model;
                 let import_metadata = prepare_result.import_metadata;
+                self.disc_image_listing.extend(prepare_result.disc_image_listing);
                 for file in import_model.content.values() {
                     self.files.guard().push_back(ReadFile {
                         file_name: file.file_name.clone(),
@@ -1,12 +1,13 @@
 use std::sync::Arc;
 
-use core_types::FileType;
+use core_types::{FileType, events::DeletionEvent};
+use flume::unbounded;
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt,
     gtk::{
         self,
         glib::{self, clone},
-        prelude::{BoxExt, ButtonExt, GtkWindowExt, OrientableExt, WidgetExt},
+        prelude::{BoxExt, ButtonExt, DialogExt, GtkWindowExt, OrientableExt, WidgetExt},
     },
     typed_view::list::TypedListView,
 };
@@ -14,6 +15,7 @@ use service::{
     error::Error as ServiceError, file_set_deletion::model::FileDeletionResult,
     view_models::FileSetListModel,
 };
+use tokio::task;
 use ui_components::{DropDownOutputMsg, FileTypeDropDown, FileTypeSelectedMsg};
 
 use crate::{
@@ -22,7 +24,7 @@ use crate::{
     },
     file_set_form::{FileSetFormInit, FileSetFormModel, FileSetFormMsg, FileSetFormOutputMsg},
     list_item::FileSetListItem,
-    utils::dialog_utils::{show_error_dialog, show_info_dialog},
+    utils::dialog_utils::show_error_dialog,
 };
 
 #[derive(Debug)]
@@ -41,6 +43,8 @@ pub enum FileSetSelectorMsg {
     Hide,
     Ignore,
     ShowError(String),
+    UndoDelete(i64),
+    ProcessDeletionEvent(DeletionEvent),
 }
 
 #[derive(Debug)]
@@ -55,6 +59,7 @@ pub enum CommandMsg {
         result: Result<Vec<FileDeletionResult>, ServiceError>,
         id: i64,
     },
+    FileSetRestored(Result<(), ServiceError>),
 }
 
 pub struct FileSetSelectorInit {
@@ -73,6 +78,7 @@ pub struct FileSetSelector {
     selected_file_set_ids: Vec<i64>,
     dropdown: Controller<FileTypeDropDown>,
     file_set_details_view: Controller<FileSetDetailsView>,
+    deletion_status: Option<String>,
 }
 
 #[relm4::component(pub)]
@@ -145,7 +151,13 @@ impl Component for FileSetSelector {
                         set_sensitive: model.selected_file_set.is_some() && model.selected_file_set.as_ref().is_some_and(|fs| fs.can_delete),
                     },
                     gtk::Label {
-                        set_label: "When deleting a file set, also that actual files will be deleted\nunless they are linked to other file sets\n(in that case only those files that are linked won't be deleted).",
+                        #[watch]
+                        set_visible: model.deletion_status.is_some(),
+                        #[watch]
+                        set_label: model.deletion_status.as_deref().unwrap_or_default(),
+                    },
+                    gtk::Label {
+                        set_label: "When deleting a file set, it's moved to trash together with the actual files\nunless they are linked to other file sets\n(in that case only those files that are linked won't be moved).\nTrash is emptied after the retention period has passed.",
                     },
                 },
 
@@ -211,6 +223,7 @@ impl Component for FileSetSelector {
             selected_file_set_ids: Vec::new(),
             dropdown,
             file_set_details_view,
+            deletion_status: None,
         };
         let file_types_dropdown = model.dropdown.widget();
         let file_set_list_view = &model.list_view_wrapper.view;
@@ -306,12 +319,25 @@ impl Component for FileSetSelector {
                     tracing::info!(id = selected_file_set.id, "Deleting file set");
                     let file_set_deletion_service = self.app_services.file_set_deletion().clone();
                     let file_set_id = selected_file_set.id;
+                    self.deletion_status = Some("Deleting file set…".to_string());
+
+                    let (progress_tx, progress_rx) = unbounded::<DeletionEvent>();
+                    let ui_sender = sender.clone();
+
+                    // Spawn task to forward progress messages to UI
+                    task::spawn(async move {
+                        while let Ok(event) = progress_rx.recv_async().await {
+                            ui_sender.input(FileSetSelectorMsg::ProcessDeletionEvent(event));
+                        }
+                    });
 
                     sender.oneshot_command(clone!(
                         #[strong]
                         file_set_deletion_service,
                         async move {
-                            let res = file_set_deletion_service.delete_file_set(file_set_id).await;
+                            let res = file_set_deletion_service
+                                .soft_delete_file_set(file_set_id, Some(progress_tx))
+                                .await;
                             CommandMsg::FilesSetDeletionFinished {
                                 result: res,
                                 id: file_set_id,
@@ -320,6 +346,25 @@ impl Component for FileSetSelector {
                     ));
                 }
             }
+            FileSetSelectorMsg::UndoDelete(file_set_id) => {
+                tracing::info!(id = file_set_id, "Restoring file set from trash");
+                let file_set_deletion_service = self.app_services.file_set_deletion().clone();
+                sender.oneshot_command(async move {
+                    let res = file_set_deletion_service
+                        .restore_file_set(file_set_id)
+                        .await;
+                    CommandMsg::FileSetRestored(res)
+                });
+            }
+            FileSetSelectorMsg::ProcessDeletionEvent(event) => {
+                tracing::debug!(event = ?event, "Received deletion event");
+                if let DeletionEvent::FileProgress { total_files, .. } = event {
+                    self.deletion_status = Some(format!(
+                        "Deleting file set… (removing {} files)",
+                        total_files
+                    ));
+                }
+            }
             FileSetSelectorMsg::ShowError(msg) => show_error_dialog(msg, root),
             FileSetSelectorMsg::Ignore => {}
         }
@@ -328,7 +373,7 @@ impl Component for FileSetSelector {
     fn update_cmd(
         &mut self,
         message: Self::CommandOutput,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
         root: &Self::Root,
     ) {
         match message {
@@ -351,10 +396,22 @@ impl Component for FileSetSelector {
             CommandMsg::FilesFetched(Err(e)) => {
                 show_error_dialog(format!("Error fetching file sets: {}", e), root);
             }
-            CommandMsg::FilesSetDeletionFinished { result, id } => match result {
-                Err(e) => show_error_dialog(format!("Error deleting file set: {}", e), root),
-                Ok(deletion_results) => self.handle_deletion_result(deletion_results, id, root),
-            },
+            CommandMsg::FilesSetDeletionFinished { result, id } => {
+                self.deletion_status = None;
+                match result {
+                    Err(e) => show_error_dialog(format!("Error deleting file set: {}", e), root),
+                    Ok(deletion_results) => {
+                        self.handle_deletion_result(deletion_results, id, &sender, root)
+                    }
+                }
+            }
+            CommandMsg::FileSetRestored(Ok(())) => {
+                tracing::info!("File set restored from trash");
+                sender.input(FileSetSelectorMsg::FetchFiles);
+            }
+            CommandMsg::FileSetRestored(Err(e)) => {
+                show_error_dialog(format!("Error restoring file set: {}", e), root);
+            }
         }
     }
 }
@@ -418,57 +475,60 @@ impl FileSetSelector {
         &mut self,
         deletion_results: Vec<FileDeletionResult>,
         id: i64,
+        sender: &ComponentSender<Self>,
         root: &gtk::Window,
     ) {
-        let successful_deletions = deletion_results
+        let failed_moves = deletion_results
             .iter()
-            .filter(|r| {
-                r.file_deletion_success.is_some_and(|s| s)
-                    && r.db_deletion_success.is_some_and(|s| s)
-            })
-            .collect::<Vec<_>>();
-
-        let failed_deletions = deletion_results
-            .iter()
-            .filter(|r| {
-                r.file_deletion_success.is_some_and(|s| !s)
-                    || r.db_deletion_success.is_some_and(|s| !s)
-            })
+            .filter(|r| r.moved_to_trash.is_some_and(|s| !s))
             .collect::<Vec<_>>();
 
         tracing::info!(
             id = id,
-            succesful = successful_deletions.len(),
-            failed = failed_deletions.len(),
-            "File set deletion complete"
+            moved = deletion_results
+                .iter()
+                .filter(|r| r.moved_to_trash.is_some_and(|s| s))
+                .count(),
+            failed = failed_moves.len(),
+            "File set moved to trash"
         );
+        self.remove_from_list(id);
 
-        // TODO: create better summary dialog
-        if !failed_deletions.is_empty() {
-            let mut message = String::from("Some files failed to delete:\n");
-            for result in &failed_deletions {
-                message.push_str(&format!(
-                    "- File ID {}: (Deleted from FS: {}, Deleted from DB: {})\n",
-                    result.file_info.id,
-                    result.file_deletion_success.is_some_and(|s| s),
-                    result.db_deletion_success.is_some_and(|s| s)
-                ));
+        if !failed_moves.is_empty() {
+            let mut message =
+                String::from("File set was moved to trash but some files failed to move:\n");
+            for result in &failed_moves {
+                message.push_str(&format!("- File ID {}\n", result.file_info.id));
                 for error in result.error_messages.iter() {
                     message.push_str(&format!("  Error: {}\n", error));
                 }
             }
             show_error_dialog(message, root);
-        } else if !successful_deletions.is_empty() {
-            // TODO: list which files were deleted?
-            // TODO: show total amount of files in file set?
-            show_info_dialog("File set deleted successfully.".to_string(), root);
-            self.remove_from_list(id);
-        } else {
-            show_info_dialog(
-                "File set was deleted but no files included in file set were deleted.\nFiles may be linked to other file sets.".to_string(),
-                root,
-            );
-            self.remove_from_list(id);
+            return;
         }
+
+        let retention_days = self.app_services.app_settings().trash_retention_days();
+        let dialog = gtk::MessageDialog::new(
+            Some(root),
+            gtk::DialogFlags::MODAL,
+            gtk::MessageType::Info,
+            gtk::ButtonsType::Ok,
+            &format!(
+                "File set moved to trash.\nIt will be permanently deleted after {} days.",
+                retention_days
+            ),
+        );
+        dialog.add_button("Undo", gtk::ResponseType::Reject);
+        dialog.connect_response(clone!(
+            #[strong]
+            sender,
+            move |dialog, response| {
+                dialog.close();
+                if response == gtk::ResponseType::Reject {
+                    sender.input(FileSetSelectorMsg::UndoDelete(id));
+                }
+            }
+        ));
+        dialog.show();
     }
 }
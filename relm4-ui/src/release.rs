@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use core_types::{DOCUMENT_FILE_TYPES, EMULATOR_FILE_TYPES, IMAGE_FILE_TYPES};
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller,
     gtk::{
@@ -439,7 +438,7 @@ impl ReleaseModel {
         let image_file_sets = release
             .file_sets
             .iter()
-            .filter(|fs| IMAGE_FILE_TYPES.contains(&fs.file_type))
+            .filter(|fs| fs.file_type.is_image())
             .cloned()
             .collect::<Vec<_>>();
 
@@ -453,7 +452,7 @@ impl ReleaseModel {
         let emulator_file_sets = release
             .file_sets
             .iter()
-            .filter(|fs| EMULATOR_FILE_TYPES.contains(&fs.file_type))
+            .filter(|fs| fs.file_type.is_emulator_file())
             .cloned()
             .collect::<Vec<_>>();
 
@@ -487,7 +486,7 @@ impl ReleaseModel {
         let image_file_sets = release
             .file_sets
             .iter()
-            .filter(|fs| IMAGE_FILE_TYPES.contains(&fs.file_type))
+            .filter(|fs| fs.file_type.is_image())
             .cloned()
             .collect::<Vec<_>>();
 
@@ -521,7 +520,7 @@ impl ReleaseModel {
         let document_file_sets = release
             .file_sets
             .iter()
-            .filter(|fs| DOCUMENT_FILE_TYPES.contains(&fs.file_type))
+            .filter(|fs| fs.file_type.is_document())
             .cloned()
             .collect::<Vec<_>>();
 
@@ -1,5 +1,6 @@
 use std::{path::PathBuf, sync::Arc};
 
+use cloud_storage::BucketAddressing;
 use relm4::{
     Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt,
     gtk::{
@@ -30,6 +31,7 @@ pub struct SettingsForm {
     pub s3_endpoint: String,
     pub s3_region: String,
     pub s3_sync_enabled: bool,
+    pub s3_path_style: bool,
     pub s3_access_key_id: String,
     pub s3_secret_access_key: String,
     pub libretro_core_dir: Option<PathBuf>,
@@ -74,6 +76,7 @@ pub enum SettingsFormMsg {
     Hide,
     ClearCredentials,
     S3FileSyncToggled,
+    S3PathStyleToggled,
     S3BucketNameChanged(String),
     S3EndpointChanged(String),
     S3RegionChanged(String),
@@ -314,6 +317,16 @@ impl Component for SettingsForm {
                             },
                         },
 
+                        gtk::CheckButton {
+                            set_label: Some("Use path-style bucket addressing (e.g. MinIO)"),
+                            #[watch]
+                            #[block_signal(path_style_toggled)]
+                            set_active: model.s3_path_style,
+                            connect_toggled[sender] => move |_| {
+                                sender.input(SettingsFormMsg::S3PathStyleToggled);
+                            } @path_style_toggled,
+                        },
+
                         gtk::Box {
                             set_orientation: gtk::Orientation::Horizontal,
                             set_spacing: 5,
@@ -397,6 +410,7 @@ impl Component for SettingsForm {
             s3_access_key_id: String::new(),
             s3_secret_access_key: String::new(),
             s3_sync_enabled: settings.s3_sync_enabled,
+            s3_path_style: s3_settings.addressing == BucketAddressing::PathStyle,
             credentials_stored: false,
             stored_access_key_preview: None,
             collection_root_dir: Some(settings.collection_root_dir.clone()),
@@ -442,6 +456,9 @@ impl Component for SettingsForm {
             SettingsFormMsg::S3FileSyncToggled => {
                 self.s3_sync_enabled = !self.s3_sync_enabled;
             }
+            SettingsFormMsg::S3PathStyleToggled => {
+                self.s3_path_style = !self.s3_path_style;
+            }
             SettingsFormMsg::S3BucketNameChanged(name) => {
                 self.s3_bucket_name = name;
             }
@@ -489,6 +506,7 @@ impl Component for SettingsForm {
                     endpoint: self.s3_endpoint.clone(),
                     region: self.s3_region.clone(),
                     sync_enabled: self.s3_sync_enabled,
+                    path_style: self.s3_path_style,
                     access_key_id: self.s3_access_key_id.clone(),
                     secret_access_key: self.s3_secret_access_key.clone(),
                     collection_root_dir: self.collection_root_dir.clone(),
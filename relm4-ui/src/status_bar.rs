@@ -227,6 +227,12 @@ impl StatusBarModel {
                     status: MessageStatus::Info,
                 });
             }
+            SyncEvent::PartRetrying { key, part, attempt } => {
+                self.message_list_view_wrapper.append(MessageListItem {
+                    message: format!("Retrying part {part} of file: {key} (attempt {attempt})"),
+                    status: MessageStatus::Warning,
+                });
+            }
             SyncEvent::PartUploadFailed { key, error } => {
                 self.message_list_view_wrapper.append(MessageListItem {
                     message: format!("Failed to upload part of file: {key}. Error: {error}"),
@@ -1,5 +1,9 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use core_types::{CloudSyncStatus, FileSyncStatus, events::SyncEvent};
+use database::models::CloudSyncableFileInfo;
 use flume::Sender;
+use futures::StreamExt;
 
 use crate::{
     cloud_sync::context::{FileSyncResult, SyncContext},
@@ -20,6 +24,8 @@ async fn send_progress_event(event: SyncEvent, progress_tx: &Sender<SyncEvent>)
 /// On success, sets `cloud_sync_status = Synced` and writes an `UploadCompleted` log entry.
 /// On failure, leaves `cloud_sync_status = NotSynced` (auto-retried next sync) and writes
 /// an `UploadFailed` log entry for diagnosis.
+/// At most `Settings::max_concurrent_uploads` files are uploaded at the same time, in parts of
+/// `Settings::upload_part_size` bytes.
 pub struct UploadPendingFilesStep;
 
 #[async_trait::async_trait]
@@ -36,12 +42,23 @@ impl PipelineStep<SyncContext, Error> for UploadPendingFilesStep {
         tracing::debug!("Uploading pending files to cloud storage");
         let mut file_count = 0;
         let mut session_skip: i64 = 0;
+        let max_concurrent_uploads = context.settings.max_concurrent_uploads();
+        let batch_size = max_concurrent_uploads.max(10) as i64;
+        let multipart_config = match cloud_storage::MultipartConfig::with_chunk_size(
+            context.settings.upload_part_size(),
+        ) {
+            Ok(multipart_config) => multipart_config,
+            Err(e) => {
+                tracing::error!(error = %e, "Invalid upload part size");
+                return StepAction::Abort(e.into());
+            }
+        };
 
         loop {
             let pending_files_result = context
                 .repository_manager
                 .get_file_info_repository()
-                .get_files_pending_upload(10, session_skip)
+                .get_files_pending_upload(batch_size, session_skip)
                 .await;
 
             match pending_files_result {
@@ -58,183 +75,226 @@ impl PipelineStep<SyncContext, Error> for UploadPendingFilesStep {
                         break;
                     }
 
-                    for file in pending_files {
-                        if context.cancel_rx.try_recv().is_ok() {
-                            tracing::info!("Cloud sync cancelled by user");
-                            return StepAction::Abort(Error::OperationCancelled);
-                        }
-
-                        let cloud_key =
-                            cloud_storage::cloud_key(file.file_type, &file.archive_file_name);
-
-                        let local_path = context
-                            .settings
-                            .get_file_path(&file.file_type, &file.archive_file_name);
+                    let first_file_number = file_count + 1;
+                    file_count += pending_files.len() as i64;
+                    let cancelled = AtomicBool::new(false);
+                    // Uploads are started in file number order. Cancellation stops starting new
+                    // uploads, uploads already in flight are finished.
+                    let upload_results: Vec<Option<(String, FileSyncResult)>> = {
+                        let context = &*context;
+                        futures::stream::iter(pending_files.into_iter().zip(first_file_number..))
+                            .map(|(file, file_number)| {
+                                upload_pending_file(
+                                    context,
+                                    file,
+                                    file_number,
+                                    &multipart_config,
+                                    &cancelled,
+                                )
+                            })
+                            .buffer_unordered(max_concurrent_uploads)
+                            .collect()
+                            .await
+                    };
 
-                        tracing::debug!(
-                            file_info_id = file.id,
-                            cloud_key = %cloud_key,
-                            local_path = %local_path.display(),
-                            "Uploading file"
-                        );
+                    for (cloud_key, file_sync_result) in upload_results.into_iter().flatten() {
+                        if !file_sync_result.cloud_operation_success {
+                            // Failed files stay NotSynced and re-appear at offset 0 on the
+                            // next fetch, so advance the session offset past them.
+                            session_skip += 1;
+                        }
+                        context.upload_results.insert(cloud_key, file_sync_result);
+                    }
+                    if cancelled.load(Ordering::SeqCst) {
+                        tracing::info!("Cloud sync cancelled by user");
+                        return StepAction::Abort(Error::OperationCancelled);
+                    }
+                }
+            }
+        }
+        tracing::debug!("Pending file uploads completed");
+        StepAction::Continue
+    }
+}
 
-                        file_count += 1;
+/// Uploads a single pending file and updates its sync status. Returns `None` if the sync was
+/// cancelled before or during the upload, in which case `cancelled` is set so that no further
+/// uploads are started.
+async fn upload_pending_file(
+    context: &SyncContext,
+    file: CloudSyncableFileInfo,
+    file_number: i64,
+    multipart_config: &cloud_storage::MultipartConfig,
+    cancelled: &AtomicBool,
+) -> Option<(String, FileSyncResult)> {
+    if cancelled.load(Ordering::SeqCst) || context.cancel_rx.try_recv().is_ok() {
+        cancelled.store(true, Ordering::SeqCst);
+        return None;
+    }
 
-                        send_progress_event(
-                            SyncEvent::FileUploadStarted {
-                                key: cloud_key.clone(),
-                                file_number: file_count,
-                                total_files: context.files_prepared_for_upload,
-                            },
-                            &context.progress_tx,
-                        )
-                        .await;
+    let cloud_key = cloud_storage::cloud_key(file.file_type, &file.archive_file_name);
 
-                        let mut file_sync_result = FileSyncResult {
-                            file_info_id: file.id,
-                            cloud_key: cloud_key.clone(),
-                            cloud_operation_success: false,
-                            cloud_error: None,
-                            db_update_success: false,
-                            db_error: None,
-                        };
+    let local_path = context
+        .settings
+        .get_file_path(&file.file_type, &file.archive_file_name);
 
-                        let upload_res = context
-                            .cloud_ops
-                            .as_ref()
-                            .expect("cloud_ops guaranteed by should_execute")
-                            .upload_file(
-                                local_path.as_path(),
-                                &cloud_key,
-                                Some(&context.progress_tx),
-                            )
-                            .await;
+    tracing::debug!(
+        file_info_id = file.id,
+        cloud_key = %cloud_key,
+        local_path = %local_path.display(),
+        "Uploading file"
+    );
 
-                        match upload_res {
-                            Ok(()) => {
-                                tracing::info!(
-                                    file_info_id = file.id,
-                                    cloud_key = %cloud_key,
-                                    "Upload succeeded"
-                                );
-                                file_sync_result.cloud_operation_success = true;
-
-                                // Update cloud_sync_status to Synced
-                                let status_res = context
-                                    .repository_manager
-                                    .get_file_info_repository()
-                                    .update_cloud_sync_status(file.id, CloudSyncStatus::Synced)
-                                    .await;
+    send_progress_event(
+        SyncEvent::FileUploadStarted {
+            key: cloud_key.clone(),
+            file_number,
+            total_files: context.files_prepared_for_upload,
+        },
+        &context.progress_tx,
+    )
+    .await;
+
+    let mut file_sync_result = FileSyncResult {
+        file_info_id: file.id,
+        cloud_key: cloud_key.clone(),
+        cloud_operation_success: false,
+        cloud_error: None,
+        db_update_success: false,
+        db_error: None,
+    };
 
-                                // Record audit log entry
-                                let log_res = context
-                                    .repository_manager
-                                    .get_file_sync_log_repository()
-                                    .add_log_entry(
-                                        file.id,
-                                        FileSyncStatus::UploadCompleted,
-                                        "",
-                                        &cloud_key,
-                                    )
-                                    .await;
+    let upload_res = context
+        .cloud_ops
+        .as_ref()
+        .expect("cloud_ops guaranteed by should_execute")
+        .upload_file(
+            local_path.as_path(),
+            &cloud_key,
+            multipart_config,
+            Some(&context.progress_tx),
+            Some(&context.cancel_rx),
+        )
+        .await;
+
+    match upload_res {
+        Err(cloud_storage::CloudStorageError::Cancelled) => {
+            // File stays NotSynced and is uploaded again on next sync.
+            tracing::info!(
+                cloud_key = %cloud_key,
+                "Cloud sync cancelled by user during upload"
+            );
+            cancelled.store(true, Ordering::SeqCst);
+            return None;
+        }
+        Ok(()) => {
+            tracing::info!(
+                file_info_id = file.id,
+                cloud_key = %cloud_key,
+                "Upload succeeded"
+            );
+            file_sync_result.cloud_operation_success = true;
 
-                                match (status_res, log_res) {
-                                    (Ok(()), Ok(_)) => {
-                                        file_sync_result.db_update_success = true;
-                                    }
-                                    (Err(e), _) => {
-                                        tracing::error!(
-                                            file_info_id = file.id,
-                                            error = %e,
-                                            "Error updating cloud_sync_status after upload"
-                                        );
-                                        file_sync_result.db_update_success = false;
-                                        file_sync_result.db_error = Some(format!("{}", e));
-                                    }
-                                    (_, Err(e)) => {
-                                        tracing::error!(
-                                            file_info_id = file.id,
-                                            error = %e,
-                                            "Error writing upload log entry"
-                                        );
-                                        file_sync_result.db_update_success = false;
-                                        file_sync_result.db_error = Some(format!("{}", e));
-                                    }
-                                }
+            // Update cloud_sync_status to Synced
+            let status_res = context
+                .repository_manager
+                .get_file_info_repository()
+                .update_cloud_sync_status(file.id, CloudSyncStatus::Synced)
+                .await;
 
-                                send_progress_event(
-                                    SyncEvent::FileUploadCompleted {
-                                        key: cloud_key.clone(),
-                                        file_number: file_count,
-                                        total_files: context.files_prepared_for_upload,
-                                    },
-                                    &context.progress_tx,
-                                )
-                                .await;
-                            }
-                            Err(e) => {
-                                tracing::error!(
-                                    file_info_id = file.id,
-                                    cloud_key = cloud_key,
-                                    local_path = %local_path.display(),
-                                    error = %e,
-                                    "Upload failed"
-                                );
-                                file_sync_result.cloud_operation_success = false;
-                                file_sync_result.cloud_error = Some(format!("{}", e));
+            // Record audit log entry
+            let log_res = context
+                .repository_manager
+                .get_file_sync_log_repository()
+                .add_log_entry(file.id, FileSyncStatus::UploadCompleted, "", &cloud_key)
+                .await;
 
-                                // Leave cloud_sync_status = NotSynced so it is retried next sync.
-                                // Write audit log entry for diagnosis.
-                                let log_res = context
-                                    .repository_manager
-                                    .get_file_sync_log_repository()
-                                    .add_log_entry(
-                                        file.id,
-                                        FileSyncStatus::UploadFailed,
-                                        &format!("{}", e),
-                                        &cloud_key,
-                                    )
-                                    .await;
+            match (status_res, log_res) {
+                (Ok(()), Ok(_)) => {
+                    file_sync_result.db_update_success = true;
+                }
+                (Err(e), _) => {
+                    tracing::error!(
+                        file_info_id = file.id,
+                        error = %e,
+                        "Error updating cloud_sync_status after upload"
+                    );
+                    file_sync_result.db_update_success = false;
+                    file_sync_result.db_error = Some(format!("{}", e));
+                }
+                (_, Err(e)) => {
+                    tracing::error!(
+                        file_info_id = file.id,
+                        error = %e,
+                        "Error writing upload log entry"
+                    );
+                    file_sync_result.db_update_success = false;
+                    file_sync_result.db_error = Some(format!("{}", e));
+                }
+            }
 
-                                match log_res {
-                                    Ok(_) => {
-                                        file_sync_result.db_update_success = true;
-                                    }
-                                    Err(e) => {
-                                        tracing::error!(
-                                            file_info_id = file.id,
-                                            error = %e,
-                                            "Error writing failure log after failed upload"
-                                        );
-                                        file_sync_result.db_update_success = false;
-                                        file_sync_result.db_error = Some(format!("{}", e));
-                                    }
-                                }
+            send_progress_event(
+                SyncEvent::FileUploadCompleted {
+                    key: cloud_key.clone(),
+                    file_number,
+                    total_files: context.files_prepared_for_upload,
+                },
+                &context.progress_tx,
+            )
+            .await;
+        }
+        Err(e) => {
+            tracing::error!(
+                file_info_id = file.id,
+                cloud_key = cloud_key,
+                local_path = %local_path.display(),
+                error = %e,
+                "Upload failed"
+            );
+            file_sync_result.cloud_operation_success = false;
+            file_sync_result.cloud_error = Some(format!("{}", e));
 
-                                send_progress_event(
-                                    SyncEvent::FileUploadFailed {
-                                        key: cloud_key.clone(),
-                                        error: format!("{e}"),
-                                        file_number: file_count,
-                                        total_files: context.files_prepared_for_upload,
-                                    },
-                                    &context.progress_tx,
-                                )
-                                .await;
+            // Leave cloud_sync_status = NotSynced so it is retried next sync.
+            // Write audit log entry for diagnosis.
+            let log_res = context
+                .repository_manager
+                .get_file_sync_log_repository()
+                .add_log_entry(
+                    file.id,
+                    FileSyncStatus::UploadFailed,
+                    &format!("{}", e),
+                    &cloud_key,
+                )
+                .await;
 
-                                // Failed files stay NotSynced and re-appear at offset 0 on the
-                                // next fetch, so advance the session offset past them.
-                                session_skip += 1;
-                            }
-                        }
-                        context.upload_results.insert(cloud_key, file_sync_result);
-                    }
+            match log_res {
+                Ok(_) => {
+                    file_sync_result.db_update_success = true;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        file_info_id = file.id,
+                        error = %e,
+                        "Error writing failure log after failed upload"
+                    );
+                    file_sync_result.db_update_success = false;
+                    file_sync_result.db_error = Some(format!("{}", e));
                 }
             }
+
+            send_progress_event(
+                SyncEvent::FileUploadFailed {
+                    key: cloud_key.clone(),
+                    error: format!("{e}"),
+                    file_number,
+                    total_files: context.files_prepared_for_upload,
+                },
+                &context.progress_tx,
+            )
+            .await;
         }
-        tracing::debug!("Pending file uploads completed");
-        StepAction::Continue
     }
+    Some((cloud_key, file_sync_result))
 }
 
 /// Step 3: Delete files with `cloud_sync_status = DeletionPending` AND `archive_file_name IS NOT NULL`
@@ -955,6 +1015,70 @@ mod tests {
         }
     }
 
+    async fn upload_files_with_concurrency(max_concurrent_uploads: usize) -> usize {
+        let cloud_ops = Arc::new(MockCloudStorage::new());
+        cloud_ops.set_upload_delay(std::time::Duration::from_millis(20));
+        let mut context = initialize_sync_context_with_cloud(cloud_ops.clone()).await;
+        context.settings = Arc::new(Settings {
+            collection_root_dir: PathBuf::from("/"),
+            max_concurrent_uploads: Some(max_concurrent_uploads),
+            ..Default::default()
+        });
+        for i in 0..8u8 {
+            add_file_info(
+                &context.repository_manager,
+                [i; 20],
+                &format!("file{}.zst", i),
+                FileType::Rom,
+            )
+            .await;
+        }
+        context.files_prepared_for_upload = 8;
+
+        let action = UploadPendingFilesStep.execute(&mut context).await;
+
+        assert_eq!(action, StepAction::Continue);
+        assert_eq!(context.successful_uploads(), 8);
+        cloud_ops.max_uploads_in_flight()
+    }
+
+    #[async_std::test]
+    async fn test_upload_step_aborts_with_too_small_part_size() {
+        let cloud_ops = Arc::new(MockCloudStorage::new());
+        let mut context = initialize_sync_context_with_cloud(cloud_ops.clone()).await;
+        context.settings = Arc::new(Settings {
+            collection_root_dir: PathBuf::from("/"),
+            upload_part_size_mb: Some(1),
+            ..Default::default()
+        });
+        add_file_info(
+            &context.repository_manager,
+            [0; 20],
+            "file1.zst",
+            FileType::Rom,
+        )
+        .await;
+        context.files_prepared_for_upload = 1;
+
+        let action = UploadPendingFilesStep.execute(&mut context).await;
+
+        assert!(matches!(
+            action,
+            StepAction::Abort(crate::error::Error::CloudSyncError(_))
+        ));
+        assert!(cloud_ops.get_uploaded_keys().is_empty());
+    }
+
+    #[async_std::test]
+    async fn test_upload_step_limits_concurrent_uploads() {
+        assert_eq!(upload_files_with_concurrency(3).await, 3);
+    }
+
+    #[async_std::test]
+    async fn test_upload_step_uploads_one_at_a_time_with_concurrency_of_one() {
+        assert_eq!(upload_files_with_concurrency(1).await, 1);
+    }
+
     #[async_std::test]
     async fn test_upload_step_emits_upload_progress_events() {
         // Arrange
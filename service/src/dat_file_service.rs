@@ -152,6 +152,7 @@ impl DatFileService {
                 cloneofid: game.cloneofid.clone(),
                 categories: vec![], // Categories are not stored in the current implementation
                 roms,
+                disks: vec![],    // Disks are not stored in the current implementation
                 releases: vec![], // Releases are not stored in the current implementation
             };
             games_out.push(game);
@@ -215,6 +216,7 @@ mod tests {
                     serial: None,
                     header: None,
                 }],
+                disks: vec![],
                 releases: vec![],
             }],
         };
@@ -6,11 +6,22 @@ use std::{
 
 use core_types::{FileType, Sha1Checksum};
 use database::{models::System, repository_manager::RepositoryManager};
-use file_export::{FileSetExportModel, OutputFile, export_files_zipped};
+use file_export::{ExportContainer, FileSetExportModel, OutputFile, export_files_zipped};
 
 use crate::{error::Error, view_model_service::ViewModelService, view_models::FileSetViewModel};
 
 /// Service responsible for exporting all the files from the collection to a specified destination.
+///
+/// # Exporting to cloud storage
+///
+/// Exports can be streamed to cloud storage without staging them locally with
+/// [`file_export::export_files_tar_gz_to_writer`] (or [`file_export::export_files_to_writer`]
+/// for zip, which requires a seekable writer). The export runs in a blocking task with a writer
+/// that sends the written bytes as chunks over a bounded flume channel. An async task receives
+/// the chunks, buffers them to the multipart part size and uploads each full buffer with
+/// `Bucket::put_multipart_chunk`, like `cloud_storage::multipart_upload` does for files. The
+/// upload is completed once the export returns and the channel is closed, or aborted if the
+/// export fails.
 // TODO: refactor to use download service for exporting files
 #[derive(Debug)]
 pub struct ExportService {
@@ -151,5 +162,9 @@ fn prepare_fileset_for_export(
         output_dir: output_dir.to_path_buf(),
         extract_files,
         exported_zip_file_name,
+        skip_existing: false,
+        container: ExportContainer::Zip,
+        zip_compression: None,
+        fail_fast: true,
     }
 }
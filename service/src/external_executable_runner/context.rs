@@ -3,7 +3,7 @@ use std::sync::Arc;
 use core_types::{ArgumentType, events::DownloadEvent};
 use database::repository_manager::RepositoryManager;
 use executable_runner::ops::ExecutableRunnerOps;
-use flume::Sender;
+use flume::{Receiver, Sender};
 
 use crate::{
     file_set_download::download_service_ops::DownloadServiceOps, file_system_ops::FileSystemOps,
@@ -29,4 +29,5 @@ pub struct ExternalExecutableRunnerContext {
     /// Set to true for viewers that spawn child processes (like xdg-open)
     /// where the parent returns immediately.
     pub skip_cleanup: bool,
+    pub cancel_rx: Option<Receiver<()>>,
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use core_types::{ArgumentType, events::DownloadEvent};
 use database::repository_manager::RepositoryManager;
 use executable_runner::ops::{DefaultExecutableRunnerOps, ExecutableRunnerOps};
-use flume::Sender;
+use flume::{Receiver, Sender};
 
 use crate::{
     error::Error,
@@ -39,6 +39,8 @@ pub struct ExecutableRunnerModel {
     /// Set to true for viewers that spawn child processes (like xdg-open)
     /// where the parent returns immediately.
     pub skip_cleanup: bool,
+    /// Kills the executable when a message is received
+    pub cancel_rx: Option<Receiver<()>>,
 }
 
 impl ExternalExecutableRunnerService {
@@ -47,11 +49,15 @@ impl ExternalExecutableRunnerService {
             repository_manager.clone(),
             settings.clone(),
         ));
+        let mut executable_runner_ops = DefaultExecutableRunnerOps::new();
+        if let Some(timeout) = settings.executable_timeout() {
+            executable_runner_ops = executable_runner_ops.with_timeout(timeout);
+        }
         Self::new_with_ops(
             repository_manager,
             settings,
             Arc::new(StdFileSystemOps),
-            Arc::new(DefaultExecutableRunnerOps),
+            Arc::new(executable_runner_ops),
             download_service,
         )
     }
@@ -93,6 +99,7 @@ impl ExternalExecutableRunnerService {
             download_service_ops: self.download_service_ops.clone(),
             progress_tx,
             skip_cleanup: model.skip_cleanup,
+            cancel_rx: model.cancel_rx,
         };
 
         let pipeline = Pipeline::<ExternalExecutableRunnerContext>::new();
@@ -87,6 +87,7 @@ impl PipelineStep<ExternalExecutableRunnerContext, Error> for StartExecutableSte
                 &context.file_names,
                 initial_file,
                 temp_dir,
+                context.cancel_rx.clone(),
             )
             .await;
 
@@ -373,6 +374,7 @@ mod tests {
                 .unwrap_or(Arc::new(MockDownloadServiceOps::new())),
             progress_tx: None,
             skip_cleanup: false,
+            cancel_rx: None,
         }
     }
 }
@@ -231,6 +231,12 @@ impl CheckExistingFilesContext for AddFileSetContext {
     fn set_existing_files(&mut self, existing_files: Vec<FileInfo>) {
         self.state.existing_files = existing_files;
     }
+    fn settings(&self) -> Arc<Settings> {
+        self.deps.settings.clone()
+    }
+    fn fs_ops(&self) -> Arc<dyn FileSystemOps> {
+        self.ops.fs_ops.clone()
+    }
 }
 
 impl CheckExistingFileSetContext for AddFileSetContext {
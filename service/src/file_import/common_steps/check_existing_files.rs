@@ -5,7 +5,9 @@ use database::{models::FileInfo, repository_manager::RepositoryManager};
 
 use crate::{
     error::Error,
+    file_system_ops::FileSystemOps,
     pipeline::pipeline_step::{PipelineStep, StepAction},
+    view_models::Settings,
 };
 
 pub trait CheckExistingFilesContext {
@@ -13,11 +15,16 @@ pub trait CheckExistingFilesContext {
     fn file_type(&self) -> FileType;
     fn repository_manager(&self) -> Arc<RepositoryManager>;
     fn set_existing_files(&mut self, existing_files: Vec<FileInfo>);
+    fn settings(&self) -> Arc<Settings>;
+    fn fs_ops(&self) -> Arc<dyn FileSystemOps>;
 }
 
 /// Pipeline step that checks for existing files in the database based on their SHA1 checksums.
 /// Existing file information is then stored in the context for use in later steps, such as
 /// skipping files that already exist in the database.
+///
+/// Existing files that were moved to trash with a soft-deleted file set are moved back to the
+/// collection, since they are going to be used again.
 pub struct CheckExistingFilesStep<T: CheckExistingFilesContext> {
     // `PhantomData<T>` is required to satisfy Rust's type system for generic structs that don't store their generic type directly.
     _phantom: std::marker::PhantomData<T>,
@@ -65,6 +72,9 @@ where
                     "Fetched existing file info from repository"
                 );
                 dbg!("existing files", &existing_files_file_info);
+                if let Err(err) = restore_files_from_trash(context, &existing_files_file_info) {
+                    return StepAction::Abort(err);
+                }
                 context.set_existing_files(existing_files_file_info);
 
                 StepAction::Continue
@@ -79,22 +89,55 @@ where
         }
     }
 }
+fn restore_files_from_trash<T: CheckExistingFilesContext>(
+    context: &T,
+    existing_files: &[FileInfo],
+) -> Result<(), Error> {
+    let settings = context.settings();
+    let fs_ops = context.fs_ops();
+    for file_info in existing_files {
+        let Some(archive_file_name) = &file_info.archive_file_name else {
+            continue;
+        };
+        let trash_path = settings.get_trash_file_path(&file_info.file_type, archive_file_name);
+        if !fs_ops.exists(&trash_path) {
+            continue;
+        }
+        let file_path = settings.get_file_path(&file_info.file_type, archive_file_name);
+        fs_ops.move_file(&trash_path, &file_path).map_err(|e| {
+            tracing::error!(path = %trash_path.display(), error = %e, "Failed to restore reused file from trash");
+            Error::IoError(format!(
+                "Failed to restore file {} from trash: {}",
+                trash_path.display(),
+                e
+            ))
+        })?;
+        tracing::info!(path = %file_path.display(), "Restored reused file from trash");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, sync::Arc};
+    use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-    use core_types::{FileType, ReadFile, Sha1Checksum};
+    use core_types::{FileType, ImportedFile, ReadFile, Sha1Checksum};
     use database::{models::FileInfo, repository_manager::RepositoryManager, setup_test_db};
 
     use crate::{
         file_import::common_steps::check_existing_files::{
             CheckExistingFilesContext, CheckExistingFilesStep,
         },
+        file_set_deletion::service::FileSetDeletionService,
+        file_system_ops::{FileSystemOps, mock::MockFileSystemOps},
         pipeline::pipeline_step::PipelineStep,
+        view_models::Settings,
     };
 
     struct TestContext {
         pub repository_manager: Arc<RepositoryManager>,
+        pub settings: Arc<Settings>,
+        pub fs_ops: Arc<MockFileSystemOps>,
         pub file_type: FileType,
         pub existing_files: Vec<FileInfo>,
         pub file_info: HashMap<Sha1Checksum, ReadFile>,
@@ -113,6 +156,12 @@ mod tests {
         fn set_existing_files(&mut self, existing_files: Vec<FileInfo>) {
             self.existing_files = existing_files;
         }
+        fn settings(&self) -> Arc<Settings> {
+            self.settings.clone()
+        }
+        fn fs_ops(&self) -> Arc<dyn FileSystemOps> {
+            self.fs_ops.clone()
+        }
     }
 
     async fn initialize_context() -> TestContext {
@@ -121,6 +170,12 @@ mod tests {
 
         TestContext {
             repository_manager,
+            settings: Arc::new(Settings {
+                collection_root_dir: PathBuf::from("/"),
+                trash_retention_days: Some(0),
+                ..Default::default()
+            }),
+            fs_ops: Arc::new(MockFileSystemOps::new()),
             file_type: FileType::Rom,
             file_info: HashMap::new(),
             existing_files: Vec::new(),
@@ -187,4 +242,85 @@ mod tests {
             Some(existing_file_archive_name.to_string())
         );
     }
+
+    #[async_std::test]
+    async fn test_soft_delete_then_reimport_same_file() {
+        let checksum: Sha1Checksum = [1u8; 20];
+        let mut context = initialize_context().await;
+        let repository_manager = context.repository_manager.clone();
+        let system_id = repository_manager
+            .get_system_repository()
+            .add_system("Test System")
+            .await
+            .unwrap();
+        let file = ImportedFile {
+            original_file_name: "game.rom".to_string(),
+            archive_file_name: Some("archive_1".to_string()),
+            sha1_checksum: checksum,
+            file_size: 2048,
+        };
+        let file_path = context.settings.get_file_path(&FileType::Rom, "archive_1");
+        let trash_path = context
+            .settings
+            .get_trash_file_path(&FileType::Rom, "archive_1");
+        context
+            .fs_ops
+            .add_file(file_path.to_string_lossy().as_ref());
+        let file_set_id = repository_manager
+            .get_file_set_repository()
+            .add_file_set(
+                "deleted set",
+                "game.rom",
+                &FileType::Rom,
+                "",
+                std::slice::from_ref(&file),
+                &[system_id],
+            )
+            .await
+            .unwrap();
+        let deletion_service = FileSetDeletionService::new_with_fs_ops(
+            repository_manager.clone(),
+            context.settings.clone(),
+            context.fs_ops.clone(),
+        );
+        deletion_service
+            .soft_delete_file_set(file_set_id, None)
+            .await
+            .unwrap();
+        assert!(context.fs_ops.exists(&trash_path));
+
+        // Import the same file again
+        context.file_info.insert(
+            checksum,
+            ReadFile {
+                file_name: "game.rom".into(),
+                sha1_checksum: checksum,
+                file_size: 2048,
+            },
+        );
+        let step = CheckExistingFilesStep::<TestContext>::new();
+        let action = step.execute(&mut context).await;
+
+        assert!(matches!(action, super::StepAction::Continue));
+        assert_eq!(context.existing_files.len(), 1);
+        assert!(context.fs_ops.exists(&file_path));
+        assert!(!context.fs_ops.exists(&trash_path));
+
+        // File is kept when the deleted file set is purged
+        repository_manager
+            .get_file_set_repository()
+            .add_file_set(
+                "imported set",
+                "game.rom",
+                &FileType::Rom,
+                "",
+                &[file],
+                &[system_id],
+            )
+            .await
+            .unwrap();
+        deletion_service.purge_expired_file_sets().await.unwrap();
+        assert!(context.fs_ops.get_deleted_files().is_empty());
+        assert!(context.fs_ops.exists(&file_path));
+    }
 }
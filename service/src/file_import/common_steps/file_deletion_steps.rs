@@ -1,7 +1,8 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
-use core_types::{CloudSyncStatus, Sha1Checksum};
+use core_types::{CloudSyncStatus, FileType, Sha1Checksum, events::DeletionEvent};
 use database::repository_manager::RepositoryManager;
+use flume::Sender;
 
 use crate::{
     error::Error,
@@ -31,6 +32,33 @@ pub trait FileDeletionStepsContext {
     ) -> &mut std::collections::HashMap<Sha1Checksum, FileDeletionResult>;
     fn settings(&self) -> Arc<Settings>;
     fn fs_ops(&self) -> Arc<dyn FileSystemOps>;
+    /// Resolve the local path of a file to be deleted. Defaults to the file's location in the
+    /// collection.
+    fn local_file_path(&self, file_type: &FileType, archive_file_name: &str) -> PathBuf {
+        self.settings().get_file_path(file_type, archive_file_name)
+    }
+    /// Optional sender for per-file deletion progress.
+    fn progress_tx(&self) -> Option<&Sender<DeletionEvent>> {
+        None
+    }
+}
+
+/// Send per-file progress to the context's progress sender, if any.
+pub fn send_file_progress(
+    progress_tx: Option<&Sender<DeletionEvent>>,
+    file_name: &str,
+    file_number: usize,
+    total_files: usize,
+) {
+    if let Some(tx) = progress_tx
+        && let Err(e) = tx.send(DeletionEvent::FileProgress {
+            file_name: file_name.to_string(),
+            file_number,
+            total_files,
+        })
+    {
+        tracing::warn!(error = %e, "Failed to send deletion progress event");
+    }
 }
 
 /// Filter files that are only in this file set (safe to delete)
@@ -159,27 +187,53 @@ where
             "Deleting local files for file set"
         );
 
-        let settings = context.settings();
         let fs_ops = context.fs_ops();
-
-        for deletion_result in context
+        let file_paths = context
+            .deletion_results()
+            .iter()
+            .filter(|(_, f)| f.is_deletable)
+            .filter_map(|(sha1, f)| {
+                f.file_info.archive_file_name.as_ref().map(|archive_name| {
+                    (
+                        *sha1,
+                        context.local_file_path(&f.file_info.file_type, archive_name),
+                    )
+                })
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
+        let progress_tx = context.progress_tx().cloned();
+        let total_files = file_paths.len();
+        let mut file_number = 0;
+
+        for (sha1, deletion_result) in context
             .deletion_results_mut()
-            .values_mut()
-            .filter(|f| f.is_deletable)
+            .iter_mut()
+            .filter(|(_, f)| f.is_deletable)
         {
             tracing::info!(
                 file_info_id = deletion_result.file_info.id,
                 "Processing file info for local deletion"
             );
-            let Some(archive_name) = &deletion_result.file_info.archive_file_name else {
+            let Some(file_path) = file_paths.get(sha1) else {
                 tracing::warn!(
                     file_info_id = deletion_result.file_info.id,
                     "File info does not have an archive file name, skipping local deletion.",
                 );
                 continue;
             };
-            let file_path =
-                settings.get_file_path(&deletion_result.file_info.file_type, archive_name);
+
+            file_number += 1;
+            send_file_progress(
+                progress_tx.as_ref(),
+                deletion_result
+                    .file_info
+                    .archive_file_name
+                    .as_deref()
+                    .unwrap_or_default(),
+                file_number,
+                total_files,
+            );
 
             let path_str = file_path.to_string_lossy().to_string();
             tracing::info!(
@@ -191,12 +245,12 @@ where
 
             tracing::info!(path = path_str.as_str(), "Attempting to delete local file");
 
-            if fs_ops.exists(&file_path) {
+            if fs_ops.exists(file_path) {
                 tracing::info!(
                     path = path_str.as_str(),
                     "File exists, proceeding with deletion"
                 );
-                match fs_ops.remove_file(&file_path) {
+                match fs_ops.remove_file(file_path) {
                     Ok(_) => {
                         tracing::info!(path = path_str.as_str(), "Deleted local file");
                         deletion_result.file_deletion_success = Some(true);
@@ -1,8 +1,8 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use core_types::{FileSize, FileType, ImportedFile, Sha1Checksum, item_type::ItemType};
+use core_types::{FileSize, FileType, ImportedFile, ReadFile, Sha1Checksum, item_type::ItemType};
 use database::models::FileInfo;
-use file_import::{FileImportModel, SelectedImportEntry};
+use file_import::{FileImportModel, ImportOptions, SelectedImportEntry};
 
 use crate::error::Error;
 
@@ -110,6 +110,10 @@ impl FileImportData {
             output_dir: self.output_dir.clone(),
             file_type: self.file_type,
             selected_entries: self.get_new_selected_entries(existing_files),
+            // Existing files are already left out from the selected entries and are only linked
+            // to the file set. Passing them as existing files would make the rollback after a
+            // failed database update delete files that belong to other file sets.
+            import_options: ImportOptions::default(),
         }
     }
 }
@@ -154,6 +158,9 @@ impl FileImportData {
 pub struct FileImportPrepareResult {
     pub import_model: FileImportSource,
     pub import_metadata: FileImportMetadata,
+    /// Files inside an ISO9660 disc image. Informational only, the disc image itself is
+    /// imported as a single file.
+    pub disc_image_listing: Vec<ReadFile>,
 }
 
 #[derive(Debug)]
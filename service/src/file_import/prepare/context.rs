@@ -23,6 +23,8 @@ pub struct PrepareFileImportContext {
     pub import_metadata: Option<FileImportMetadata>,
     pub existing_files: Vec<FileInfo>,
     pub file_info: HashMap<Sha1Checksum, ReadFile>,
+    /// Files inside a disc image, informational only
+    pub disc_image_listing: Vec<ReadFile>,
     pub fs_ops: Arc<dyn FileSystemOps>,
     pub file_metadata_ops: Arc<dyn FileMetadataOps>,
 }
@@ -42,6 +44,7 @@ impl PrepareFileImportContext {
             import_metadata: None,
             existing_files: vec![],
             file_info: HashMap::new(),
+            disc_image_listing: vec![],
             fs_ops,
             file_metadata_ops,
         }
@@ -1,7 +1,10 @@
 use crate::{
     file_import::{
         common_steps::collect_file_info::CollectFileInfoStep,
-        prepare::{context::PrepareFileImportContext, steps::CollectFileMetadataStep},
+        prepare::{
+            context::PrepareFileImportContext,
+            steps::{CollectDiscImageListingStep, CollectFileMetadataStep},
+        },
     },
     pipeline::generic_pipeline::Pipeline,
 };
@@ -11,6 +14,7 @@ impl Pipeline<PrepareFileImportContext> {
         Self::with_steps(vec![
             Box::new(CollectFileMetadataStep),
             Box::new(CollectFileInfoStep::<PrepareFileImportContext>::new()),
+            Box::new(CollectDiscImageListingStep),
         ])
     }
 }
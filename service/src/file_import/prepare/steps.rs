@@ -1,3 +1,6 @@
+use core_types::FileType;
+use file_metadata::iso9660::is_disc_image_file_name;
+
 use crate::{
     error::Error,
     file_import::{model::FileImportMetadata, prepare::context::PrepareFileImportContext},
@@ -58,11 +61,52 @@ impl PipelineStep<PrepareFileImportContext, Error> for CollectFileMetadataStep {
     }
 }
 
+/// Lists files inside ISO9660 disc images. The listing is informational only, a failure to
+/// read it doesn't prevent the import.
+pub struct CollectDiscImageListingStep;
+
+#[async_trait::async_trait]
+impl PipelineStep<PrepareFileImportContext, Error> for CollectDiscImageListingStep {
+    fn name(&self) -> &'static str {
+        "collect_disc_image_listing"
+    }
+
+    fn should_execute(&self, context: &PrepareFileImportContext) -> bool {
+        context.file_type == FileType::DiskImage
+            && is_disc_image_file_name(&context.file_path)
+            && context.fs_ops.exists(&context.file_path)
+    }
+
+    async fn execute(&self, context: &mut PrepareFileImportContext) -> StepAction<Error> {
+        match context
+            .file_metadata_ops
+            .read_disc_image_listing(&context.file_path)
+        {
+            Ok(listing) => {
+                tracing::info!(
+                    file_path = %context.file_path.display(),
+                    files = listing.len(),
+                    "Read disc image file listing"
+                );
+                context.disc_image_listing = listing;
+            }
+            Err(err) => {
+                tracing::warn!(
+                    error = %err,
+                    file_path = %context.file_path.display(),
+                    "Failed to read disc image file listing"
+                );
+            }
+        }
+        StepAction::Continue
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{path::Path, sync::Arc};
 
-    use core_types::FileType;
+    use core_types::{FileType, ReadFile};
     use database::{repository_manager::RepositoryManager, setup_test_db};
     use file_import::file_import_ops::mock::MockFileImportOps;
     use file_metadata::file_metadata_ops::mock::MockFileMetadataOps;
@@ -78,7 +122,8 @@ mod tests {
         let fs_ops = Arc::new(MockFileSystemOps::new());
         fs_ops.add_file(test_path.to_string_lossy().to_string());
         let file_metadata_ops = Arc::new(MockFileMetadataOps::new());
-        let mut context = initialize_context(test_path, fs_ops, file_metadata_ops).await;
+        let mut context =
+            initialize_context(test_path, FileType::Rom, fs_ops, file_metadata_ops).await;
 
         let step = super::CollectFileMetadataStep;
         let action = step.execute(&mut context).await;
@@ -91,8 +136,61 @@ mod tests {
         assert!(metadata.is_zip_archive);
     }
 
+    #[async_std::test]
+    async fn test_collect_disc_image_listing_step() {
+        let test_path = Path::new("/test/discs/game.iso");
+        let fs_ops = Arc::new(MockFileSystemOps::new());
+        fs_ops.add_file(test_path.to_string_lossy().to_string());
+        let file_metadata_ops = Arc::new(MockFileMetadataOps::new());
+        let executable = ReadFile {
+            file_name: "SLUS_000.01".to_string(),
+            sha1_checksum: [1u8; 20],
+            file_size: 1024,
+        };
+        file_metadata_ops.add_disc_image_entry(executable.clone());
+        let mut context =
+            initialize_context(test_path, FileType::DiskImage, fs_ops, file_metadata_ops).await;
+
+        let step = super::CollectDiscImageListingStep;
+        assert!(step.should_execute(&context));
+        let action = step.execute(&mut context).await;
+
+        assert!(matches!(action, super::StepAction::Continue));
+        assert_eq!(context.disc_image_listing, vec![executable]);
+    }
+
+    #[async_std::test]
+    async fn test_collect_disc_image_listing_step_skipped_for_other_file_types() {
+        let test_path = Path::new("/test/roms/game.bin");
+        let fs_ops = Arc::new(MockFileSystemOps::new());
+        fs_ops.add_file(test_path.to_string_lossy().to_string());
+        let file_metadata_ops = Arc::new(MockFileMetadataOps::new());
+        let context = initialize_context(test_path, FileType::Rom, fs_ops, file_metadata_ops).await;
+
+        let step = super::CollectDiscImageListingStep;
+        assert!(!step.should_execute(&context));
+    }
+
+    #[async_std::test]
+    async fn test_collect_disc_image_listing_step_failure_does_not_abort() {
+        let test_path = Path::new("/test/discs/game.iso");
+        let fs_ops = Arc::new(MockFileSystemOps::new());
+        fs_ops.add_file(test_path.to_string_lossy().to_string());
+        let file_metadata_ops = Arc::new(MockFileMetadataOps::new());
+        file_metadata_ops.set_should_fail(true);
+        let mut context =
+            initialize_context(test_path, FileType::DiskImage, fs_ops, file_metadata_ops).await;
+
+        let step = super::CollectDiscImageListingStep;
+        let action = step.execute(&mut context).await;
+
+        assert!(matches!(action, super::StepAction::Continue));
+        assert!(context.disc_image_listing.is_empty());
+    }
+
     async fn initialize_context(
         path: &Path,
+        file_type: FileType,
         fs_ops: Arc<MockFileSystemOps>,
         file_metadata_ops: Arc<MockFileMetadataOps>,
     ) -> PrepareFileImportContext {
@@ -102,7 +200,7 @@ mod tests {
         PrepareFileImportContext::new(
             repository_manager,
             path,
-            FileType::Rom,
+            file_type,
             fs_ops,
             file_metadata_ops,
         )
@@ -94,6 +94,7 @@ impl FileImportService {
                 Ok(FileImportPrepareResult {
                     import_model,
                     import_metadata,
+                    disc_image_listing: context.disc_image_listing,
                 })
             }
             Err(err) => {
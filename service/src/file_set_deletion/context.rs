@@ -1,7 +1,8 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-use core_types::Sha1Checksum;
+use core_types::{FileType, Sha1Checksum, events::DeletionEvent};
 use database::repository_manager::RepositoryManager;
+use flume::Sender;
 
 use crate::{
     file_import::common_steps::file_deletion_steps::FileDeletionStepsContext,
@@ -15,6 +16,9 @@ pub struct DeletionContext {
     pub repository_manager: Arc<RepositoryManager>,
     pub settings: Arc<Settings>,
     pub fs_ops: Arc<dyn FileSystemOps>,
+    /// When set, local files are resolved from the trash directory (purging a soft-deleted file set)
+    pub from_trash: bool,
+    pub progress_tx: Option<Sender<DeletionEvent>>,
 
     // Accumulated state as pipeline progresses
     pub deletion_results: HashMap<Sha1Checksum, FileDeletionResult>,
@@ -48,4 +52,21 @@ impl FileDeletionStepsContext for DeletionContext {
     fn settings(&self) -> Arc<Settings> {
         self.settings.clone()
     }
+
+    fn local_file_path(&self, file_type: &FileType, archive_file_name: &str) -> PathBuf {
+        if self.from_trash {
+            let trash_path = self
+                .settings
+                .get_trash_file_path(file_type, archive_file_name);
+            // Files that were shared at the time of soft-deletion were never moved to trash
+            if self.fs_ops.exists(&trash_path) {
+                return trash_path;
+            }
+        }
+        self.settings.get_file_path(file_type, archive_file_name)
+    }
+
+    fn progress_tx(&self) -> Option<&Sender<DeletionEvent>> {
+        self.progress_tx.as_ref()
+    }
 }
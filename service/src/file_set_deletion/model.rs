@@ -15,6 +15,8 @@ pub struct FileDeletionResult {
     pub db_deletion_success: Option<bool>,
     /// None = not attempted, Some(true) = success, Some(false) = failed
     pub cloud_delete_marked_successfully: Option<bool>,
+    /// None = not attempted, Some(true) = success, Some(false) = failed
+    pub moved_to_trash: Option<bool>,
 }
 
 impl FileDeletionResult {
@@ -27,6 +29,7 @@ impl FileDeletionResult {
             is_deletable: false,
             db_deletion_success: None,
             cloud_delete_marked_successfully: None,
+            moved_to_trash: None,
         }
     }
 }
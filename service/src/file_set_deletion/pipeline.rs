@@ -5,7 +5,11 @@ use crate::{
     },
     file_set_deletion::{
         context::DeletionContext,
-        steps::{DeleteFileSetStep, FetchFileInfosStep, ValidateFileSetNotInUseStep},
+        steps::{
+            DeleteFileSetStep, FetchFileInfosStep, MarkFileSetDeletedStep, MoveFilesToTrashStep,
+            RemoveThumbnailsStep, RestoreFileSetStep, RestoreFilesFromTrashStep,
+            ValidateFileSetNotInUseStep,
+        },
     },
     pipeline::generic_pipeline::Pipeline,
 };
@@ -18,6 +22,42 @@ impl Pipeline<DeletionContext> {
             Box::new(FilterDeletableFilesStep::<DeletionContext>::new()),
             Box::new(DeleteFileSetStep),
             Box::new(DeleteLocalFilesStep::<DeletionContext>::new()),
+            Box::new(RemoveThumbnailsStep),
+            Box::new(MarkForCloudDeletionStep::<DeletionContext>::new()),
+            Box::new(DeleteFileInfosStep::<DeletionContext>::new()),
+        ])
+    }
+
+    /// Marks the file set deleted and moves its unshared files to trash.
+    pub fn new_soft_delete() -> Self {
+        Self::with_steps(vec![
+            Box::new(ValidateFileSetNotInUseStep),
+            Box::new(FetchFileInfosStep),
+            Box::new(FilterDeletableFilesStep::<DeletionContext>::new()),
+            Box::new(MarkFileSetDeletedStep),
+            Box::new(MoveFilesToTrashStep),
+        ])
+    }
+
+    /// Moves trashed files back to the collection and clears the deleted mark.
+    pub fn new_restore() -> Self {
+        Self::with_steps(vec![
+            Box::new(FetchFileInfosStep),
+            Box::new(RestoreFilesFromTrashStep),
+            Box::new(RestoreFileSetStep),
+        ])
+    }
+
+    /// Permanently deletes a soft-deleted file set. Expects the context to resolve files from
+    /// trash.
+    pub fn new_purge() -> Self {
+        Self::with_steps(vec![
+            Box::new(FetchFileInfosStep),
+            Box::new(FilterDeletableFilesStep::<DeletionContext>::new()),
+            Box::new(RestoreFilesFromTrashStep),
+            Box::new(DeleteFileSetStep),
+            Box::new(DeleteLocalFilesStep::<DeletionContext>::new()),
+            Box::new(RemoveThumbnailsStep),
             Box::new(MarkForCloudDeletionStep::<DeletionContext>::new()),
             Box::new(DeleteFileInfosStep::<DeletionContext>::new()),
         ])
@@ -1,12 +1,14 @@
 use std::{collections::HashMap, sync::Arc};
 
+use core_types::events::DeletionEvent;
 use database::repository_manager::RepositoryManager;
+use flume::Sender;
 
 use crate::{
     error::Error,
     file_set_deletion::{context::DeletionContext, model::FileDeletionResult},
     file_system_ops::{FileSystemOps, StdFileSystemOps},
-    pipeline::generic_pipeline::Pipeline,
+    pipeline::generic_pipeline::{Pipeline, StepStatus},
     view_models::Settings,
 };
 
@@ -43,24 +45,124 @@ impl FileSetDeletionService {
         }
     }
 
+    /// Permanently deletes the file set and the files used only by it.
+    ///
+    /// When `progress_tx` is given, step and per-file progress is reported as `DeletionEvent`s.
     pub async fn delete_file_set(
         &self,
         file_set_id: i64,
+        progress_tx: Option<Sender<DeletionEvent>>,
     ) -> Result<Vec<FileDeletionResult>, Error> {
         tracing::info!("Starting deletion for file set ID {}", file_set_id);
-        let mut context = DeletionContext {
+        let mut context = self.create_context(file_set_id, false, progress_tx.clone());
+
+        let pipeline = Pipeline::<DeletionContext>::new();
+        pipeline
+            .execute_with_listener(&mut context, step_listener(progress_tx))
+            .await?;
+
+        tracing::info!("Completed deletion for file set ID {}", file_set_id);
+        Ok(context.deletion_results.values().cloned().collect())
+    }
+
+    /// Moves the file set to trash: the file set is hidden from listings and the files used only
+    /// by it are moved to the trash directory. The file set can be restored with
+    /// `restore_file_set` until it's purged by `purge_expired_file_sets`.
+    ///
+    /// When `progress_tx` is given, step and per-file progress is reported as `DeletionEvent`s.
+    pub async fn soft_delete_file_set(
+        &self,
+        file_set_id: i64,
+        progress_tx: Option<Sender<DeletionEvent>>,
+    ) -> Result<Vec<FileDeletionResult>, Error> {
+        tracing::info!("Moving file set ID {} to trash", file_set_id);
+        let mut context = self.create_context(file_set_id, false, progress_tx.clone());
+
+        let pipeline = Pipeline::<DeletionContext>::new_soft_delete();
+        pipeline
+            .execute_with_listener(&mut context, step_listener(progress_tx))
+            .await?;
+
+        Ok(context.deletion_results.values().cloned().collect())
+    }
+
+    /// Restores a soft-deleted file set and moves its files back from trash.
+    pub async fn restore_file_set(&self, file_set_id: i64) -> Result<(), Error> {
+        tracing::info!("Restoring file set ID {} from trash", file_set_id);
+        let mut context = self.create_context(file_set_id, false, None);
+
+        let pipeline = Pipeline::<DeletionContext>::new_restore();
+        pipeline.execute(&mut context).await
+    }
+
+    /// Permanently deletes file sets that have been in trash longer than the configured
+    /// retention period.
+    ///
+    /// A failure to purge a single file set is logged and the file set is left in trash to be
+    /// retried on the next purge.
+    pub async fn purge_expired_file_sets(&self) -> Result<Vec<FileDeletionResult>, Error> {
+        let cutoff = chrono::Utc::now().naive_utc()
+            - chrono::Duration::days(self.settings.trash_retention_days() as i64);
+        let file_set_ids = self
+            .repository_manager
+            .get_file_set_repository()
+            .get_file_set_ids_deleted_before(cutoff)
+            .await
+            .map_err(|e| Error::DbError(format!("Failed to fetch expired file sets: {}", e)))?;
+
+        let mut results = Vec::new();
+        for file_set_id in file_set_ids {
+            tracing::info!("Purging file set ID {} from trash", file_set_id);
+            let mut context = self.create_context(file_set_id, true, None);
+            let pipeline = Pipeline::<DeletionContext>::new_purge();
+            match pipeline.execute(&mut context).await {
+                Ok(()) => results.extend(context.deletion_results.into_values()),
+                Err(e) => tracing::error!(
+                    file_set_id = file_set_id,
+                    error = %e,
+                    "Failed to purge file set from trash"
+                ),
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn create_context(
+        &self,
+        file_set_id: i64,
+        from_trash: bool,
+        progress_tx: Option<Sender<DeletionEvent>>,
+    ) -> DeletionContext {
+        DeletionContext {
             file_set_id,
             repository_manager: self.repository_manager.clone(),
             settings: self.settings.clone(),
             fs_ops: self.fs_ops.clone(),
+            from_trash,
+            progress_tx,
             deletion_results: HashMap::new(),
-        };
-
-        let pipeline = Pipeline::<DeletionContext>::new();
-        pipeline.execute(&mut context).await?;
+        }
+    }
+}
 
-        tracing::info!("Completed deletion for file set ID {}", file_set_id);
-        Ok(context.deletion_results.values().cloned().collect())
+/// Forward pipeline step statuses to the optional progress sender as `DeletionEvent`s.
+fn step_listener(
+    progress_tx: Option<Sender<DeletionEvent>>,
+) -> impl Fn(&'static str, StepStatus) + Send + Sync {
+    move |step, status| {
+        let Some(tx) = &progress_tx else {
+            return;
+        };
+        let step = step.to_string();
+        let event = match status {
+            StepStatus::Started => DeletionEvent::StepStarted { step },
+            StepStatus::Completed => DeletionEvent::StepCompleted { step },
+            StepStatus::Skipped => DeletionEvent::StepSkipped { step },
+        };
+        if let Err(e) = tx.send(event) {
+            tracing::warn!(error = %e, "Failed to send deletion progress event");
+        }
     }
 }
 
@@ -130,7 +232,7 @@ mod tests {
         let service =
             FileSetDeletionService::new_with_fs_ops(repo_manager, settings, mock_fs.clone());
 
-        let result = service.delete_file_set(file_set_id).await;
+        let result = service.delete_file_set(file_set_id, None).await;
         assert!(result.is_ok());
         let file_deletion_result = result.unwrap();
         assert_eq!(file_deletion_result.len(), 1);
@@ -140,4 +242,170 @@ mod tests {
         assert!(deletion_info.db_deletion_success.is_none());
         assert!(deletion_info.cloud_delete_marked_successfully.unwrap());
     }
+
+    #[async_std::test]
+    async fn test_soft_delete_and_restore_file_set() {
+        let (repo_manager, settings, mock_fs, file_set_id) = prepare_file_set(None).await;
+        let file_path = settings.get_file_path(&FileType::Rom, "file1.zst");
+        let trash_path = settings.get_trash_file_path(&FileType::Rom, "file1.zst");
+
+        let service = FileSetDeletionService::new_with_fs_ops(
+            repo_manager.clone(),
+            settings,
+            mock_fs.clone(),
+        );
+
+        let results = service.soft_delete_file_set(file_set_id, None).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].moved_to_trash, Some(true));
+        assert!(!mock_fs.exists(&file_path));
+        assert!(mock_fs.exists(&trash_path));
+        assert!(
+            repo_manager
+                .get_file_set_repository()
+                .get_all_file_sets()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        service.restore_file_set(file_set_id).await.unwrap();
+        assert!(mock_fs.exists(&file_path));
+        assert!(!mock_fs.exists(&trash_path));
+        assert_eq!(
+            repo_manager
+                .get_file_set_repository()
+                .get_all_file_sets()
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[async_std::test]
+    async fn test_delete_file_set_sends_progress_events() {
+        let (repo_manager, settings, mock_fs, file_set_id) = prepare_file_set(None).await;
+        let service = FileSetDeletionService::new_with_fs_ops(repo_manager, settings, mock_fs);
+        let (tx, rx) = flume::unbounded();
+
+        service
+            .delete_file_set(file_set_id, Some(tx))
+            .await
+            .unwrap();
+
+        let events: Vec<DeletionEvent> = rx.drain().collect();
+        assert!(events.contains(&DeletionEvent::StepStarted {
+            step: "delete_local_files".to_string()
+        }));
+        assert!(events.contains(&DeletionEvent::StepCompleted {
+            step: "delete_local_files".to_string()
+        }));
+        assert!(events.contains(&DeletionEvent::FileProgress {
+            file_name: "file1.zst".to_string(),
+            file_number: 1,
+            total_files: 1,
+        }));
+    }
+
+    #[async_std::test]
+    async fn test_purge_expired_file_sets() {
+        let (repo_manager, settings, mock_fs, file_set_id) = prepare_file_set(Some(0)).await;
+        let trash_path = settings.get_trash_file_path(&FileType::Rom, "file1.zst");
+
+        let service = FileSetDeletionService::new_with_fs_ops(
+            repo_manager.clone(),
+            settings,
+            mock_fs.clone(),
+        );
+        service.soft_delete_file_set(file_set_id, None).await.unwrap();
+
+        let results = service.purge_expired_file_sets().await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].file_deletion_success.unwrap());
+        assert!(results[0].db_deletion_success.unwrap());
+        assert!(mock_fs.was_deleted(trash_path.to_string_lossy().as_ref()));
+        assert!(
+            repo_manager
+                .get_file_set_repository()
+                .get_deleted_file_sets()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[async_std::test]
+    async fn test_purge_keeps_file_sets_within_retention_period() {
+        let (repo_manager, settings, mock_fs, file_set_id) = prepare_file_set(Some(30)).await;
+
+        let service = FileSetDeletionService::new_with_fs_ops(
+            repo_manager.clone(),
+            settings,
+            mock_fs.clone(),
+        );
+        service.soft_delete_file_set(file_set_id, None).await.unwrap();
+
+        let results = service.purge_expired_file_sets().await.unwrap();
+        assert!(results.is_empty());
+        assert!(mock_fs.get_deleted_files().is_empty());
+        assert_eq!(
+            repo_manager
+                .get_file_set_repository()
+                .get_deleted_file_sets()
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    async fn prepare_file_set(
+        trash_retention_days: Option<u32>,
+    ) -> (
+        Arc<RepositoryManager>,
+        Arc<Settings>,
+        Arc<MockFileSystemOps>,
+        i64,
+    ) {
+        let test_db_pool = Arc::new(setup_test_db().await);
+        let repo_manager = Arc::new(RepositoryManager::new(test_db_pool));
+        let settings = Arc::new(Settings {
+            collection_root_dir: PathBuf::from("/"),
+            trash_retention_days,
+            ..Default::default()
+        });
+
+        let system_id = repo_manager
+            .get_system_repository()
+            .add_system("Test System")
+            .await
+            .unwrap();
+
+        let file1 = ImportedFile {
+            original_file_name: "file1.zst".to_string(),
+            archive_file_name: Some("file1.zst".to_string()),
+            sha1_checksum: Sha1Checksum::from([0; 20]),
+            file_size: 1234,
+        };
+
+        let mock_fs = Arc::new(MockFileSystemOps::new());
+        let file_path = settings.get_file_path(&FileType::Rom, "file1.zst");
+        mock_fs.add_file(file_path.to_string_lossy().as_ref());
+
+        let file_set_id = repo_manager
+            .get_file_set_repository()
+            .add_file_set(
+                "test_set",
+                "file name",
+                &FileType::Rom,
+                "",
+                &[file1],
+                &[system_id],
+            )
+            .await
+            .unwrap();
+
+        (repo_manager, settings, mock_fs, file_set_id)
+    }
 }
@@ -1,5 +1,8 @@
 use crate::{
     error::Error,
+    file_import::common_steps::file_deletion_steps::{
+        FileDeletionStepsContext, send_file_progress,
+    },
     file_set_deletion::{context::DeletionContext, model::FileDeletionResult},
     pipeline::pipeline_step::{PipelineStep, StepAction},
 };
@@ -150,6 +153,246 @@ impl PipelineStep<DeletionContext, Error> for DeleteFileSetStep {
     }
 }
 
+/// Marks the file set as deleted in database without removing it so that it can be restored
+/// until the trash retention period has passed.
+pub struct MarkFileSetDeletedStep;
+
+#[async_trait::async_trait]
+impl PipelineStep<DeletionContext, Error> for MarkFileSetDeletedStep {
+    fn name(&self) -> &'static str {
+        "mark_file_set_deleted"
+    }
+
+    async fn execute(&self, context: &mut DeletionContext) -> StepAction<Error> {
+        tracing::info!(
+            "Marking file set with id {} as deleted",
+            context.file_set_id
+        );
+
+        let res = context
+            .repository_manager
+            .get_file_set_repository()
+            .soft_delete_file_set(context.file_set_id)
+            .await;
+
+        match res {
+            Ok(_) => StepAction::Continue,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to mark file set {} as deleted: {}",
+                    context.file_set_id,
+                    e
+                );
+                StepAction::Abort(Error::DbError(format!(
+                    "Failed to mark file set as deleted: {}",
+                    e
+                )))
+            }
+        }
+    }
+}
+
+/// Moves files that are only used by this file set from the collection to the trash directory.
+pub struct MoveFilesToTrashStep;
+
+#[async_trait::async_trait]
+impl PipelineStep<DeletionContext, Error> for MoveFilesToTrashStep {
+    fn name(&self) -> &'static str {
+        "move_files_to_trash"
+    }
+
+    fn should_execute(&self, context: &DeletionContext) -> bool {
+        context.has_deletable_files()
+    }
+
+    async fn execute(&self, context: &mut DeletionContext) -> StepAction<Error> {
+        tracing::info!(
+            file_set_id = context.file_set_id,
+            "Moving files of file set to trash"
+        );
+
+        let total_files = context
+            .deletion_results
+            .values()
+            .filter(|f| f.is_deletable)
+            .count();
+
+        for (index, deletion_result) in context
+            .deletion_results
+            .values_mut()
+            .filter(|f| f.is_deletable)
+            .enumerate()
+        {
+            let Some(archive_name) = &deletion_result.file_info.archive_file_name else {
+                tracing::warn!(
+                    file_info_id = deletion_result.file_info.id,
+                    "File info does not have an archive file name, skipping move to trash.",
+                );
+                continue;
+            };
+            send_file_progress(
+                context.progress_tx.as_ref(),
+                archive_name,
+                index + 1,
+                total_files,
+            );
+            let file_type = &deletion_result.file_info.file_type;
+            let file_path = context.settings.get_file_path(file_type, archive_name);
+            let trash_path = context
+                .settings
+                .get_trash_file_path(file_type, archive_name);
+            deletion_result.file_path = Some(trash_path.to_string_lossy().to_string());
+
+            if !context.fs_ops.exists(&file_path) {
+                tracing::info!(
+                    path = %file_path.display(),
+                    "File does not exist, nothing to move to trash."
+                );
+                deletion_result.moved_to_trash = Some(true);
+                continue;
+            }
+
+            match context.fs_ops.move_file(&file_path, &trash_path) {
+                Ok(_) => {
+                    tracing::info!(
+                        from = %file_path.display(),
+                        to = %trash_path.display(),
+                        "Moved file to trash"
+                    );
+                    deletion_result.moved_to_trash = Some(true);
+                }
+                Err(e) => {
+                    tracing::error!(path = %file_path.display(), error = %e, "Failed to move file to trash");
+                    deletion_result.moved_to_trash = Some(false);
+                    deletion_result.error_messages.push(e.to_string());
+                }
+            }
+        }
+
+        StepAction::Continue
+    }
+}
+
+/// Moves trashed files that are not going to be deleted back to the collection.
+///
+/// When restoring a file set none of the files are marked deletable, so all of them are
+/// restored. When purging, files that have since been taken into use by other file sets are
+/// restored and the rest are left for deletion.
+pub struct RestoreFilesFromTrashStep;
+
+#[async_trait::async_trait]
+impl PipelineStep<DeletionContext, Error> for RestoreFilesFromTrashStep {
+    fn name(&self) -> &'static str {
+        "restore_files_from_trash"
+    }
+
+    fn should_execute(&self, context: &DeletionContext) -> bool {
+        context.has_deletion_candidates()
+    }
+
+    async fn execute(&self, context: &mut DeletionContext) -> StepAction<Error> {
+        for deletion_result in context
+            .deletion_results
+            .values()
+            .filter(|f| !f.is_deletable)
+        {
+            let Some(archive_name) = &deletion_result.file_info.archive_file_name else {
+                continue;
+            };
+            let file_type = &deletion_result.file_info.file_type;
+            let trash_path = context
+                .settings
+                .get_trash_file_path(file_type, archive_name);
+            if !context.fs_ops.exists(&trash_path) {
+                continue;
+            }
+
+            let file_path = context.settings.get_file_path(file_type, archive_name);
+            if let Err(e) = context.fs_ops.move_file(&trash_path, &file_path) {
+                tracing::error!(path = %trash_path.display(), error = %e, "Failed to restore file from trash");
+                return StepAction::Abort(Error::IoError(format!(
+                    "Failed to restore file {} from trash: {}",
+                    trash_path.display(),
+                    e
+                )));
+            }
+            tracing::info!(path = %file_path.display(), "Restored file from trash");
+        }
+
+        StepAction::Continue
+    }
+}
+
+/// Clears the deleted mark from the file set making it visible again.
+pub struct RestoreFileSetStep;
+
+#[async_trait::async_trait]
+impl PipelineStep<DeletionContext, Error> for RestoreFileSetStep {
+    fn name(&self) -> &'static str {
+        "restore_file_set"
+    }
+
+    async fn execute(&self, context: &mut DeletionContext) -> StepAction<Error> {
+        let res = context
+            .repository_manager
+            .get_file_set_repository()
+            .restore_file_set(context.file_set_id)
+            .await;
+
+        match res {
+            Ok(_) => {
+                tracing::info!("Restored file set {} from trash", context.file_set_id);
+                StepAction::Continue
+            }
+            Err(e) => {
+                StepAction::Abort(Error::DbError(format!("Failed to restore file set: {}", e)))
+            }
+        }
+    }
+}
+
+/// Removes the thumbnails of the deleted files from the thumbnails directory.
+///
+/// Failing to remove thumbnails doesn't abort the deletion, leftover thumbnails are only a waste
+/// of disk space.
+pub struct RemoveThumbnailsStep;
+
+#[async_trait::async_trait]
+impl PipelineStep<DeletionContext, Error> for RemoveThumbnailsStep {
+    fn name(&self) -> &'static str {
+        "remove_thumbnails"
+    }
+
+    fn should_execute(&self, context: &DeletionContext) -> bool {
+        context.has_deleted_files()
+    }
+
+    async fn execute(&self, context: &mut DeletionContext) -> StepAction<Error> {
+        let archive_file_names: Vec<String> = context
+            .deletion_results
+            .values()
+            .filter(|r| r.is_deletable && r.file_deletion_success.is_some_and(|s| s))
+            .filter_map(|r| r.file_info.archive_file_name.clone())
+            .collect();
+        let thumbnails_dir = context.settings.get_thumbnails_path();
+
+        match thumbnails::remove_thumbnails(&thumbnails_dir, &archive_file_names) {
+            Ok(removed) => tracing::info!(
+                file_set_id = context.file_set_id,
+                removed = removed,
+                "Removed thumbnails of deleted files"
+            ),
+            Err(e) => tracing::warn!(
+                file_set_id = context.file_set_id,
+                error = %e,
+                "Failed to remove thumbnails of deleted files"
+            ),
+        }
+
+        StepAction::Continue
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, path::PathBuf, sync::Arc};
@@ -204,6 +447,8 @@ mod tests {
             repository_manager: repo_manager.clone(),
             settings: settings.clone(),
             fs_ops: fs_ops.clone(),
+            from_trash: false,
+            progress_tx: None,
             deletion_results: HashMap::new(),
         };
 
@@ -241,6 +486,8 @@ mod tests {
             repository_manager: repo_manager.clone(),
             settings: settings.clone(),
             fs_ops: fs_ops.clone(),
+            from_trash: false,
+            progress_tx: None,
             deletion_results: HashMap::new(),
         };
         let step = FetchFileInfosStep;
@@ -256,6 +503,52 @@ mod tests {
         assert!(!deletion_result.is_deletable);
     }
 
+    #[async_std::test]
+    async fn test_remove_thumbnails_step() {
+        let TestSetup {
+            repo_manager,
+            fs_ops,
+            system_id,
+            file1,
+            ..
+        } = prepare_test().await;
+        let collection_root_dir =
+            std::env::temp_dir().join(format!("efm-remove-thumbnails-{}", std::process::id()));
+        let settings = Arc::new(Settings {
+            collection_root_dir: collection_root_dir.clone(),
+            ..Default::default()
+        });
+        let thumbnails_dir = settings.get_thumbnails_path();
+        std::fs::create_dir_all(&thumbnails_dir).unwrap();
+        std::fs::write(thumbnails_dir.join("file1.zst.00000000.png"), b"").unwrap();
+        std::fs::write(thumbnails_dir.join("other.zst.00000000.png"), b"").unwrap();
+
+        let file_set_id = prepare_file_set_with_files(&repo_manager, system_id, &[file1]).await;
+        let mut context = DeletionContext {
+            file_set_id,
+            repository_manager: repo_manager.clone(),
+            settings,
+            fs_ops: fs_ops.clone(),
+            from_trash: false,
+            progress_tx: None,
+            deletion_results: HashMap::new(),
+        };
+        FetchFileInfosStep.execute(&mut context).await;
+        for deletion_result in context.deletion_results.values_mut() {
+            deletion_result.is_deletable = true;
+            deletion_result.file_deletion_success = Some(true);
+        }
+
+        let step = RemoveThumbnailsStep;
+        assert!(step.should_execute(&context));
+        let action = step.execute(&mut context).await;
+
+        assert!(matches!(action, StepAction::Continue));
+        assert!(!thumbnails_dir.join("file1.zst.00000000.png").exists());
+        assert!(thumbnails_dir.join("other.zst.00000000.png").exists());
+        std::fs::remove_dir_all(collection_root_dir).unwrap();
+    }
+
     struct TestSetup {
         settings: Arc<Settings>,
         repo_manager: Arc<RepositoryManager>,
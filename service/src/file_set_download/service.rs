@@ -72,7 +72,7 @@ impl DownloadService {
             cloud_ops: None,
             fs_ops: self.fs_ops.clone(),
             export_ops: Arc::new(DefaultFileExportOps),
-            thumbnail_generator: Arc::new(ThumbnailGenerator),
+            thumbnail_generator: Arc::new(ThumbnailGenerator::default()),
         };
         let mut context = DownloadContext::new(settings);
 
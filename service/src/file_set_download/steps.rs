@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
 use cloud_storage::cloud_key;
-use core_types::{IMAGE_FILE_TYPES, events::DownloadEvent};
-use file_export::{FileSetExportModel, OutputFile};
+use core_types::{FileType, events::DownloadEvent};
+use file_export::{ExportContainer, FileSetExportModel, OutputFile};
 
 use crate::{
     error::Error,
@@ -319,6 +319,10 @@ impl PipelineStep<DownloadContext, Error> for ExportFilesStep {
             output_dir: context.settings.temp_output_dir.clone(),
             extract_files: context.extract_files,
             exported_zip_file_name,
+            skip_existing: false,
+            container: ExportContainer::Zip,
+            zip_compression: None,
+            fail_fast: true,
         };
 
         let res = if context.extract_files {
@@ -365,7 +369,7 @@ impl PipelineStep<DownloadContext, Error> for PrepareThumbnailsStep {
 
     fn should_execute(&self, context: &DownloadContext) -> bool {
         if let Some(file_set) = &context.file_set
-            && IMAGE_FILE_TYPES.contains(&file_set.file_type)
+            && (file_set.file_type.is_image() || file_set.file_type == FileType::Manual)
             && context.extract_files
             && !context.file_output_mapping.is_empty()
         {
@@ -386,27 +390,25 @@ impl PipelineStep<DownloadContext, Error> for PrepareThumbnailsStep {
             output_dir = %output_dir.to_string_lossy(),
             "Thumbnail preparation paths"
         );
-        let res = context.thumbnail_generator.prepare_thumbnails(
+        let result = context.thumbnail_generator.prepare_thumbnails(
             &thumnail_dir,
             output_dir,
             output_mapping,
         );
-        match res {
-            Ok(thumbnail_map) => {
-                tracing::info!(
-                    thumbnail_count = thumbnail_map.len(),
-                    "Thumbnails prepared successfully"
-                );
-                context.thumbnail_path_map = thumbnail_map;
-            }
-            Err(e) => {
-                tracing::error!(
-                    error = %e,
-                    "Failed to prepare thumbnails"
-                );
-                // No need to abort the whole process for thumbnail generation failure
-            }
+        // No need to abort the whole process for thumbnail generation failures
+        for (output_file_name, error) in &result.failed {
+            tracing::error!(
+                output_file_name = %output_file_name,
+                error = %error,
+                "Failed to prepare thumbnail"
+            );
         }
+        tracing::info!(
+            thumbnail_count = result.generated.len(),
+            failed_count = result.failed.len(),
+            "Thumbnails prepared"
+        );
+        context.thumbnail_path_map = result.generated;
         StepAction::Continue
     }
 }
@@ -415,7 +417,7 @@ impl PipelineStep<DownloadContext, Error> for PrepareThumbnailsStep {
 mod tests {
     use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
-    use cloud_storage::{cloud_key, mock::MockCloudStorage};
+    use cloud_storage::{MultipartConfig, cloud_key, mock::MockCloudStorage};
     use core_types::{FileType, ImportedFile, Sha1Checksum};
     use database::{models::FileSet, repository_manager::RepositoryManager, setup_test_db};
     use file_export::{OutputFile, file_export_ops::MockFileExportOps};
@@ -638,7 +640,7 @@ mod tests {
             .cloud_ops
             .clone()
             .unwrap()
-            .upload_file(&file_path, &key, None)
+            .upload_file(&file_path, &key, &MultipartConfig::default(), None, None)
             .await
             .unwrap();
 
@@ -898,6 +900,30 @@ mod tests {
         assert!(context.thumbnail_path_map.len() == 1);
     }
 
+    #[async_std::test]
+    async fn test_prepare_thumbnails_step_executed_for_manual() {
+        let file_set = FileSet {
+            id: 1,
+            name: "Test File Set".to_string(),
+            file_type: FileType::Manual,
+            file_name: "test_file.zst".to_string(),
+            source: "".to_string(),
+        };
+
+        let (mut context, _file_export_ops) = initialize_context(true).await;
+        context.file_set = Some(file_set);
+        context.file_output_mapping = HashMap::from([(
+            "archive_file_name".to_string(),
+            OutputFile {
+                output_file_name: "manual.pdf".to_string(),
+                checksum: Sha1Checksum::from([1; 20]),
+            },
+        )]);
+
+        let step = PrepareThumbnailsStep;
+        assert!(step.should_execute(&context));
+    }
+
     async fn initialize_context(extract_files: bool) -> (DownloadContext, Arc<MockFileExportOps>) {
         let pool = Arc::new(setup_test_db().await);
         let repository_manager = Arc::new(RepositoryManager::new(pool));
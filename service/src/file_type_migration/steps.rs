@@ -572,7 +572,7 @@ impl PipelineStep<FileTypeMigrationContext, Error> for AddItemsToFileSetsStep {
 mod tests {
     use std::sync::Arc;
 
-    use cloud_storage::{CloudStorageOps, mock::MockCloudStorage};
+    use cloud_storage::{CloudStorageOps, MultipartConfig, mock::MockCloudStorage};
     use core_types::{CloudSyncStatus, FileType, ImportedFile, Sha1Checksum};
     use database::{models::FileInfo, repository_manager::RepositoryManager, setup_test_db};
 
@@ -933,7 +933,13 @@ mod tests {
         );
 
         cloud_ops
-            .upload_file(&file_path, &old_cloud_key, None)
+            .upload_file(
+                &file_path,
+                &old_cloud_key,
+                &MultipartConfig::default(),
+                None,
+                None,
+            )
             .await
             .unwrap();
 
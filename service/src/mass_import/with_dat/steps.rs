@@ -643,6 +643,7 @@ mod tests {
                     serial: None,
                     header: None,
                 }],
+                disks: vec![],
                 releases: vec![],
             }],
         };
@@ -70,7 +70,8 @@ impl FilesOnlyMassImportContext {
         metadata: &[ReadFile],
     ) -> FileSetImportModel {
         let file_name = file_path.file_name().unwrap().to_string_lossy().to_string();
-        let software_title = file_name_to_canonical_software_title(&file_name);
+        let software_title =
+            file_name_to_canonical_software_title(&file_name).unwrap_or_else(|| file_name.clone());
 
         FileSetImportModel {
             file_set_name: file_path.file_stem().unwrap().to_string_lossy().to_string(),
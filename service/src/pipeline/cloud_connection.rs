@@ -6,7 +6,7 @@ use crate::{
     error::Error,
     pipeline::pipeline_step::{PipelineStep, StepAction},
     settings_service::SettingsService,
-    view_models::Settings,
+    view_models::{S3Settings, Settings},
 };
 
 /// A trait for contexts that support cloud connection.
@@ -79,16 +79,6 @@ where
     }
 
     async fn execute(&self, context: &mut T) -> StepAction<Error> {
-        let s3_settings = match context.settings().s3_settings.clone() {
-            Some(settings) => settings,
-            None => {
-                eprintln!("S3 settings are not configured.");
-                return StepAction::Abort(crate::error::Error::SettingsError(
-                    "S3 settings missing".to_string(),
-                ));
-            }
-        };
-
         let credentials = match context.settings_service().load_credentials().await {
             Ok(Some(creds)) => creds,
             Ok(None) => {
@@ -106,12 +96,36 @@ where
             }
         };
 
+        // Connection details stored with the credentials are used when settings don't have them
+        let s3_settings = match context.settings().s3_settings.clone() {
+            Some(settings) => settings,
+            None => match (
+                &credentials.endpoint,
+                &credentials.region,
+                &credentials.bucket,
+            ) {
+                (Some(endpoint), Some(region), Some(bucket)) => S3Settings {
+                    endpoint: endpoint.clone(),
+                    region: region.clone(),
+                    bucket: bucket.clone(),
+                    ..Default::default()
+                },
+                _ => {
+                    eprintln!("S3 settings are not configured.");
+                    return StepAction::Abort(crate::error::Error::SettingsError(
+                        "S3 settings missing".to_string(),
+                    ));
+                }
+            },
+        };
+
         let cloud_ops_res = S3CloudStorage::get_bucket(
             s3_settings.endpoint.as_str(),
             s3_settings.region.as_str(),
             s3_settings.bucket.as_str(),
             credentials.access_key_id.as_str(),
             credentials.secret_access_key.as_str(),
+            s3_settings.addressing,
         );
 
         match cloud_ops_res {
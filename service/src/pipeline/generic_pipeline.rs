@@ -1,6 +1,14 @@
 use super::pipeline_step::{PipelineStep, StepAction};
 use crate::error::Error;
 
+/// Status of a step reported to the listener of [`Pipeline::execute_with_listener`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    Started,
+    Completed,
+    Skipped,
+}
+
 /// A generic pipeline that executes a series of steps in sequence.
 ///
 /// The pipeline pattern provides a structured way to organize complex operations
@@ -68,21 +76,38 @@ where
     /// `Ok(())` if all steps complete successfully or a step returns `Skip`,
     /// `Err(error)` if a step returns `Abort(error)`
     pub async fn execute(&self, context: &mut T) -> Result<(), E> {
+        self.execute_with_listener(context, |_, _| {}).await
+    }
+
+    /// Execute all steps like [`Pipeline::execute`], reporting the status of each step to
+    /// `listener`.
+    ///
+    /// The listener is called with `Started` before a step executes and `Completed` after it
+    /// returns `Continue` or `Skip`. Steps that are not executed based on `should_execute()` are
+    /// reported as `Skipped`. Aborted steps are not reported as completed.
+    pub async fn execute_with_listener<F>(&self, context: &mut T, listener: F) -> Result<(), E>
+    where
+        F: Fn(&'static str, StepStatus) + Send + Sync,
+    {
         for step in &self.steps {
             if !step.should_execute(context) {
                 tracing::info!("Step {} will be skipped based on context", step.name());
+                listener(step.name(), StepStatus::Skipped);
                 continue;
             }
 
             tracing::info!("Executing step: {}", step.name());
+            listener(step.name(), StepStatus::Started);
 
             match step.execute(context).await {
                 StepAction::Continue => {
+                    listener(step.name(), StepStatus::Completed);
                     // Proceed to next step
                     continue;
                 }
                 StepAction::Skip => {
                     tracing::info!("Step {} requested skip - stopping pipeline", step.name());
+                    listener(step.name(), StepStatus::Completed);
                     return Ok(());
                 }
                 StepAction::Abort(error) => {
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use core_types::SettingName;
-use credentials_storage::{CloudCredentials, CredentialsError};
+use credentials_storage::{CloudCredentials, CredentialsError, DEFAULT_PROFILE};
 use database::repository_manager::RepositoryManager;
 
 use crate::{error::Error, view_models::Settings};
@@ -11,6 +11,8 @@ pub struct SettingsSaveModel {
     pub region: String,
     pub bucket: String,
     pub sync_enabled: bool,
+    /// Use path-style bucket addressing instead of virtual-hosted-style addressing
+    pub path_style: bool,
     pub access_key_id: String,
     pub secret_access_key: String,
     pub collection_root_dir: Option<PathBuf>,
@@ -52,9 +54,9 @@ impl SettingsService {
     /// with environment variable fallback for credentials.
     pub async fn save_settings(&self, settings: SettingsSaveModel) -> Result<(), Error> {
         let mut settings_map = HashMap::from([
-            (SettingName::S3Bucket, settings.bucket),
-            (SettingName::S3EndPoint, settings.endpoint),
-            (SettingName::S3Region, settings.region),
+            (SettingName::S3Bucket, settings.bucket.clone()),
+            (SettingName::S3EndPoint, settings.endpoint.clone()),
+            (SettingName::S3Region, settings.region.clone()),
             (
                 SettingName::S3FileSyncEnabled,
                 if settings.sync_enabled {
@@ -63,6 +65,7 @@ impl SettingsService {
                     "false".to_string()
                 },
             ),
+            (SettingName::S3PathStyle, settings.path_style.to_string()),
         ]);
 
         if let Some(collection_root_dir) = settings.collection_root_dir {
@@ -101,12 +104,17 @@ impl SettingsService {
 
         // Store credentials only if both are provided and non-empty
         if !settings.access_key_id.is_empty() && !settings.secret_access_key.is_empty() {
+            // Connection details are stored with the credentials so that the whole cloud
+            // configuration can be restored from the keyring
             let creds = CloudCredentials {
                 access_key_id: settings.access_key_id.clone(),
                 secret_access_key: settings.secret_access_key.clone(),
+                endpoint: Some(settings.endpoint.clone()).filter(|e| !e.is_empty()),
+                region: Some(settings.region.clone()).filter(|r| !r.is_empty()),
+                bucket: Some(settings.bucket.clone()).filter(|b| !b.is_empty()),
             };
 
-            if let Err(e) = credentials_storage::store_credentials(&creds) {
+            if let Err(e) = credentials_storage::store_credentials(DEFAULT_PROFILE, &creds) {
                 // Log error but don't fail - credentials can be provided via env vars
                 eprintln!("Warning: Failed to store credentials in keyring: {}", e);
             }
@@ -164,7 +172,7 @@ impl SettingsService {
     /// }
     /// ```
     pub async fn load_credentials(&self) -> Result<Option<CloudCredentials>, Error> {
-        match credentials_storage::load_credentials_with_fallback() {
+        match credentials_storage::load_credentials_with_fallback(DEFAULT_PROFILE) {
             Ok(creds) => Ok(Some(creds)),
             Err(CredentialsError::NoCredentials) => Ok(None),
             Err(e) => Err(Error::SettingsError(format!(
@@ -195,7 +203,7 @@ impl SettingsService {
     /// Returns an error if the keyring is not accessible. Does not error if
     /// credentials don't exist (operation is idempotent).
     pub async fn delete_credentials(&self) -> Result<(), Error> {
-        credentials_storage::delete_credentials()
+        credentials_storage::delete_credentials(DEFAULT_PROFILE)
             .map_err(|e| Error::SettingsError(format!("Failed to delete credentials: {}", e)))
     }
 }
@@ -203,12 +211,13 @@ impl SettingsService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cloud_storage::BucketAddressing;
     use database::setup_test_db;
 
     #[async_std::test]
     async fn test_save_and_load_settings() {
         // Clean up any test credentials before starting
-        credentials_storage::delete_credentials().ok();
+        credentials_storage::delete_credentials(DEFAULT_PROFILE).ok();
 
         let pool = Arc::new(setup_test_db().await);
         let repo_manager = Arc::new(RepositoryManager::new(pool));
@@ -219,6 +228,7 @@ mod tests {
             region: "us-east-1".to_string(),
             bucket: "my-bucket".to_string(),
             sync_enabled: true,
+            path_style: false,
             access_key_id: "test-access-key".to_string(),
             secret_access_key: "test-secret-key".to_string(),
             collection_root_dir: Some(PathBuf::from("/path/to/collection")),
@@ -240,6 +250,10 @@ mod tests {
         );
         assert_eq!(settings.s3_settings.as_ref().unwrap().region, "us-east-1");
         assert_eq!(settings.s3_settings.as_ref().unwrap().bucket, "my-bucket");
+        assert_eq!(
+            settings.s3_settings.as_ref().unwrap().addressing,
+            BucketAddressing::VirtualHosted
+        );
         assert_eq!(
             settings.collection_root_dir,
             PathBuf::from("/path/to/collection")
@@ -254,7 +268,7 @@ mod tests {
         );
 
         // Clean up test credentials after test
-        credentials_storage::delete_credentials().ok();
+        credentials_storage::delete_credentials(DEFAULT_PROFILE).ok();
     }
 
     #[async_std::test]
@@ -2,8 +2,10 @@ use std::{
     collections::HashMap,
     fmt::{self, Display, Formatter},
     path::PathBuf,
+    time::Duration,
 };
 
+use cloud_storage::BucketAddressing;
 use core_types::{
     ArgumentType, DocumentType, FileSize, FileType, SettingName, Sha1Checksum, item_type::ItemType,
 };
@@ -55,8 +57,13 @@ pub struct S3Settings {
     pub endpoint: String,
     pub region: String,
     pub bucket: String,
+    pub addressing: BucketAddressing,
 }
 
+/// Number of days a soft-deleted file set is kept in trash before it's purged.
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+pub const DEFAULT_MAX_CONCURRENT_UPLOADS: usize = 4;
+
 #[derive(Debug, Clone, Default)]
 pub struct Settings {
     pub collection_root_dir: PathBuf,
@@ -65,6 +72,10 @@ pub struct Settings {
     pub s3_sync_enabled: bool,
     pub libretro_core_dir: Option<PathBuf>,
     pub libretro_system_dir: Option<PathBuf>,
+    pub trash_retention_days: Option<u32>,
+    pub max_concurrent_uploads: Option<usize>,
+    pub executable_timeout_secs: Option<u64>,
+    pub upload_part_size_mb: Option<usize>,
 }
 
 impl Settings {
@@ -84,6 +95,49 @@ impl Settings {
     pub fn get_thumbnails_path(&self) -> PathBuf {
         self.collection_root_dir.join("thumbnails")
     }
+
+    /// Get the path to the trash directory where files of soft-deleted file sets are kept
+    pub fn get_trash_path(&self) -> PathBuf {
+        self.collection_root_dir.join("trash")
+    }
+
+    /// Get the full path to a specific file within the trash directory.
+    /// Mirrors the layout of `get_file_path` under the trash directory.
+    pub fn get_trash_file_path(&self, file_type: &FileType, archive_file_name: &str) -> PathBuf {
+        self.get_trash_path()
+            .join(file_type.dir_name())
+            .join(archive_file_name)
+            .with_extension("zst")
+    }
+
+    /// Number of days soft-deleted file sets are kept in trash before purging
+    pub fn trash_retention_days(&self) -> u32 {
+        self.trash_retention_days
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+    }
+
+    /// Number of files uploaded at the same time during cloud sync. 1 uploads files one at a time.
+    pub fn max_concurrent_uploads(&self) -> usize {
+        self.max_concurrent_uploads
+            .filter(|&uploads| uploads > 0)
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_UPLOADS)
+    }
+
+    /// Size in bytes of the parts files are uploaded in during cloud sync. Defaults to the
+    /// smallest part size S3 accepts.
+    pub fn upload_part_size(&self) -> usize {
+        self.upload_part_size_mb
+            .map_or(cloud_storage::MIN_MULTIPART_CHUNK_SIZE, |mb| {
+                mb.saturating_mul(1024 * 1024)
+            })
+    }
+
+    /// Time after which emulators and viewers are killed, `None` if they may run indefinitely
+    pub fn executable_timeout(&self) -> Option<Duration> {
+        self.executable_timeout_secs
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+    }
 }
 
 impl From<HashMap<String, String>> for Settings {
@@ -95,11 +149,17 @@ impl From<HashMap<String, String>> for Settings {
         let s3_endpoint = map.get(SettingName::S3EndPoint.as_str());
         let s3_region = map.get(SettingName::S3Region.as_str());
         let s3_bucket = map.get(SettingName::S3Bucket.as_str());
+        // Path style addressing is used unless explicitly disabled
+        let addressing = match map.get(SettingName::S3PathStyle.as_str()) {
+            Some(v) if v == "false" => BucketAddressing::VirtualHosted,
+            _ => BucketAddressing::PathStyle,
+        };
         let s3_settings = match (s3_endpoint, s3_region, s3_bucket) {
             (Some(endpoint), Some(region), Some(bucket)) => Some(S3Settings {
                 endpoint: endpoint.clone(),
                 region: region.clone(),
                 bucket: bucket.clone(),
+                addressing,
             }),
             _ => None,
         };
@@ -113,6 +173,18 @@ impl From<HashMap<String, String>> for Settings {
         let libretro_system_dir = map
             .get(SettingName::LibretroSystemDir.as_str())
             .map(PathBuf::from);
+        let trash_retention_days = map
+            .get(SettingName::TrashRetentionDays.as_str())
+            .and_then(|v| v.parse().ok());
+        let max_concurrent_uploads = map
+            .get(SettingName::MaxConcurrentUploads.as_str())
+            .and_then(|v| v.parse().ok());
+        let executable_timeout_secs = map
+            .get(SettingName::ExecutableTimeoutSecs.as_str())
+            .and_then(|v| v.parse().ok());
+        let upload_part_size_mb = map
+            .get(SettingName::UploadPartSizeMb.as_str())
+            .and_then(|v| v.parse().ok());
         Self {
             collection_root_dir,
             temp_output_dir: std::env::temp_dir(),
@@ -120,6 +192,10 @@ impl From<HashMap<String, String>> for Settings {
             s3_sync_enabled,
             libretro_core_dir,
             libretro_system_dir,
+            trash_retention_days,
+            max_concurrent_uploads,
+            executable_timeout_secs,
+            upload_part_size_mb,
         }
     }
 }
@@ -1,10 +1,22 @@
 use std::{
     collections::HashMap,
+    io::{BufRead, Cursor, Read, Seek},
+    num::NonZeroUsize,
     path::{Path, PathBuf},
+    sync::{
+        Mutex, OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
 };
 
+use core_types::{DocumentType, Sha1Checksum, sha1_bytes_to_hex_string};
 use file_export::{FileSetExportModel, OutputFile};
-use image::GenericImageView;
+use image::{
+    DynamicImage, GenericImageView, ImageDecoder, ImageFormat, ImageReader, ImageResult, RgbImage,
+    metadata::Orientation,
+};
+use pdfium_render::prelude::{PdfRenderConfig, Pdfium};
 
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
@@ -25,16 +37,103 @@ impl Display for ThumbnailsError {
 
 pub type ThumbnailPathMap = HashMap<String, PathBuf>;
 
+/// Outcome of preparing thumbnails for a set of files.
+#[derive(Debug, Default)]
+pub struct ThumbnailResult {
+    pub generated: ThumbnailPathMap,
+    /// Output file names and errors of the files for which a thumbnail couldn't be prepared
+    pub failed: Vec<(String, String)>,
+}
+
 pub trait ThumbnailOps: Send + Sync {
     fn prepare_thumbnails(
         &self,
         thumbnails_dir: &Path,
         output_dir: &Path,
         output_mapping: &HashMap<String, OutputFile>,
-    ) -> Result<ThumbnailPathMap, ThumbnailsError>;
+    ) -> ThumbnailResult;
+}
+
+/// How images are fitted to the thumbnail bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThumbnailMode {
+    /// Resize to fit in the bounds, preserving the aspect ratio
+    #[default]
+    Fit,
+    /// Crop the center square and resize it to the smaller of the bounds
+    CropSquare,
+}
+
+/// Image format of the thumbnail files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThumbnailFormat {
+    #[default]
+    Png,
+    /// Lossless WebP, smaller than PNG for most images
+    Webp,
+    Jpeg,
+}
+
+impl ThumbnailFormat {
+    pub const ALL: [ThumbnailFormat; 3] = [
+        ThumbnailFormat::Png,
+        ThumbnailFormat::Webp,
+        ThumbnailFormat::Jpeg,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Png => "png",
+            ThumbnailFormat::Webp => "webp",
+            ThumbnailFormat::Jpeg => "jpg",
+        }
+    }
+
+    fn image_format(&self) -> ImageFormat {
+        match self {
+            ThumbnailFormat::Png => ImageFormat::Png,
+            ThumbnailFormat::Webp => ImageFormat::WebP,
+            ThumbnailFormat::Jpeg => ImageFormat::Jpeg,
+        }
+    }
+}
+
+/// Settings for generating thumbnails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThumbnailConfig {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub mode: ThumbnailMode,
+    pub format: ThumbnailFormat,
+    /// Rotate and flip images according to their EXIF orientation tag
+    pub apply_exif_orientation: bool,
+    /// Maximum number of thumbnails generated in parallel, number of available CPUs if `None`
+    pub max_workers: Option<NonZeroUsize>,
+}
+
+impl Default for ThumbnailConfig {
+    fn default() -> Self {
+        Self {
+            max_width: 100,
+            max_height: 100,
+            mode: ThumbnailMode::default(),
+            format: ThumbnailFormat::default(),
+            apply_exif_orientation: true,
+            max_workers: None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ThumbnailGenerator {
+    config: ThumbnailConfig,
 }
 
-pub struct ThumbnailGenerator;
+impl ThumbnailGenerator {
+    pub fn new(config: ThumbnailConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl ThumbnailOps for ThumbnailGenerator {
     fn prepare_thumbnails(
@@ -42,8 +141,8 @@ impl ThumbnailOps for ThumbnailGenerator {
         thumbnails_dir: &Path,
         output_dir: &Path,
         output_mapping: &HashMap<String, OutputFile>,
-    ) -> Result<ThumbnailPathMap, ThumbnailsError> {
-        prepare_thumbnails_from_output_dir(thumbnails_dir, output_dir, output_mapping)
+    ) -> ThumbnailResult {
+        prepare_thumbnails_from_output_dir(thumbnails_dir, output_dir, output_mapping, &self.config)
     }
 }
 
@@ -56,7 +155,7 @@ impl ThumbnailOps for ThumbnailGeneratorMock {
         thumbnails_dir: &Path,
         _output_dir: &Path,
         output_mapping: &HashMap<String, OutputFile>,
-    ) -> Result<ThumbnailPathMap, ThumbnailsError> {
+    ) -> ThumbnailResult {
         println!(
             "Mock preparing thumbnails in directory: {}",
             thumbnails_dir.display()
@@ -68,73 +167,441 @@ impl ThumbnailOps for ThumbnailGeneratorMock {
                 archive_file_name,
                 thumbnails_dir.display()
             );
-            let thumbnail_path = thumbnails_dir.join(format!("{}.png", archive_file_name));
+            let thumbnail_path = thumbnail_path(
+                thumbnails_dir,
+                archive_file_name,
+                &output_file.checksum,
+                ThumbnailFormat::Png,
+            );
             thumbnail_path_mapp.insert(output_file.output_file_name.clone(), thumbnail_path);
         }
-        Ok(thumbnail_path_mapp)
+        ThumbnailResult {
+            generated: thumbnail_path_mapp,
+            failed: vec![],
+        }
     }
 }
 
 pub fn prepare_thumbnails(
     export_model: &FileSetExportModel,
     collection_root_dir: &Path,
-) -> Result<ThumbnailPathMap, ThumbnailsError> {
+    config: &ThumbnailConfig,
+) -> ThumbnailResult {
     let thumbnails_dir = collection_root_dir.join("thumbnails");
     prepare_thumbnails_from_output_dir(
         thumbnails_dir.as_path(),
         &export_model.output_dir,
         &export_model.output_mapping,
+        config,
     )
 }
 
+/// Generate thumbnails for the exported images in parallel with at most `config.max_workers`
+/// threads. An existing thumbnail is reused without checking its size.
+///
+/// All files are attempted even if some of them fail, failures are returned in the result.
 pub fn prepare_thumbnails_from_output_dir(
     thumbnails_dir: &Path,
     output_dir: &Path,
     output_mapping: &HashMap<String, OutputFile>,
-) -> Result<ThumbnailPathMap, ThumbnailsError> {
-    let exported_files_dir = &output_dir;
-    let mut thumbnail_path_mapp: HashMap<String, PathBuf> = HashMap::new();
-    for (archive_file_name, output_file) in output_mapping {
-        let thumbnail_path = thumbnails_dir.join(format!("{}.png", archive_file_name));
-        let exported_file_path = exported_files_dir.join(&output_file.output_file_name);
-
-        if thumbnail_path.exists() {
-            thumbnail_path_mapp.insert(output_file.output_file_name.clone(), thumbnail_path);
-        } else {
-            println!(
-                "Generating thumbnail for archive file name '{}' output file name '{}' at '{}'",
-                archive_file_name,
-                exported_file_path.display(),
-                thumbnail_path.display()
-            );
+    config: &ThumbnailConfig,
+) -> ThumbnailResult {
+    let entries: Vec<(&String, &OutputFile)> = output_mapping.iter().collect();
+    let max_workers = config
+        .max_workers
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+    let worker_count = max_workers.min(entries.len());
+    let next_entry = AtomicUsize::new(0);
+    let result = Mutex::new(ThumbnailResult::default());
+
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| {
+                while let Some((archive_file_name, output_file)) =
+                    entries.get(next_entry.fetch_add(1, Ordering::Relaxed))
+                {
+                    let outcome = prepare_thumbnail(
+                        thumbnails_dir,
+                        output_dir,
+                        archive_file_name,
+                        output_file,
+                        config,
+                    );
+                    let mut result = result.lock().unwrap();
+                    let output_file_name = output_file.output_file_name.clone();
+                    match outcome {
+                        Ok(thumbnail_path) => {
+                            result.generated.insert(output_file_name, thumbnail_path);
+                        }
+                        Err(e) => result.failed.push((output_file_name, e.to_string())),
+                    }
+                }
+            });
+        }
+    });
+
+    let mut result = result.into_inner().unwrap();
+    result.failed.sort_by(|a, b| a.0.cmp(&b.0));
+    result
+}
+
+fn prepare_thumbnail(
+    thumbnails_dir: &Path,
+    output_dir: &Path,
+    archive_file_name: &str,
+    output_file: &OutputFile,
+    config: &ThumbnailConfig,
+) -> Result<PathBuf, ThumbnailsError> {
+    let thumbnail_path = thumbnail_path(
+        thumbnails_dir,
+        archive_file_name,
+        &output_file.checksum,
+        config.format,
+    );
+    let exported_file_path = output_dir.join(&output_file.output_file_name);
+
+    if thumbnail_path.exists() {
+        return Ok(thumbnail_path);
+    }
+    println!(
+        "Generating thumbnail for archive file name '{}' output file name '{}' at '{}'",
+        archive_file_name,
+        exported_file_path.display(),
+        thumbnail_path.display()
+    );
 
-            let image = image::open(&exported_file_path).map_err(|err| {
+    let (image, thumbnail_path) = if let Some(document_type) = document_type(&exported_file_path) {
+        match render_document(&exported_file_path, document_type, config) {
+            Ok(image) => (image, thumbnail_path),
+            Err(err) => {
+                eprintln!("{}, using placeholder thumbnail", err);
+                // The placeholder isn't cached as the thumbnail, so that the document is
+                // rendered again next time, e.g. after the Pdfium library has been installed
+                let placeholder_path =
+                    placeholder_thumbnail_path(thumbnails_dir, archive_file_name, config.format);
+                (placeholder_image(config), placeholder_path)
+            }
+        }
+    } else {
+        let image = ImageReader::open(&exported_file_path)
+            .map_err(image::ImageError::from)
+            .and_then(|reader| decode_image(reader, config))
+            .map_err(|err| {
                 ThumbnailsError::IoError(format!(
                     "Failed opening image {} with error: {}",
                     exported_file_path.display(),
                     &err
                 ))
             })?;
+        (image, thumbnail_path)
+    };
 
-            let thumbnail = image.thumbnail(100, 100);
-            std::fs::create_dir_all(thumbnails_dir).map_err(|_| {
-                ThumbnailsError::IoError(format!(
-                    "Failed creating directory: {}",
-                    &thumbnails_dir.display()
-                ))
-            })?;
+    save_thumbnail(&image, thumbnails_dir, &thumbnail_path, config)?;
+    Ok(thumbnail_path)
+}
+
+/// Generate a thumbnail for an image compressed with zstd, decompressing it to memory instead of
+/// exporting it first. `checksum` is the checksum of the image, see [`thumbnail_path`]. Returns
+/// the path of the thumbnail, an existing thumbnail is reused.
+pub fn prepare_thumbnail_from_zst(
+    zst_path: &Path,
+    thumbnails_dir: &Path,
+    archive_file_name: &str,
+    checksum: &Sha1Checksum,
+    config: &ThumbnailConfig,
+) -> Result<PathBuf, ThumbnailsError> {
+    let thumbnail_path = thumbnail_path(thumbnails_dir, archive_file_name, checksum, config.format);
+    if thumbnail_path.exists() {
+        return Ok(thumbnail_path);
+    }
+
+    let read_error = |err: &dyn Display| {
+        ThumbnailsError::IoError(format!(
+            "Failed decompressing {} with error: {}",
+            zst_path.display(),
+            err
+        ))
+    };
+    let file = std::fs::File::open(zst_path).map_err(|e| read_error(&e))?;
+    let mut decoder = zstd::Decoder::new(file).map_err(|e| read_error(&e))?;
+    let mut buffer = Vec::new();
+    decoder
+        .read_to_end(&mut buffer)
+        .map_err(|e| read_error(&e))?;
+
+    let image = decode_image(ImageReader::new(Cursor::new(buffer)), config).map_err(|err| {
+        ThumbnailsError::IoError(format!(
+            "Failed decoding image {} with error: {}",
+            zst_path.display(),
+            &err
+        ))
+    })?;
+    save_thumbnail(&image, thumbnails_dir, &thumbnail_path, config)?;
+    Ok(thumbnail_path)
+}
+
+/// Decode an image guessing its format from the content, applying the EXIF orientation when
+/// enabled in the config.
+fn decode_image<R: BufRead + Seek>(
+    reader: ImageReader<R>,
+    config: &ThumbnailConfig,
+) -> ImageResult<DynamicImage> {
+    let mut decoder = reader.with_guessed_format()?.into_decoder()?;
+    // Malformed EXIF data shouldn't prevent the thumbnail, the image is used as is instead
+    let orientation = if config.apply_exif_orientation {
+        decoder.orientation().unwrap_or(Orientation::NoTransforms)
+    } else {
+        Orientation::NoTransforms
+    };
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Number of hex characters of the image checksum included in the thumbnail file name.
+const CHECKSUM_PREFIX_LEN: usize = 8;
 
-            thumbnail.save(&thumbnail_path).map_err(|err| {
+/// Thumbnail file name is tagged with the checksum of the image, so that a changed image gets a
+/// new thumbnail instead of the cached one: `{archive_file_name}.{sha1_prefix}.{extension}`.
+pub fn thumbnail_path(
+    thumbnails_dir: &Path,
+    archive_file_name: &str,
+    checksum: &Sha1Checksum,
+    format: ThumbnailFormat,
+) -> PathBuf {
+    let checksum = sha1_bytes_to_hex_string(checksum);
+    thumbnails_dir.join(format!(
+        "{}.{}.{}",
+        archive_file_name,
+        &checksum[..CHECKSUM_PREFIX_LEN],
+        format.extension()
+    ))
+}
+
+/// Suffix of the placeholder thumbnail of a document that couldn't be rendered.
+const PLACEHOLDER_SUFFIX: &str = ".placeholder";
+
+/// Placeholder thumbnail file name isn't looked up as a cached thumbnail:
+/// `{archive_file_name}.placeholder.{extension}`.
+fn placeholder_thumbnail_path(
+    thumbnails_dir: &Path,
+    archive_file_name: &str,
+    format: ThumbnailFormat,
+) -> PathBuf {
+    thumbnails_dir.join(format!(
+        "{}{}.{}",
+        archive_file_name,
+        PLACEHOLDER_SUFFIX,
+        format.extension()
+    ))
+}
+
+/// Delete the thumbnails of the given archive files in any of the supported formats, checksum
+/// tagged, placeholder and legacy `{archive_file_name}.{extension}` names. Returns the number of
+/// removed thumbnails.
+pub fn remove_thumbnails(
+    thumbnails_dir: &Path,
+    archive_file_names: &[String],
+) -> Result<usize, ThumbnailsError> {
+    let entries = match std::fs::read_dir(thumbnails_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => {
+            return Err(ThumbnailsError::IoError(format!(
+                "Failed to read thumbnails directory {}: {}",
+                thumbnails_dir.display(),
+                e
+            )));
+        }
+    };
+
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| ThumbnailsError::IoError(e.to_string()))?
+            .path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(archive_file_name) = thumbnail_archive_file_name(file_name) else {
+            continue;
+        };
+        if archive_file_names
+            .iter()
+            .any(|name| name == archive_file_name)
+        {
+            std::fs::remove_file(&path).map_err(|e| {
                 ThumbnailsError::IoError(format!(
-                    "Failed saving thumbnail to {} with error: {}",
-                    thumbnail_path.display(),
-                    &err
+                    "Failed to remove thumbnail {}: {}",
+                    path.display(),
+                    e
                 ))
             })?;
-            thumbnail_path_mapp.insert(output_file.output_file_name.clone(), thumbnail_path);
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Archive file name part of a thumbnail file name, `None` if the file is not a thumbnail.
+fn thumbnail_archive_file_name(file_name: &str) -> Option<&str> {
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if !ThumbnailFormat::ALL
+        .iter()
+        .any(|format| format.extension() == extension)
+    {
+        return None;
+    }
+    if let Some(archive_file_name) = stem.strip_suffix(PLACEHOLDER_SUFFIX) {
+        return Some(archive_file_name);
+    }
+    match stem.rsplit_once('.') {
+        Some((archive_file_name, checksum))
+            if checksum.len() == CHECKSUM_PREFIX_LEN
+                && checksum.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            Some(archive_file_name)
+        }
+        _ => Some(stem),
+    }
+}
+
+fn save_thumbnail(
+    image: &DynamicImage,
+    thumbnails_dir: &Path,
+    thumbnail_path: &Path,
+    config: &ThumbnailConfig,
+) -> Result<(), ThumbnailsError> {
+    let thumbnail = match config.mode {
+        ThumbnailMode::Fit => image.thumbnail(config.max_width, config.max_height),
+        ThumbnailMode::CropSquare => {
+            let (width, height) = image.dimensions();
+            let side = width.min(height);
+            let size = config.max_width.min(config.max_height);
+            image
+                .crop_imm((width - side) / 2, (height - side) / 2, side, side)
+                .thumbnail_exact(size, size)
         }
+    };
+    std::fs::create_dir_all(thumbnails_dir).map_err(|_| {
+        ThumbnailsError::IoError(format!(
+            "Failed creating directory: {}",
+            &thumbnails_dir.display()
+        ))
+    })?;
+
+    // JPEG doesn't support transparency
+    let thumbnail = match config.format {
+        ThumbnailFormat::Jpeg => DynamicImage::ImageRgb8(thumbnail.to_rgb8()),
+        _ => thumbnail,
+    };
+    thumbnail
+        .save_with_format(thumbnail_path, config.format.image_format())
+        .map_err(|err| {
+            ThumbnailsError::IoError(format!(
+                "Failed saving thumbnail to {} with error: {}",
+                thumbnail_path.display(),
+                &err
+            ))
+        })
+}
+
+fn document_type(path: &Path) -> Option<DocumentType> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(DocumentType::from_extension)
+}
+
+/// Render the first page of a document. Document types without a renderer get a placeholder.
+fn render_document(
+    path: &Path,
+    document_type: DocumentType,
+    config: &ThumbnailConfig,
+) -> Result<DynamicImage, ThumbnailsError> {
+    match document_type {
+        DocumentType::Pdf => render_pdf_first_page(path, config),
+        DocumentType::Cbz => read_cbz_first_page(path, config),
+        DocumentType::Txt | DocumentType::Epub => Ok(placeholder_image(config)),
     }
-    Ok(thumbnail_path_mapp)
+}
+
+/// Decode the first page of a comic book archive, the image file that sorts first by name.
+fn read_cbz_first_page(
+    cbz_path: &Path,
+    config: &ThumbnailConfig,
+) -> Result<DynamicImage, ThumbnailsError> {
+    let read_error = |err: &dyn Display| {
+        ThumbnailsError::IoError(format!(
+            "Failed reading CBZ {} with error: {}",
+            cbz_path.display(),
+            err
+        ))
+    };
+    let file = std::fs::File::open(cbz_path).map_err(|e| read_error(&e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| read_error(&e))?;
+    let first_page = archive
+        .file_names()
+        .filter(|name| ImageFormat::from_path(name).is_ok())
+        .min()
+        .map(str::to_string)
+        .ok_or_else(|| read_error(&"no images in archive"))?;
+    let mut buffer = Vec::new();
+    archive
+        .by_name(&first_page)
+        .map_err(|e| read_error(&e))?
+        .read_to_end(&mut buffer)
+        .map_err(|e| read_error(&e))?;
+    decode_image(ImageReader::new(Cursor::new(buffer)), config).map_err(|e| read_error(&e))
+}
+
+/// Pdfium bound to the library installed in the system. Binding loads the dynamic library, so
+/// it's done once and the instance is shared by all renders.
+fn pdfium() -> Result<&'static Pdfium, &'static str> {
+    static PDFIUM: OnceLock<Result<Pdfium, String>> = OnceLock::new();
+    PDFIUM
+        .get_or_init(|| {
+            Pdfium::bind_to_system_library()
+                .map(Pdfium::new)
+                .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(String::as_str)
+}
+
+/// Render the first page of a PDF document with the Pdfium library installed in the system.
+fn render_pdf_first_page(
+    pdf_path: &Path,
+    config: &ThumbnailConfig,
+) -> Result<DynamicImage, ThumbnailsError> {
+    let render_error = |err: &dyn Display| {
+        ThumbnailsError::IoError(format!(
+            "Failed rendering PDF {} with error: {}",
+            pdf_path.display(),
+            err
+        ))
+    };
+    let document = pdfium()
+        .map_err(|e| render_error(&e))?
+        .load_pdf_from_file(pdf_path, None)
+        .map_err(|e| render_error(&e))?;
+    let page = document.pages().first().map_err(|e| render_error(&e))?;
+    let render_config = PdfRenderConfig::new()
+        .set_target_width(config.max_width as i32)
+        .set_maximum_height(config.max_height as i32);
+    let bitmap = page
+        .render_with_config(&render_config)
+        .map_err(|e| render_error(&e))?;
+    Ok(bitmap.as_image())
+}
+
+/// Blank page used as the thumbnail of a document that can't be rendered.
+fn placeholder_image(config: &ThumbnailConfig) -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_pixel(
+        config.max_width,
+        config.max_height,
+        image::Rgb([230, 230, 230]),
+    ))
 }
 
 pub fn get_image_size(image_path: &Path) -> Result<(u32, u32), ThumbnailsError> {
@@ -147,3 +614,361 @@ pub fn get_image_size(image_path: &Path) -> Result<(u32, u32), ThumbnailsError>
     })?;
     Ok(image.dimensions())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("efm-thumbnails-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_image(dir: &Path, file_name: &str, width: u32, height: u32) -> OutputFile {
+        image::RgbImage::from_fn(width, height, |x, y| image::Rgb([x as u8, y as u8, 128]))
+            .save(dir.join(file_name))
+            .unwrap();
+        OutputFile {
+            output_file_name: file_name.to_string(),
+            checksum: [0; 20],
+        }
+    }
+
+    #[test]
+    fn test_thumbnail_with_custom_size() {
+        let dir = temp_dir("custom-size");
+        let output_dir = dir.join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let output_mapping = HashMap::from([(
+            "cover".to_string(),
+            write_image(&output_dir, "cover.png", 1024, 512),
+        )]);
+        let generator = ThumbnailGenerator::new(ThumbnailConfig {
+            max_width: 256,
+            max_height: 256,
+            ..Default::default()
+        });
+
+        let thumbnails = generator
+            .prepare_thumbnails(&dir.join("thumbnails"), &output_dir, &output_mapping)
+            .generated;
+
+        let thumbnail_path = &thumbnails["cover.png"];
+        assert_eq!(get_image_size(thumbnail_path).unwrap(), (256, 128));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_pdf_thumbnail() {
+        let dir = temp_dir("pdf");
+        let output_dir = Path::new("example-data");
+        let output_mapping = HashMap::from([(
+            "manual".to_string(),
+            OutputFile {
+                output_file_name: "manual.pdf".to_string(),
+                checksum: [0; 20],
+            },
+        )]);
+
+        let thumbnails = ThumbnailGenerator::default()
+            .prepare_thumbnails(&dir, output_dir, &output_mapping)
+            .generated;
+
+        let thumbnail_path = &thumbnails["manual.pdf"];
+        let thumbnail = image::open(thumbnail_path).unwrap().to_rgb8();
+        if pdfium().is_ok() {
+            assert_eq!(thumbnail_path, &dir.join("manual.00000000.png"));
+            // The 200x300 page has a blue rectangle on a white background
+            let (width, height) = thumbnail.dimensions();
+            assert!(height == 100 && width < height);
+            let center = thumbnail.get_pixel(width / 2, height / 2);
+            assert!(center[2] > center[0] && center[2] > center[1]);
+        } else {
+            // Without the Pdfium library a placeholder is generated, but not cached
+            assert_eq!(thumbnail_path, &dir.join("manual.placeholder.png"));
+            assert!(!dir.join("manual.00000000.png").exists());
+            assert_eq!(thumbnail.dimensions(), (100, 100));
+            assert!(
+                thumbnail
+                    .pixels()
+                    .all(|pixel| *pixel == image::Rgb([230, 230, 230]))
+            );
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_thumbnail_from_zst() {
+        let dir = temp_dir("zst");
+        write_image(&dir, "cover.png", 400, 200);
+        let png = std::fs::read(dir.join("cover.png")).unwrap();
+        let zst_path = dir.join("cover.zst");
+        std::fs::write(&zst_path, zstd::encode_all(png.as_slice(), 0).unwrap()).unwrap();
+        let thumbnails_dir = dir.join("thumbnails");
+
+        let thumbnail_path = prepare_thumbnail_from_zst(
+            &zst_path,
+            &thumbnails_dir,
+            "cover",
+            &[0xab; 20],
+            &ThumbnailConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(thumbnail_path, thumbnails_dir.join("cover.abababab.png"));
+        assert_eq!(get_image_size(&thumbnail_path).unwrap(), (100, 50));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_thumbnail_does_not_abort_others() {
+        let dir = temp_dir("failures");
+        let output_dir = dir.join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        std::fs::write(output_dir.join("corrupt.png"), b"not an image").unwrap();
+        let output_mapping = HashMap::from([
+            (
+                "cover".to_string(),
+                write_image(&output_dir, "cover.png", 200, 200),
+            ),
+            (
+                "corrupt".to_string(),
+                OutputFile {
+                    output_file_name: "corrupt.png".to_string(),
+                    checksum: [1; 20],
+                },
+            ),
+        ]);
+
+        let result = ThumbnailGenerator::default().prepare_thumbnails(
+            &dir.join("thumbnails"),
+            &output_dir,
+            &output_mapping,
+        );
+
+        assert_eq!(result.generated.len(), 1);
+        assert!(result.generated["cover.png"].exists());
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, "corrupt.png");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_thumbnail_modes() {
+        let dir = temp_dir("modes");
+        let wide_image = write_image(&dir, "wide.png", 400, 200);
+        let output_mapping = HashMap::from([("wide".to_string(), wide_image)]);
+
+        for (mode, expected_size) in [
+            (ThumbnailMode::Fit, (100, 50)),
+            (ThumbnailMode::CropSquare, (100, 100)),
+        ] {
+            let thumbnails_dir = dir.join(format!("{:?}", mode));
+            let generator = ThumbnailGenerator::new(ThumbnailConfig {
+                mode,
+                ..Default::default()
+            });
+
+            let result = generator.prepare_thumbnails(&thumbnails_dir, &dir, &output_mapping);
+
+            let thumbnail_path = &result.generated["wide.png"];
+            assert_eq!(get_image_size(thumbnail_path).unwrap(), expected_size);
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_webp_thumbnail_is_smaller_than_png() {
+        let dir = temp_dir("formats");
+        let output_mapping = HashMap::from([(
+            "cover".to_string(),
+            write_image(&dir, "cover.png", 400, 400),
+        )]);
+        let mut sizes = Vec::new();
+
+        for format in [ThumbnailFormat::Png, ThumbnailFormat::Webp] {
+            let generator = ThumbnailGenerator::new(ThumbnailConfig {
+                format,
+                ..Default::default()
+            });
+
+            let result =
+                generator.prepare_thumbnails(&dir.join("thumbnails"), &dir, &output_mapping);
+
+            let thumbnail_path = &result.generated["cover.png"];
+            assert_eq!(
+                thumbnail_path,
+                &dir.join("thumbnails")
+                    .join(format!("cover.00000000.{}", format.extension()))
+            );
+            sizes.push(std::fs::metadata(thumbnail_path).unwrap().len());
+        }
+        assert!(sizes[1] < sizes[0], "{:?}", sizes);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_existing_thumbnail_is_not_regenerated() {
+        let dir = temp_dir("cached");
+        let output_dir = dir.join("output");
+        let thumbnails_dir = dir.join("thumbnails");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        std::fs::create_dir_all(&thumbnails_dir).unwrap();
+        write_image(&thumbnails_dir, "cover.00000000.png", 10, 10);
+        let output_mapping = HashMap::from([(
+            "cover".to_string(),
+            write_image(&output_dir, "cover.png", 1024, 512),
+        )]);
+
+        let thumbnails = ThumbnailGenerator::default()
+            .prepare_thumbnails(&thumbnails_dir, &output_dir, &output_mapping)
+            .generated;
+
+        assert_eq!(get_image_size(&thumbnails["cover.png"]).unwrap(), (10, 10));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_changed_checksum_generates_new_thumbnail() {
+        let dir = temp_dir("checksum");
+        let output_dir = dir.join("output");
+        let thumbnails_dir = dir.join("thumbnails");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let mut output_file = write_image(&output_dir, "cover.png", 1024, 512);
+        let generator = ThumbnailGenerator::default();
+
+        let output_mapping = HashMap::from([("cover".to_string(), output_file.clone())]);
+        let old_thumbnail = generator
+            .prepare_thumbnails(&thumbnails_dir, &output_dir, &output_mapping)
+            .generated["cover.png"]
+            .clone();
+
+        output_file.checksum = [0x12; 20];
+        let output_mapping = HashMap::from([("cover".to_string(), output_file)]);
+        let new_thumbnail = generator
+            .prepare_thumbnails(&thumbnails_dir, &output_dir, &output_mapping)
+            .generated["cover.png"]
+            .clone();
+
+        assert_eq!(new_thumbnail, thumbnails_dir.join("cover.12121212.png"));
+        assert_ne!(new_thumbnail, old_thumbnail);
+        assert!(old_thumbnail.exists());
+        assert!(new_thumbnail.exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_thumbnails() {
+        let dir = temp_dir("remove");
+        for file_name in [
+            "cover.00000000.png",
+            "cover.abcdef12.webp",
+            "cover.png",
+            "cover.placeholder.png",
+            "manual.00000000.jpg",
+            "other.00000000.png",
+        ] {
+            write_image(&dir, file_name, 10, 10);
+        }
+
+        let removed =
+            remove_thumbnails(&dir, &["cover".to_string(), "manual".to_string()]).unwrap();
+
+        assert_eq!(removed, 5);
+        let mut remaining: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, vec!["other.00000000.png".to_string()]);
+        assert_eq!(remove_thumbnails(&dir.join("missing"), &[]).unwrap(), 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_exif_orientation_is_applied() {
+        let dir = temp_dir("exif");
+        let output_dir = Path::new("example-data");
+        // 40x20 image tagged with orientation 6 (rotate 90 degrees clockwise)
+        let output_mapping = HashMap::from([(
+            "rotated".to_string(),
+            OutputFile {
+                output_file_name: "rotated.jpg".to_string(),
+                checksum: [0; 20],
+            },
+        )]);
+
+        let rotated = ThumbnailGenerator::default()
+            .prepare_thumbnails(&dir.join("rotated"), output_dir, &output_mapping)
+            .generated;
+        let unrotated = ThumbnailGenerator::new(ThumbnailConfig {
+            apply_exif_orientation: false,
+            ..Default::default()
+        })
+        .prepare_thumbnails(&dir.join("unrotated"), output_dir, &output_mapping)
+        .generated;
+
+        assert_eq!(get_image_size(&rotated["rotated.jpg"]).unwrap(), (50, 100));
+        assert_eq!(
+            get_image_size(&unrotated["rotated.jpg"]).unwrap(),
+            (100, 50)
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_document_thumbnails() {
+        let dir = temp_dir("documents");
+        let output_dir = dir.join("output");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        write_image(&dir, "page01.png", 40, 20);
+        write_image(&dir, "page02.png", 20, 40);
+        let mut cbz =
+            zip::ZipWriter::new(std::fs::File::create(output_dir.join("comic.cbz")).unwrap());
+        for name in ["page02.png", "info.txt", "page01.png"] {
+            cbz.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            let content = std::fs::read(dir.join(name)).unwrap_or_default();
+            std::io::Write::write_all(&mut cbz, &content).unwrap();
+        }
+        cbz.finish().unwrap();
+        std::fs::write(output_dir.join("readme.txt"), "manual").unwrap();
+        let output_mapping = HashMap::from([
+            (
+                "comic".to_string(),
+                OutputFile {
+                    output_file_name: "comic.cbz".to_string(),
+                    checksum: [0; 20],
+                },
+            ),
+            (
+                "readme".to_string(),
+                OutputFile {
+                    output_file_name: "readme.txt".to_string(),
+                    checksum: [0; 20],
+                },
+            ),
+        ]);
+
+        let result = ThumbnailGenerator::default().prepare_thumbnails(
+            &dir.join("thumbnails"),
+            &output_dir,
+            &output_mapping,
+        );
+
+        assert!(result.failed.is_empty(), "{:?}", result.failed);
+        // First page by name is used as the thumbnail of a comic book archive
+        assert_eq!(
+            get_image_size(&result.generated["comic.cbz"]).unwrap(),
+            (100, 50)
+        );
+        assert_eq!(
+            get_image_size(&result.generated["readme.txt"]).unwrap(),
+            (100, 100)
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}